@@ -0,0 +1,167 @@
+# Bot interface strings. Every message here must also be in pt.ftl and it.ftl.
+
+## Command descriptions, registered with Telegram per language
+
+cmd-start = Start here and choose the translation direction
+cmd-help = Show what I can do
+cmd-flip = Toggle translation direction
+cmd-settings = Choose the translation direction and my language
+cmd-autodetect = Toggle automatic detection of the word's language
+cmd-variant = Prefer European or Brazilian Portuguese translations
+cmd-save = Save a word to your vocabulary
+cmd-export = Export saved words: /export anki or /export csv
+cmd-conj = Show the conjugation of a Portuguese or Italian verb
+cmd-batch = Look up a list of words, one per line, as a glossary
+cmd-t = Look up a word, or reply with /t to pick words from a message
+cmd-groupmode = Choose which group messages I answer: /groupmode mention, reply or all
+
+## Language names
+
+language-pt = Portuguese
+language-en = English
+language-it = Italian
+language-es = Spanish
+language-fr = French
+language-de = German
+
+## Lookups
+
+slow-down = 🐢 You're looking words up faster than I can fetch them. Please wait a minute and try again.
+no-translations = No translations found.
+lemma-note = <code>{ $form }</code> → form of <code>{ $lemma }</code> ({ $description })
+detected-direction = 🔎 { $direction }
+show-all = 📄 Show all
+tts-failed = 🔇 Couldn't generate the pronunciation.
+admins-only = ⛔ This command is only available to bot admins.
+
+## /start and /help
+
+start-welcome = 👋 Hi { $name }! Send me a word or a phrase and I'll look it up on WordReference.
+start-choose = First, choose the translation direction. You can change it any time with /settings or /flip.
+help-intro = Send me a word or a phrase to translate it. Commands:
+
+## /t
+
+pick-hint = Reply to a message with /t to pick words from it, or send /t <word>.
+pick-no-words = There are no words to translate in that message.
+pick-prompt = 👆 Pick a word to translate:
+
+## /settings and /flip
+
+settings-prompt = ⚙️ Choose the translation direction, or the language I speak here:
+direction-changed = ✅ Translation direction changed to: { $direction }
+direction-changed-for-user = ✅ Translation direction for { $name } changed to: { $direction }
+direction-changed-for-group = ✅ Translation direction for this group changed to: { $direction }
+locale-changed = 🌐 From now on I speak English here.
+locale-auto = 🌐 I speak each person's Telegram language.
+locale-auto-button = 🌐 Auto
+locale-admins-only = ⛔ Only group admins can change the language I speak here.
+flip-prompt = 🔄 Flip the translation direction for whom?
+flip-for-me = 🙋 For me
+flip-for-group = 👥 For this group
+flip-admins-only = ⛔ Only group admins can change the group's direction.
+
+## /autodetect
+
+autodetect-on = 🔎 Automatic language detection is on. Words are looked up in whichever direction matches their language.
+autodetect-off = 🔎 Automatic language detection is off.
+
+## /variant
+
+variant-pt = European Portuguese
+variant-br = Brazilian Portuguese
+variant-only = { $flag } Showing { $variant } only. Translations labelled for the other variant are hidden.
+variant-preferred = { $flag } Preferring { $variant }. Its translations are flagged and the other variant's are in italics.
+variant-any = 🌍 Showing European and Brazilian Portuguese translations alike.
+variant-both = Both
+variant-show-other = Show the other variant
+variant-hide-other = Hide the other variant
+variant-admins-only = ⛔ Only group admins can change the group's variant.
+
+## /save and /export
+
+save-usage = Usage: /save <word>
+word-saved = ⭐ Saved "{ $word }" to your vocabulary.
+export-usage = Usage: /export anki or /export csv
+export-empty = You have no saved words yet. Use /save <word> to add some.
+export-skipped = These words no longer have translations and were left out: { $words }
+
+## /batch and /conj
+
+batch-usage = Usage: /batch followed by one word or phrase per line
+batch-glossary = 📚 <b>Glossary</b> ({ $count } words)
+batch-not-found = 🤷 Not found: { $words }
+batch-failed = ⚠️ Failed: { $words }
+batch-rate-limited = 🐢 Not looked up yet, as you reached the lookup limit. Try these again in a minute: { $words }
+batch-truncated = ✂️ Only the first { $count } lines were looked up; { $dropped } more were left out.
+conj-usage = Usage: /conj <verb>
+conj-unsupported = Conjugation tables are available for Portuguese and Italian only.
+conj-not-found = No conjugation found.
+
+## /groupmode
+
+group-mode-mention = messages that mention me
+group-mode-reply = replies to my messages
+group-mode-all = every message
+group-mode-private = Group modes only apply in groups.
+group-mode-current = 👥 Here I answer { $mode }, plus commands. Admins can change it with /groupmode mention, reply or all.
+group-mode-usage = Usage: /groupmode mention, reply or all
+group-mode-admins-only = ⛔ Only group admins can change the group mode.
+group-mode-changed = ✅ From now on I answer { $mode }.
+
+## Inflections, shown in lemma notes
+
+inflection-1st-pl-present = 1st pl. present
+inflection-1st-pl-present-preterite = 1st pl. present / preterite
+inflection-1st-pl-preterite = 1st pl. preterite
+inflection-1st-sg-3rd-pl-present = 1st sg. / 3rd pl. present
+inflection-1st-sg-future = 1st sg. future
+inflection-1st-sg-imperfect = 1st sg. imperfect
+inflection-1st-sg-passato-remoto = 1st sg. passato remoto
+inflection-1st-sg-present = 1st sg. present
+inflection-1st-sg-preterite = 1st sg. preterite
+inflection-1st-2nd-3rd-sg-present-subjunctive = 1st/2nd/3rd sg. present subjunctive
+inflection-1st-3rd-sg-conditional = 1st/3rd sg. conditional
+inflection-1st-3rd-sg-imperfect = 1st/3rd sg. imperfect
+inflection-1st-3rd-sg-imperfect-subjunctive = 1st/3rd sg. imperfect subjunctive
+inflection-1st-3rd-sg-present-subjunctive = 1st/3rd sg. present subjunctive
+inflection-1st-3rd-sg-preterite = 1st/3rd sg. preterite
+inflection-2nd-pl-present = 2nd pl. present
+inflection-2nd-sg-present = 2nd sg. present
+inflection-2nd-sg-preterite = 2nd sg. preterite
+inflection-3rd-pl-imperfect = 3rd pl. imperfect
+inflection-3rd-pl-passato-remoto = 3rd pl. passato remoto
+inflection-3rd-pl-present = 3rd pl. present
+inflection-3rd-pl-preterite = 3rd pl. preterite
+inflection-3rd-sg-future = 3rd sg. future
+inflection-3rd-sg-imperfect = 3rd sg. imperfect
+inflection-3rd-sg-passato-remoto = 3rd sg. passato remoto
+inflection-3rd-sg-present = 3rd sg. present
+inflection-3rd-sg-preterite = 3rd sg. preterite
+inflection-gerund = gerund
+inflection-gerund-past-participle = gerund / past participle
+inflection-past-participle = past participle
+inflection-plural = plural
+
+## Admin commands
+
+cmd-stats = Show user, lookup and cache statistics
+cmd-purge = Delete a cached article: /purge <word> [direction]
+cmd-user = Show a chat's settings row: /user <chat_id>
+cmd-broadcast = Send a message to every chat: /broadcast <text>
+help-admin = Admin commands:
+admin-stats =
+    📊 <b>Stats</b>
+    Users: { $users }
+    Chats: { $chats }
+    Lookups today: { $lookups } ({ $hits } from cache, { $rate })
+    All lookups: { $total_lookups } ({ $total_hits } from cache, { $total_rate })
+    Cache: { $articles } articles, { $megabytes } MB
+admin-no-hit-rate = n/a
+admin-purge-usage = Usage: /purge <word> [direction]
+admin-purged = 🗑 Deleted { $count } cached article(s) for "{ $word }".
+admin-user-usage = Usage: /user <chat_id>
+admin-no-user = No row for chat { $chat }.
+admin-broadcast-usage = Usage: /broadcast <text>
+admin-broadcasting = 📣 Broadcasting to { $count } chats…
+admin-broadcast-finished = 📣 Broadcast finished: { $delivered } delivered, { $failed } failed.
@@ -0,0 +1,167 @@
+# Testi dell'interfaccia del bot, in italiano.
+
+## Descrizioni dei comandi
+
+cmd-start = Inizia da qui e scegli la direzione della traduzione
+cmd-help = Mostra cosa so fare
+cmd-flip = Inverti la direzione della traduzione
+cmd-settings = Scegli la direzione della traduzione e la mia lingua
+cmd-autodetect = Attiva o disattiva il rilevamento automatico della lingua della parola
+cmd-variant = Preferisci le traduzioni in portoghese europeo o brasiliano
+cmd-save = Salva una parola nel tuo vocabolario
+cmd-export = Esporta le parole salvate: /export anki o /export csv
+cmd-conj = Mostra la coniugazione di un verbo portoghese o italiano
+cmd-batch = Cerca un elenco di parole, una per riga, come glossario
+cmd-t = Cerca una parola, o rispondi con /t per scegliere le parole di un messaggio
+cmd-groupmode = Scegli a quali messaggi del gruppo rispondo: /groupmode mention, reply o all
+
+## Nomi delle lingue
+
+language-pt = Portoghese
+language-en = Inglese
+language-it = Italiano
+language-es = Spagnolo
+language-fr = Francese
+language-de = Tedesco
+
+## Ricerche
+
+slow-down = 🐢 Stai cercando parole più in fretta di quanto riesca a recuperarle. Aspetta un minuto e riprova.
+no-translations = Nessuna traduzione trovata.
+lemma-note = <code>{ $form }</code> → forma di <code>{ $lemma }</code> ({ $description })
+detected-direction = 🔎 { $direction }
+show-all = 📄 Mostra tutto
+tts-failed = 🔇 Non sono riuscito a generare la pronuncia.
+admins-only = ⛔ Questo comando è disponibile solo per gli amministratori del bot.
+
+## /start e /help
+
+start-welcome = 👋 Ciao { $name }! Mandami una parola o un'espressione e la cerco su WordReference.
+start-choose = Per prima cosa, scegli la direzione della traduzione. Puoi cambiarla quando vuoi con /settings o /flip.
+help-intro = Mandami una parola o un'espressione per tradurla. Comandi:
+
+## /t
+
+pick-hint = Rispondi a un messaggio con /t per sceglierne le parole, o invia /t <parola>.
+pick-no-words = Non ci sono parole da tradurre in quel messaggio.
+pick-prompt = 👆 Scegli una parola da tradurre:
+
+## /settings e /flip
+
+settings-prompt = ⚙️ Scegli la direzione della traduzione, o la lingua in cui parlo qui:
+direction-changed = ✅ Direzione della traduzione cambiata in: { $direction }
+direction-changed-for-user = ✅ Direzione della traduzione di { $name } cambiata in: { $direction }
+direction-changed-for-group = ✅ Direzione della traduzione di questo gruppo cambiata in: { $direction }
+locale-changed = 🌐 D'ora in poi qui parlo italiano.
+locale-auto = 🌐 Parlo la lingua di Telegram di ciascuno.
+locale-auto-button = 🌐 Automatica
+locale-admins-only = ⛔ Solo gli amministratori del gruppo possono cambiare la lingua in cui parlo qui.
+flip-prompt = 🔄 Invertire la direzione della traduzione per chi?
+flip-for-me = 🙋 Per me
+flip-for-group = 👥 Per questo gruppo
+flip-admins-only = ⛔ Solo gli amministratori del gruppo possono cambiare la direzione del gruppo.
+
+## /autodetect
+
+autodetect-on = 🔎 Il rilevamento automatico della lingua è attivo. Ogni parola viene cercata nella direzione che corrisponde alla sua lingua.
+autodetect-off = 🔎 Il rilevamento automatico della lingua è disattivato.
+
+## /variant
+
+variant-pt = portoghese europeo
+variant-br = portoghese brasiliano
+variant-only = { $flag } Mostro solo il { $variant }. Le traduzioni contrassegnate per l'altra variante sono nascoste.
+variant-preferred = { $flag } Preferisco il { $variant }. Le sue traduzioni hanno la bandiera e quelle dell'altra variante sono in corsivo.
+variant-any = 🌍 Mostro allo stesso modo le traduzioni in portoghese europeo e brasiliano.
+variant-both = Entrambe
+variant-show-other = Mostra l'altra variante
+variant-hide-other = Nascondi l'altra variante
+variant-admins-only = ⛔ Solo gli amministratori del gruppo possono cambiare la variante del gruppo.
+
+## /save e /export
+
+save-usage = Uso: /save <parola>
+word-saved = ⭐ "{ $word }" salvata nel tuo vocabolario.
+export-usage = Uso: /export anki o /export csv
+export-empty = Non hai ancora parole salvate. Usa /save <parola> per aggiungerne.
+export-skipped = Queste parole non hanno più traduzioni e sono state escluse: { $words }
+
+## /batch e /conj
+
+batch-usage = Uso: /batch seguito da una parola o espressione per riga
+batch-glossary = 📚 <b>Glossario</b> ({ $count } parole)
+batch-not-found = 🤷 Non trovate: { $words }
+batch-failed = ⚠️ Non riuscite: { $words }
+batch-rate-limited = 🐢 Non ancora cercate, perché hai raggiunto il limite di ricerche. Riprova tra un minuto: { $words }
+batch-truncated = ✂️ Ho cercato solo le prime { $count } righe; altre { $dropped } sono state escluse.
+conj-usage = Uso: /conj <verbo>
+conj-unsupported = Le tabelle di coniugazione sono disponibili solo per il portoghese e l'italiano.
+conj-not-found = Nessuna coniugazione trovata.
+
+## /groupmode
+
+group-mode-mention = ai messaggi che mi menzionano
+group-mode-reply = alle risposte ai miei messaggi
+group-mode-all = a tutti i messaggi
+group-mode-private = Le modalità di gruppo valgono solo nei gruppi.
+group-mode-current = 👥 Qui rispondo { $mode }, oltre ai comandi. Gli amministratori possono cambiarlo con /groupmode mention, reply o all.
+group-mode-usage = Uso: /groupmode mention, reply o all
+group-mode-admins-only = ⛔ Solo gli amministratori del gruppo possono cambiare la modalità del gruppo.
+group-mode-changed = ✅ D'ora in poi rispondo { $mode }.
+
+## Flessioni
+
+inflection-1st-pl-present = 1ª plur. presente
+inflection-1st-pl-present-preterite = 1ª plur. presente / passato remoto
+inflection-1st-pl-preterite = 1ª plur. passato remoto
+inflection-1st-sg-3rd-pl-present = 1ª sing. / 3ª plur. presente
+inflection-1st-sg-future = 1ª sing. futuro
+inflection-1st-sg-imperfect = 1ª sing. imperfetto
+inflection-1st-sg-passato-remoto = 1ª sing. passato remoto
+inflection-1st-sg-present = 1ª sing. presente
+inflection-1st-sg-preterite = 1ª sing. passato remoto
+inflection-1st-2nd-3rd-sg-present-subjunctive = 1ª/2ª/3ª sing. congiuntivo presente
+inflection-1st-3rd-sg-conditional = 1ª/3ª sing. condizionale
+inflection-1st-3rd-sg-imperfect = 1ª/3ª sing. imperfetto
+inflection-1st-3rd-sg-imperfect-subjunctive = 1ª/3ª sing. congiuntivo imperfetto
+inflection-1st-3rd-sg-present-subjunctive = 1ª/3ª sing. congiuntivo presente
+inflection-1st-3rd-sg-preterite = 1ª/3ª sing. passato remoto
+inflection-2nd-pl-present = 2ª plur. presente
+inflection-2nd-sg-present = 2ª sing. presente
+inflection-2nd-sg-preterite = 2ª sing. passato remoto
+inflection-3rd-pl-imperfect = 3ª plur. imperfetto
+inflection-3rd-pl-passato-remoto = 3ª plur. passato remoto
+inflection-3rd-pl-present = 3ª plur. presente
+inflection-3rd-pl-preterite = 3ª plur. passato remoto
+inflection-3rd-sg-future = 3ª sing. futuro
+inflection-3rd-sg-imperfect = 3ª sing. imperfetto
+inflection-3rd-sg-passato-remoto = 3ª sing. passato remoto
+inflection-3rd-sg-present = 3ª sing. presente
+inflection-3rd-sg-preterite = 3ª sing. passato remoto
+inflection-gerund = gerundio
+inflection-gerund-past-participle = gerundio / participio passato
+inflection-past-participle = participio passato
+inflection-plural = plurale
+
+## Comandi di amministrazione
+
+cmd-stats = Mostra le statistiche di utenti, ricerche e cache
+cmd-purge = Elimina un articolo dalla cache: /purge <parola> [direzione]
+cmd-user = Mostra la riga delle impostazioni di una chat: /user <chat_id>
+cmd-broadcast = Invia un messaggio a tutte le chat: /broadcast <testo>
+help-admin = Comandi di amministrazione:
+admin-stats =
+    📊 <b>Statistiche</b>
+    Utenti: { $users }
+    Chat: { $chats }
+    Ricerche oggi: { $lookups } ({ $hits } dalla cache, { $rate })
+    Tutte le ricerche: { $total_lookups } ({ $total_hits } dalla cache, { $total_rate })
+    Cache: { $articles } articoli, { $megabytes } MB
+admin-no-hit-rate = n/d
+admin-purge-usage = Uso: /purge <parola> [direzione]
+admin-purged = 🗑 Eliminati { $count } articoli dalla cache per "{ $word }".
+admin-user-usage = Uso: /user <chat_id>
+admin-no-user = Nessuna riga per la chat { $chat }.
+admin-broadcast-usage = Uso: /broadcast <testo>
+admin-broadcasting = 📣 Invio a { $count } chat…
+admin-broadcast-finished = 📣 Invio terminato: { $delivered } consegnati, { $failed } non riusciti.
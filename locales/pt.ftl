@@ -0,0 +1,167 @@
+# Textos da interface do bot, em português europeu.
+
+## Descrições dos comandos
+
+cmd-start = Começar aqui e escolher a direção da tradução
+cmd-help = Mostrar o que sei fazer
+cmd-flip = Inverter a direção da tradução
+cmd-settings = Escolher a direção da tradução e a minha língua
+cmd-autodetect = Ativar ou desativar a deteção automática da língua da palavra
+cmd-variant = Preferir traduções em português europeu ou do Brasil
+cmd-save = Guardar uma palavra no teu vocabulário
+cmd-export = Exportar as palavras guardadas: /export anki ou /export csv
+cmd-conj = Mostrar a conjugação de um verbo português ou italiano
+cmd-batch = Procurar uma lista de palavras, uma por linha, como glossário
+cmd-t = Procurar uma palavra, ou responder com /t para escolher palavras de uma mensagem
+cmd-groupmode = Escolher a que mensagens do grupo respondo: /groupmode mention, reply ou all
+
+## Nomes das línguas
+
+language-pt = Português
+language-en = Inglês
+language-it = Italiano
+language-es = Espanhol
+language-fr = Francês
+language-de = Alemão
+
+## Pesquisas
+
+slow-down = 🐢 Estás a procurar palavras mais depressa do que as consigo ir buscar. Espera um minuto e tenta outra vez.
+no-translations = Nenhuma tradução encontrada.
+lemma-note = <code>{ $form }</code> → forma de <code>{ $lemma }</code> ({ $description })
+detected-direction = 🔎 { $direction }
+show-all = 📄 Mostrar tudo
+tts-failed = 🔇 Não consegui gerar a pronúncia.
+admins-only = ⛔ Este comando só está disponível para os administradores do bot.
+
+## /start e /help
+
+start-welcome = 👋 Olá, { $name }! Envia-me uma palavra ou expressão e eu procuro-a no WordReference.
+start-choose = Primeiro, escolhe a direção da tradução. Podes mudá-la quando quiseres com /settings ou /flip.
+help-intro = Envia-me uma palavra ou expressão para a traduzir. Comandos:
+
+## /t
+
+pick-hint = Responde a uma mensagem com /t para escolher palavras dela, ou envia /t <palavra>.
+pick-no-words = Não há palavras para traduzir nessa mensagem.
+pick-prompt = 👆 Escolhe uma palavra para traduzir:
+
+## /settings e /flip
+
+settings-prompt = ⚙️ Escolhe a direção da tradução, ou a língua em que falo aqui:
+direction-changed = ✅ Direção da tradução alterada para: { $direction }
+direction-changed-for-user = ✅ Direção da tradução de { $name } alterada para: { $direction }
+direction-changed-for-group = ✅ Direção da tradução deste grupo alterada para: { $direction }
+locale-changed = 🌐 A partir de agora falo português aqui.
+locale-auto = 🌐 Falo a língua do Telegram de cada pessoa.
+locale-auto-button = 🌐 Automático
+locale-admins-only = ⛔ Só os administradores do grupo podem alterar a língua em que falo aqui.
+flip-prompt = 🔄 Inverter a direção da tradução para quem?
+flip-for-me = 🙋 Para mim
+flip-for-group = 👥 Para este grupo
+flip-admins-only = ⛔ Só os administradores do grupo podem alterar a direção do grupo.
+
+## /autodetect
+
+autodetect-on = 🔎 A deteção automática da língua está ativada. Cada palavra é procurada na direção que corresponde à sua língua.
+autodetect-off = 🔎 A deteção automática da língua está desativada.
+
+## /variant
+
+variant-pt = português europeu
+variant-br = português do Brasil
+variant-only = { $flag } Só mostro { $variant }. As traduções marcadas para a outra variante ficam escondidas.
+variant-preferred = { $flag } Prefiro { $variant }. As suas traduções levam a bandeira e as da outra variante aparecem em itálico.
+variant-any = 🌍 Mostro as traduções do português europeu e do Brasil por igual.
+variant-both = Ambas
+variant-show-other = Mostrar a outra variante
+variant-hide-other = Esconder a outra variante
+variant-admins-only = ⛔ Só os administradores do grupo podem alterar a variante do grupo.
+
+## /save e /export
+
+save-usage = Uso: /save <palavra>
+word-saved = ⭐ "{ $word }" guardada no teu vocabulário.
+export-usage = Uso: /export anki ou /export csv
+export-empty = Ainda não tens palavras guardadas. Usa /save <palavra> para adicionar algumas.
+export-skipped = Estas palavras já não têm traduções e ficaram de fora: { $words }
+
+## /batch e /conj
+
+batch-usage = Uso: /batch seguido de uma palavra ou expressão por linha
+batch-glossary = 📚 <b>Glossário</b> ({ $count } palavras)
+batch-not-found = 🤷 Não encontradas: { $words }
+batch-failed = ⚠️ Falharam: { $words }
+batch-rate-limited = 🐢 Ainda não procurei estas, porque chegaste ao limite de pesquisas. Tenta outra vez daqui a um minuto: { $words }
+batch-truncated = ✂️ Só procurei as primeiras { $count } linhas; ficaram de fora mais { $dropped }.
+conj-usage = Uso: /conj <verbo>
+conj-unsupported = As tabelas de conjugação só estão disponíveis para português e italiano.
+conj-not-found = Nenhuma conjugação encontrada.
+
+## /groupmode
+
+group-mode-mention = às mensagens que me mencionam
+group-mode-reply = às respostas às minhas mensagens
+group-mode-all = a todas as mensagens
+group-mode-private = Os modos de grupo só se aplicam em grupos.
+group-mode-current = 👥 Aqui respondo { $mode }, além dos comandos. Os administradores podem mudar isto com /groupmode mention, reply ou all.
+group-mode-usage = Uso: /groupmode mention, reply ou all
+group-mode-admins-only = ⛔ Só os administradores do grupo podem alterar o modo do grupo.
+group-mode-changed = ✅ A partir de agora respondo { $mode }.
+
+## Flexões
+
+inflection-1st-pl-present = 1.ª pl. presente
+inflection-1st-pl-present-preterite = 1.ª pl. presente / pretérito perfeito
+inflection-1st-pl-preterite = 1.ª pl. pretérito perfeito
+inflection-1st-sg-3rd-pl-present = 1.ª sg. / 3.ª pl. presente
+inflection-1st-sg-future = 1.ª sg. futuro
+inflection-1st-sg-imperfect = 1.ª sg. pretérito imperfeito
+inflection-1st-sg-passato-remoto = 1.ª sg. passato remoto
+inflection-1st-sg-present = 1.ª sg. presente
+inflection-1st-sg-preterite = 1.ª sg. pretérito perfeito
+inflection-1st-2nd-3rd-sg-present-subjunctive = 1.ª/2.ª/3.ª sg. presente do conjuntivo
+inflection-1st-3rd-sg-conditional = 1.ª/3.ª sg. condicional
+inflection-1st-3rd-sg-imperfect = 1.ª/3.ª sg. pretérito imperfeito
+inflection-1st-3rd-sg-imperfect-subjunctive = 1.ª/3.ª sg. pretérito imperfeito do conjuntivo
+inflection-1st-3rd-sg-present-subjunctive = 1.ª/3.ª sg. presente do conjuntivo
+inflection-1st-3rd-sg-preterite = 1.ª/3.ª sg. pretérito perfeito
+inflection-2nd-pl-present = 2.ª pl. presente
+inflection-2nd-sg-present = 2.ª sg. presente
+inflection-2nd-sg-preterite = 2.ª sg. pretérito perfeito
+inflection-3rd-pl-imperfect = 3.ª pl. pretérito imperfeito
+inflection-3rd-pl-passato-remoto = 3.ª pl. passato remoto
+inflection-3rd-pl-present = 3.ª pl. presente
+inflection-3rd-pl-preterite = 3.ª pl. pretérito perfeito
+inflection-3rd-sg-future = 3.ª sg. futuro
+inflection-3rd-sg-imperfect = 3.ª sg. pretérito imperfeito
+inflection-3rd-sg-passato-remoto = 3.ª sg. passato remoto
+inflection-3rd-sg-present = 3.ª sg. presente
+inflection-3rd-sg-preterite = 3.ª sg. pretérito perfeito
+inflection-gerund = gerúndio
+inflection-gerund-past-participle = gerúndio / particípio passado
+inflection-past-participle = particípio passado
+inflection-plural = plural
+
+## Comandos de administração
+
+cmd-stats = Mostrar estatísticas de utilizadores, pesquisas e cache
+cmd-purge = Apagar um artigo da cache: /purge <palavra> [direção]
+cmd-user = Mostrar a linha de definições de um chat: /user <chat_id>
+cmd-broadcast = Enviar uma mensagem a todos os chats: /broadcast <texto>
+help-admin = Comandos de administração:
+admin-stats =
+    📊 <b>Estatísticas</b>
+    Utilizadores: { $users }
+    Chats: { $chats }
+    Pesquisas hoje: { $lookups } ({ $hits } da cache, { $rate })
+    Todas as pesquisas: { $total_lookups } ({ $total_hits } da cache, { $total_rate })
+    Cache: { $articles } artigos, { $megabytes } MB
+admin-no-hit-rate = n/d
+admin-purge-usage = Uso: /purge <palavra> [direção]
+admin-purged = 🗑 Apagado(s) { $count } artigo(s) da cache para "{ $word }".
+admin-user-usage = Uso: /user <chat_id>
+admin-no-user = Não há nenhuma linha para o chat { $chat }.
+admin-broadcast-usage = Uso: /broadcast <texto>
+admin-broadcasting = 📣 A enviar para { $count } chats…
+admin-broadcast-finished = 📣 Envio terminado: { $delivered } entregues, { $failed } falhados.
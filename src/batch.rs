@@ -0,0 +1,128 @@
+use crate::export::csv_field;
+use crate::fetch_translations::TranslationEntry;
+use crate::i18n::{self, Locale};
+use crate::query::MAX_BATCH_LINES;
+use crate::render::escape_html;
+
+/// Most lookups of a batch running at the same time, to stay polite to WordReference.
+pub const MAX_CONCURRENT_LOOKUPS: usize = 4;
+/// Translations listed per word in the glossary and the attached file.
+pub const TOP_TRANSLATIONS: usize = 3;
+
+/// Result of looking up one word of a batch.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BatchOutcome {
+    /// Top translations, with the lemma they were found under when the word
+    /// itself had none.
+    Found {
+        translations: Vec<String>,
+        lemma: Option<String>,
+    },
+    NotFound,
+    /// The lookup failed, e.g. WordReference could not be reached.
+    Failed(String),
+    /// Not looked up: the batch needed more requests to WordReference than
+    /// the rate limits had left.
+    RateLimited,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BatchItem {
+    pub word: String,
+    pub lang_direction: String,
+    pub outcome: BatchOutcome,
+}
+
+/// The first `limit` distinct translations of a word, in dictionary order.
+pub fn top_translations(entries: &[TranslationEntry], limit: usize) -> Vec<String> {
+    let mut translations: Vec<String> = Vec::new();
+    for entry in entries {
+        if translations.len() == limit {
+            break;
+        }
+        if !entry.translation.is_empty() && !translations.contains(&entry.translation) {
+            translations.push(entry.translation.clone());
+        }
+    }
+    translations
+}
+
+/// Formats a batch as a compact HTML glossary, one line per word found,
+/// followed by the words that were not found, failed or were left for
+/// later by the rate limits, and a note when
+/// `dropped` lines past [`MAX_BATCH_LINES`] were left out.
+pub fn format_glossary(items: &[BatchItem], dropped: usize, locale: Locale) -> String {
+    let count = items.len().to_string();
+    let mut glossary = i18n::t_args(locale, "batch-glossary", &[("count", &count)]) + "\n\n";
+    let mut not_found: Vec<&str> = Vec::new();
+    let mut failed: Vec<String> = Vec::new();
+    let mut rate_limited: Vec<&str> = Vec::new();
+
+    for item in items {
+        match &item.outcome {
+            BatchOutcome::Found {
+                translations,
+                lemma,
+            } => {
+                glossary.push_str(&format!("<b>{}</b>", escape_html(&item.word)));
+                if let Some(lemma) = lemma {
+                    glossary.push_str(&format!(" (→ {})", escape_html(lemma)));
+                }
+                glossary.push_str(&format!(" — {}\n", escape_html(&translations.join("; "))));
+            }
+            BatchOutcome::NotFound => not_found.push(&item.word),
+            BatchOutcome::Failed(error) => failed.push(format!(
+                "{} ({})",
+                escape_html(&item.word),
+                escape_html(error)
+            )),
+            BatchOutcome::RateLimited => rate_limited.push(&item.word),
+        }
+    }
+
+    if !not_found.is_empty() {
+        let words = escape_html(&not_found.join(", "));
+        let line = i18n::t_args(locale, "batch-not-found", &[("words", &words)]);
+        glossary.push_str(&format!("\n{}\n", line));
+    }
+    if !failed.is_empty() {
+        let words = failed.join(", ");
+        let line = i18n::t_args(locale, "batch-failed", &[("words", &words)]);
+        glossary.push_str(&format!("\n{}\n", line));
+    }
+    if !rate_limited.is_empty() {
+        let words = escape_html(&rate_limited.join(", "));
+        let line = i18n::t_args(locale, "batch-rate-limited", &[("words", &words)]);
+        glossary.push_str(&format!("\n{}\n", line));
+    }
+    if dropped > 0 {
+        let line = i18n::t_args(
+            locale,
+            "batch-truncated",
+            &[
+                ("count", &MAX_BATCH_LINES.to_string()),
+                ("dropped", &dropped.to_string()),
+            ],
+        );
+        glossary.push_str(&format!("\n{}\n", line));
+    }
+
+    glossary
+}
+
+/// Renders the words found in a batch as CSV with a
+/// `word,lang_direction,translations` header; translations are joined with `; `.
+pub fn build_glossary_csv(items: &[BatchItem]) -> String {
+    let mut csv = String::from("word,lang_direction,translations\n");
+    for item in items {
+        if let BatchOutcome::Found { translations, .. } = &item.outcome {
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                csv_field(&item.word),
+                csv_field(&item.lang_direction),
+                csv_field(&translations.join("; "))
+            ));
+        }
+    }
+    csv
+}
@@ -0,0 +1,508 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand};
+use dotenv::dotenv;
+use pt_dict_bot::cache_repository::{ArticleFilter, ArticleRecord, CacheRepository};
+use pt_dict_bot::constants::{DATABASE_PATH, DATABASE_URL};
+use pt_dict_bot::dictionary_export::{self, DictionaryEntry};
+use pt_dict_bot::fetch_translations;
+use pt_dict_bot::i18n::{self, Locale};
+use pt_dict_bot::languages;
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::saved_words_repository::SavedWordsRepository;
+use pt_dict_bot::user_repository::UserRepository;
+use pt_dict_bot::warmup::{self, WarmupStatus, WarmupSummary};
+use pt_dict_bot::wiktionary;
+use pt_dict_bot::wiktionary_repository::WiktionaryRepository;
+use sea_orm::{ConnectionTrait, DatabaseConnection};
+use sea_orm_migration::{MigrationStatus, MigratorTrait};
+
+/// Articles read per query when walking the whole cache.
+const PAGE_SIZE: u64 = 500;
+
+/// Maintenance tool for the bot's database. Needs no Telegram token.
+#[derive(Parser)]
+#[command(name = "pt_dict_admin", version)]
+struct Cli {
+    /// Database to operate on
+    #[arg(long, default_value = DATABASE_URL)]
+    database: String,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply, roll back or list schema migrations
+    Migrate {
+        #[command(subcommand)]
+        action: MigrateAction,
+    },
+    /// Inspect and maintain the article cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Export parsed entries for use outside the bot
+    Dict {
+        #[command(subcommand)]
+        action: DictAction,
+    },
+    /// Import and inspect Wiktionary entries
+    Wiktionary {
+        #[command(subcommand)]
+        action: WiktionaryAction,
+    },
+    /// Inspect and delete chat settings
+    Users {
+        #[command(subcommand)]
+        action: UsersAction,
+    },
+    /// Reclaim free space in the SQLite file
+    Vacuum,
+}
+
+#[derive(Subcommand)]
+enum MigrateAction {
+    /// Apply all pending migrations
+    Up,
+    /// Roll back the last applied migration
+    Down,
+    /// List migrations and whether they are applied
+    Status,
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Show the number and size of cached articles
+    Stats,
+    /// Delete articles not updated in the given number of days
+    Purge {
+        #[arg(long, value_name = "DAYS")]
+        older_than: i64,
+    },
+    /// Re-format every article from its stored HTML
+    Rerender,
+    /// Write every article to a JSONL file
+    Export { path: String },
+    /// Load articles from a JSONL file written by `cache export`
+    Import { path: String },
+    /// Fetch and cache every word of a frequency list, most frequent first.
+    /// Progress is checkpointed next to the list, so an interrupted run resumes.
+    Warm {
+        /// Word list, one word per line, optionally with a rank or count
+        list: String,
+        /// Translation direction, e.g. pten
+        #[arg(long)]
+        direction: String,
+        /// Pause after each request to WordReference, in milliseconds
+        #[arg(long, default_value_t = 1000)]
+        delay_ms: u64,
+        /// Start from the top of the list, ignoring the checkpoint
+        #[arg(long)]
+        restart: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DictAction {
+    /// Write every parsed entry to a JSONL file
+    Jsonl {
+        path: String,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+    /// Write one StarDict dictionary per direction into a directory,
+    /// for GoldenDict, KOReader and other offline readers
+    Stardict {
+        dir: String,
+        #[command(flatten)]
+        filter: FilterArgs,
+    },
+}
+
+#[derive(Args)]
+struct FilterArgs {
+    /// Only this direction, e.g. pten
+    #[arg(long)]
+    direction: Option<String>,
+    /// Only articles updated on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    since: Option<NaiveDate>,
+    /// Only articles updated on or before this date (YYYY-MM-DD)
+    #[arg(long)]
+    until: Option<NaiveDate>,
+}
+
+impl FilterArgs {
+    fn to_filter(&self) -> Result<ArticleFilter, Box<dyn Error + Send + Sync>> {
+        if let Some(direction) = &self.direction
+            && languages::direction(direction).is_none()
+        {
+            return Err(format!("Unknown direction: {}", direction).into());
+        }
+        Ok(ArticleFilter {
+            lang_direction: self.direction.clone(),
+            updated_since: self.since.map(|d| d.and_hms_opt(0, 0, 0).unwrap()),
+            updated_before: self
+                .until
+                .map(|d| (d + Duration::days(1)).and_hms_opt(0, 0, 0).unwrap()),
+        })
+    }
+}
+
+#[derive(Subcommand)]
+enum WiktionaryAction {
+    /// Import a Kaikki (wiktextract) JSONL dump, plain or gzipped. Only new and
+    /// changed entries are written; a dump imported before is skipped.
+    Import {
+        path: String,
+        /// Language codes to keep, comma-separated (default: every language
+        /// the bot translates into English from)
+        #[arg(long, value_delimiter = ',')]
+        lang: Vec<String>,
+        /// Import even if this dump was imported before
+        #[arg(long)]
+        force: bool,
+    },
+    /// Show the number of imported entries
+    Stats,
+}
+
+#[derive(Subcommand)]
+enum UsersAction {
+    /// List every chat with stored settings
+    List,
+    /// Delete a chat's settings and saved words
+    Delete { chat_id: String },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
+    dotenv().ok();
+    pretty_env_logger::init();
+    let cli = Cli::parse();
+
+    if cli.database == DATABASE_URL
+        && let Some(parent) = std::path::Path::new(DATABASE_PATH).parent()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let db = sea_orm::Database::connect(&cli.database).await?;
+
+    match cli.command {
+        Command::Migrate { action } => migrate(&db, action).await?,
+        Command::Cache { action } => cache(&db, action).await?,
+        Command::Dict { action } => dict(&db, action).await?,
+        Command::Wiktionary { action } => wiktionary(&db, action).await?,
+        Command::Users { action } => users(&db, action).await?,
+        Command::Vacuum => {
+            db.execute_unprepared("VACUUM").await?;
+            println!("Vacuumed {}", cli.database);
+        }
+    }
+
+    Ok(())
+}
+
+async fn migrate(
+    db: &DatabaseConnection,
+    action: MigrateAction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    match action {
+        MigrateAction::Up => {
+            let pending = Migrator::get_pending_migrations(db).await?.len();
+            Migrator::up(db, None).await?;
+            println!("Applied {} migration(s)", pending);
+        }
+        MigrateAction::Down => {
+            Migrator::down(db, Some(1)).await?;
+            println!("Rolled back the last migration");
+        }
+        MigrateAction::Status => {
+            for migration in Migrator::get_migration_with_status(db).await? {
+                let status = match migration.status() {
+                    MigrationStatus::Applied => "applied",
+                    MigrationStatus::Pending => "pending",
+                };
+                println!("{:<8} {}", status, migration.name());
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn cache(
+    db: &DatabaseConnection,
+    action: CacheAction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let cache_repo = CacheRepository::new(db.clone());
+
+    match action {
+        CacheAction::Stats => {
+            let stats = cache_repo.get_stats().await?;
+            println!("Articles: {}", stats.articles);
+            println!("Size:     {:.1} MB", stats.bytes as f64 / 1_000_000.0);
+        }
+        CacheAction::Purge { older_than } => {
+            let cutoff = Utc::now().naive_utc() - Duration::days(older_than);
+            let deleted = cache_repo.purge_older_than(cutoff).await?;
+            println!(
+                "Deleted {} article(s) last updated before {}",
+                deleted, cutoff
+            );
+        }
+        CacheAction::Rerender => {
+            let mut after_id = 0;
+            let mut count = 0;
+            loop {
+                let page = cache_repo.list_articles(after_id, PAGE_SIZE).await?;
+                let Some(last) = page.last() else {
+                    break;
+                };
+                after_id = last.id;
+
+                for article in &page {
+                    if article.html.is_empty() {
+                        continue;
+                    }
+                    let formatted = fetch_translations::get_translations(&article.html);
+                    cache_repo
+                        .update_formatted(&article.word, &article.lang_direction, &formatted)
+                        .await?;
+                    count += 1;
+                }
+            }
+            println!("Re-rendered {} article(s)", count);
+        }
+        CacheAction::Export { path } => {
+            let mut out = BufWriter::new(File::create(&path)?);
+            let mut after_id = 0;
+            let mut count = 0;
+            loop {
+                let page = cache_repo.list_articles(after_id, PAGE_SIZE).await?;
+                let Some(last) = page.last() else {
+                    break;
+                };
+                after_id = last.id;
+
+                for article in page {
+                    serde_json::to_writer(&mut out, &ArticleRecord::from(article))?;
+                    out.write_all(b"\n")?;
+                    count += 1;
+                }
+            }
+            out.flush()?;
+            println!("Exported {} article(s) to {}", count, path);
+        }
+        CacheAction::Import { path } => {
+            let mut count = 0;
+            for (i, line) in BufReader::new(File::open(&path)?).lines().enumerate() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let record: ArticleRecord = serde_json::from_str(&line)
+                    .map_err(|e| format!("{}:{}: {}", path, i + 1, e))?;
+                cache_repo.import_article(&record).await?;
+                count += 1;
+            }
+            println!("Imported {} article(s) from {}", count, path);
+        }
+        CacheAction::Warm {
+            list,
+            direction,
+            delay_ms,
+            restart,
+        } => {
+            if languages::direction(&direction).is_none() {
+                return Err(format!("Unknown direction: {}", direction).into());
+            }
+            let words = warmup::parse_word_list(&std::fs::read_to_string(&list)?);
+            let checkpoint = PathBuf::from(format!("{}.{}.progress", list, direction));
+            let start = if restart {
+                0
+            } else {
+                warmup::read_checkpoint(&checkpoint).min(words.len())
+            };
+            if start > 0 {
+                println!("Resuming after {} of {} words", start, words.len());
+            }
+
+            let mut summary = WarmupSummary::default();
+            for (i, word) in words.iter().enumerate().skip(start) {
+                let status = warmup::warm_word(&cache_repo, word, &direction).await;
+                println!("[{}/{}] {}: {}", i + 1, words.len(), word, status);
+                summary.record(&status);
+                warmup::write_checkpoint(&checkpoint, i + 1)?;
+
+                if status != WarmupStatus::Cached {
+                    tokio::time::sleep(std::time::Duration::from_millis(delay_ms)).await;
+                }
+            }
+
+            println!(
+                "Done: {} fetched, {} already cached, {} not found, {} failed",
+                summary.fetched, summary.cached, summary.not_found, summary.failed
+            );
+            if summary.failed > 0 {
+                println!("Run again with --restart to retry failed words; cached ones are skipped");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every parsed entry matching `filter`, in cache order.
+async fn dictionary_entries(
+    cache_repo: &CacheRepository,
+    filter: &ArticleFilter,
+) -> Result<Vec<DictionaryEntry>, Box<dyn Error + Send + Sync>> {
+    let mut entries = Vec::new();
+    let mut after_id = 0;
+    loop {
+        let page = cache_repo
+            .list_articles_matching(after_id, PAGE_SIZE, filter)
+            .await?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        after_id = last.id;
+        entries.extend(page.iter().filter_map(DictionaryEntry::from_article));
+    }
+    Ok(entries)
+}
+
+async fn dict(
+    db: &DatabaseConnection,
+    action: DictAction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let cache_repo = CacheRepository::new(db.clone());
+
+    match action {
+        DictAction::Jsonl { path, filter } => {
+            let entries = dictionary_entries(&cache_repo, &filter.to_filter()?).await?;
+            let mut out = BufWriter::new(File::create(&path)?);
+            for entry in &entries {
+                serde_json::to_writer(&mut out, entry)?;
+                out.write_all(b"\n")?;
+            }
+            out.flush()?;
+            println!("Exported {} entries to {}", entries.len(), path);
+        }
+        DictAction::Stardict { dir, filter } => {
+            let entries = dictionary_entries(&cache_repo, &filter.to_filter()?).await?;
+            let mut by_direction: BTreeMap<String, Vec<DictionaryEntry>> = BTreeMap::new();
+            for entry in entries {
+                by_direction
+                    .entry(entry.lang_direction.clone())
+                    .or_default()
+                    .push(entry);
+            }
+
+            std::fs::create_dir_all(&dir)?;
+            for (direction, entries) in &by_direction {
+                let name = languages::direction(direction)
+                    .map(|d| i18n::direction_name(Locale::English, d))
+                    .unwrap_or_else(|| direction.clone());
+                let stardict = dictionary_export::build_stardict(
+                    &format!("pt_dict_bot {}", name),
+                    &format!(
+                        "WordReference translations cached by pt_dict_bot ({})",
+                        direction
+                    ),
+                    entries,
+                );
+
+                let base = Path::new(&dir).join(format!("pt_dict_bot-{}", direction));
+                std::fs::write(base.with_extension("ifo"), &stardict.ifo)?;
+                std::fs::write(base.with_extension("idx"), &stardict.idx)?;
+                std::fs::write(base.with_extension("dict"), &stardict.dict)?;
+                println!("Wrote {} entries to {}.ifo", entries.len(), base.display());
+            }
+            if by_direction.is_empty() {
+                println!("No entries matched");
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn wiktionary(
+    db: &DatabaseConnection,
+    action: WiktionaryAction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let repo = WiktionaryRepository::new(db.clone());
+
+    match action {
+        WiktionaryAction::Import { path, lang, force } => {
+            let lang_codes = if lang.is_empty() {
+                wiktionary::supported_lang_codes()
+            } else {
+                lang
+            };
+            match wiktionary::import_file(&repo, Path::new(&path), &lang_codes, force).await? {
+                Some(summary) => println!(
+                    "Imported {}: {} added, {} updated, {} unchanged, {} removed, {} skipped",
+                    path,
+                    summary.added,
+                    summary.updated,
+                    summary.unchanged,
+                    summary.removed,
+                    summary.skipped
+                ),
+                None => println!(
+                    "{} was already imported for {}; pass --force to import it again",
+                    path,
+                    lang_codes.join(",")
+                ),
+            }
+        }
+        WiktionaryAction::Stats => {
+            println!("Entries: {}", repo.count_entries().await?);
+        }
+    }
+    Ok(())
+}
+
+async fn users(
+    db: &DatabaseConnection,
+    action: UsersAction,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let user_repo = UserRepository::new(db.clone());
+
+    match action {
+        UsersAction::List => {
+            for user in user_repo.list_users().await? {
+                println!(
+                    "{}\t{}\t{}\t{}\tauto_detect={}\t{}",
+                    user.chat_id,
+                    user.user_id.map(|id| id.to_string()).unwrap_or_default(),
+                    user.username.unwrap_or_default(),
+                    user.translation_direction.unwrap_or_default(),
+                    user.auto_detect,
+                    user.created_at
+                );
+            }
+        }
+        UsersAction::Delete { chat_id } => {
+            let saved_words_repo = SavedWordsRepository::new(db.clone());
+            let deleted = user_repo.delete_user(&chat_id).await?;
+            let words = saved_words_repo.delete_saved_words(&chat_id).await?;
+            if deleted {
+                println!("Deleted chat {} and {} saved word(s)", chat_id, words);
+            } else {
+                println!(
+                    "No settings for chat {} ({} saved word(s) deleted)",
+                    chat_id, words
+                );
+            }
+        }
+    }
+    Ok(())
+}
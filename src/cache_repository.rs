@@ -1,6 +1,53 @@
-use crate::entities::cached_articles::{self, ActiveModel, Entity as CachedArticles};
-use chrono::Utc;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use crate::entities::cached_articles::{self, ActiveModel, Entity as CachedArticles, Model};
+use chrono::{NaiveDateTime, Utc};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect, Set, Statement, TransactionTrait,
+};
+use serde::{Deserialize, Serialize};
+
+/// Size of the article cache.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub articles: i64,
+    /// Bytes of stored HTML and formatted text
+    pub bytes: i64,
+}
+
+/// A cached article as written to and read from JSONL exports.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArticleRecord {
+    pub word: String,
+    pub lang_direction: String,
+    pub html: String,
+    pub formatted: Option<String>,
+    #[serde(default)]
+    pub pronunciation: Option<String>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<Model> for ArticleRecord {
+    fn from(model: Model) -> Self {
+        Self {
+            word: model.word,
+            lang_direction: model.lang_direction,
+            html: model.html,
+            formatted: model.formatted,
+            pronunciation: model.pronunciation,
+            updated_at: model.updated_at,
+        }
+    }
+}
+
+/// Restricts which cached articles are listed. Empty fields match everything.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ArticleFilter {
+    pub lang_direction: Option<String>,
+    /// Only articles updated at or after this time
+    pub updated_since: Option<NaiveDateTime>,
+    /// Only articles updated before this time
+    pub updated_before: Option<NaiveDateTime>,
+}
 
 #[derive(Clone)]
 pub struct CacheRepository {
@@ -40,6 +87,7 @@ impl CacheRepository {
         Ok(res.map(|m| m.html))
     }
 
+    #[allow(clippy::field_reassign_with_default)]
     pub async fn insert_html(
         &self,
         word: &str,
@@ -73,6 +121,51 @@ impl CacheRepository {
         }
     }
 
+    /// Stores an article's HTML with its formatted text and pronunciation in
+    /// one transaction, so a shutdown between the writes cannot leave a row
+    /// with HTML but no formatted text. A missing pronunciation keeps the one
+    /// already cached.
+    pub async fn store_article(
+        &self,
+        word: &str,
+        dir: &str,
+        html: &str,
+        formatted: Option<&str>,
+        pronunciation: Option<&str>,
+    ) -> Result<(), sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let now = Utc::now().naive_utc();
+        if let Some(existing) = CachedArticles::find()
+            .filter(cached_articles::Column::Word.eq(word.to_string()))
+            .filter(cached_articles::Column::LangDirection.eq(dir.to_string()))
+            .one(&txn)
+            .await?
+        {
+            let mut am: ActiveModel = existing.into();
+            am.html = Set(html.to_string());
+            am.formatted = Set(formatted.map(str::to_string));
+            if let Some(pronunciation) = pronunciation {
+                am.pronunciation = Set(Some(pronunciation.to_string()));
+            }
+            am.updated_at = Set(now);
+            am.update(&txn).await?;
+        } else {
+            let am = ActiveModel {
+                word: Set(word.to_string()),
+                lang_direction: Set(dir.to_string()),
+                html: Set(html.to_string()),
+                formatted: Set(formatted.map(str::to_string)),
+                pronunciation: Set(pronunciation.map(str::to_string)),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            };
+            am.insert(&txn).await?;
+        }
+        txn.commit().await
+    }
+
+    #[allow(clippy::field_reassign_with_default)]
     pub async fn update_formatted(
         &self,
         word: &str,
@@ -104,4 +197,127 @@ impl CacheRepository {
             Ok(())
         }
     }
+
+    /// Stores the IPA transcription shown in a cached article's header.
+    /// Does nothing when the article is not cached.
+    pub async fn update_pronunciation(
+        &self,
+        word: &str,
+        dir: &str,
+        pronunciation: &str,
+    ) -> Result<(), sea_orm::DbErr> {
+        if let Some(existing) = CachedArticles::find()
+            .filter(cached_articles::Column::Word.eq(word.to_string()))
+            .filter(cached_articles::Column::LangDirection.eq(dir.to_string()))
+            .one(&self.db)
+            .await?
+        {
+            let mut am: ActiveModel = existing.into();
+            am.pronunciation = Set(Some(pronunciation.to_string()));
+            am.update(&self.db).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn get_cached_pronunciation(
+        &self,
+        word: &str,
+        dir: &str,
+    ) -> Result<Option<String>, sea_orm::DbErr> {
+        Ok(CachedArticles::find()
+            .filter(cached_articles::Column::Word.eq(word.to_string()))
+            .filter(cached_articles::Column::LangDirection.eq(dir.to_string()))
+            .one(&self.db)
+            .await?
+            .and_then(|m| m.pronunciation))
+    }
+
+    /// Deletes the cached article for a word, in one direction or in all of
+    /// them. Returns the number of rows deleted.
+    pub async fn purge(&self, word: &str, dir: Option<&str>) -> Result<u64, sea_orm::DbErr> {
+        let mut query = CachedArticles::delete_many()
+            .filter(cached_articles::Column::Word.eq(word.to_string()));
+        if let Some(dir) = dir {
+            query = query.filter(cached_articles::Column::LangDirection.eq(dir.to_string()));
+        }
+        Ok(query.exec(&self.db).await?.rows_affected)
+    }
+
+    pub async fn get_stats(&self) -> Result<CacheStats, sea_orm::DbErr> {
+        let row = self
+            .db
+            .query_one(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT COUNT(*) AS articles, \
+                 COALESCE(SUM(LENGTH(html) + COALESCE(LENGTH(formatted), 0)), 0) AS bytes \
+                 FROM cached_articles",
+            ))
+            .await?;
+
+        match row {
+            Some(row) => Ok(CacheStats {
+                articles: row.try_get("", "articles")?,
+                bytes: row.try_get("", "bytes")?,
+            }),
+            None => Ok(CacheStats::default()),
+        }
+    }
+
+    /// Deletes articles last updated before `cutoff`. Returns the number of rows deleted.
+    pub async fn purge_older_than(&self, cutoff: NaiveDateTime) -> Result<u64, sea_orm::DbErr> {
+        Ok(CachedArticles::delete_many()
+            .filter(cached_articles::Column::UpdatedAt.lt(cutoff))
+            .exec(&self.db)
+            .await?
+            .rows_affected)
+    }
+
+    /// Up to `limit` articles with an id greater than `after_id`, in id order.
+    /// Pass the last id of a page to get the next one.
+    pub async fn list_articles(
+        &self,
+        after_id: i64,
+        limit: u64,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        self.list_articles_matching(after_id, limit, &ArticleFilter::default())
+            .await
+    }
+
+    /// Like [`Self::list_articles`], limited to articles matching `filter`.
+    pub async fn list_articles_matching(
+        &self,
+        after_id: i64,
+        limit: u64,
+        filter: &ArticleFilter,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        let mut query = CachedArticles::find().filter(cached_articles::Column::Id.gt(after_id));
+        if let Some(dir) = &filter.lang_direction {
+            query = query.filter(cached_articles::Column::LangDirection.eq(dir.clone()));
+        }
+        if let Some(since) = filter.updated_since {
+            query = query.filter(cached_articles::Column::UpdatedAt.gte(since));
+        }
+        if let Some(before) = filter.updated_before {
+            query = query.filter(cached_articles::Column::UpdatedAt.lt(before));
+        }
+
+        query
+            .order_by_asc(cached_articles::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+    }
+
+    /// Stores an imported article, replacing any cached copy of the same word
+    /// and direction.
+    pub async fn import_article(&self, record: &ArticleRecord) -> Result<(), sea_orm::DbErr> {
+        self.store_article(
+            &record.word,
+            &record.lang_direction,
+            &record.html,
+            record.formatted.as_deref(),
+            record.pronunciation.as_deref(),
+        )
+        .await
+    }
 }
@@ -0,0 +1,65 @@
+use crate::conjugations::ConjugationTable;
+use crate::entities::cached_conjugations::{self, ActiveModel, Entity as CachedConjugations};
+use chrono::Utc;
+use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+
+#[derive(Clone)]
+pub struct ConjugationRepository {
+    db: DatabaseConnection,
+}
+
+impl ConjugationRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_conjugation(
+        &self,
+        verb: &str,
+        lang: &str,
+    ) -> Result<Option<ConjugationTable>, sea_orm::DbErr> {
+        let res = CachedConjugations::find()
+            .filter(cached_conjugations::Column::Verb.eq(verb.to_string()))
+            .filter(cached_conjugations::Column::Lang.eq(lang.to_string()))
+            .one(&self.db)
+            .await?;
+
+        // A row that no longer deserializes is treated as a cache miss
+        Ok(res.and_then(|m| serde_json::from_str(&m.data).ok()))
+    }
+
+    pub async fn store_conjugation(
+        &self,
+        lang: &str,
+        table: &ConjugationTable,
+    ) -> Result<(), sea_orm::DbErr> {
+        let data = serde_json::to_string(table).map_err(|e| {
+            sea_orm::DbErr::Custom(format!("Failed to serialize conjugation: {}", e))
+        })?;
+        let now = Utc::now().naive_utc();
+
+        if let Some(existing) = CachedConjugations::find()
+            .filter(cached_conjugations::Column::Verb.eq(table.verb.clone()))
+            .filter(cached_conjugations::Column::Lang.eq(lang.to_string()))
+            .one(&self.db)
+            .await?
+        {
+            let mut am: ActiveModel = existing.into();
+            am.data = Set(data);
+            am.updated_at = Set(now);
+            am.update(&self.db).await?;
+        } else {
+            let am = ActiveModel {
+                verb: Set(table.verb.clone()),
+                lang: Set(lang.to_string()),
+                data: Set(data),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            };
+            am.insert(&self.db).await?;
+        }
+
+        Ok(())
+    }
+}
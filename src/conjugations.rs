@@ -0,0 +1,158 @@
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+
+use crate::fetch_translations::fetch_page;
+use crate::languages;
+use crate::render::{collapse_whitespace, escape_html};
+
+/// Full conjugation of a verb as shown by the WordReference conjugator.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConjugationTable {
+    pub verb: String,
+    pub tenses: Vec<ConjugatedTense>,
+}
+
+/// One tense of one mood, e.g. indicativo / presente.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConjugatedTense {
+    pub mood: String,
+    pub tense: String,
+    pub forms: Vec<PersonForm>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PersonForm {
+    pub person: String,
+    pub form: String,
+}
+
+impl ConjugatedTense {
+    /// Short label for the tense picker, e.g. `ind. presente`.
+    pub fn label(&self) -> String {
+        let mood: String = self.mood.chars().take(3).collect();
+        if mood.is_empty() {
+            self.tense.clone()
+        } else {
+            format!("{}. {}", mood.to_lowercase(), self.tense)
+        }
+    }
+}
+
+/// Returns the language whose conjugator should be used for a translation
+/// direction, or None when the direction has no Portuguese or Italian side.
+pub fn conjugation_language(lang_direction: &str) -> Option<&'static str> {
+    let direction = languages::direction(lang_direction)?;
+    [direction.source, direction.target]
+        .into_iter()
+        .find(|lang| matches!(*lang, "pt" | "it"))
+}
+
+/// Characters that must be escaped in a query string value. Non-ASCII bytes
+/// are always encoded.
+const QUERY_VALUE: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'=')
+    .add(b'>');
+
+pub fn get_conjugation_url(verb: &str, lang: &str) -> String {
+    format!(
+        "https://www.wordreference.com/conj/{}verbs.aspx?v={}",
+        lang,
+        utf8_percent_encode(verb, QUERY_VALUE)
+    )
+}
+
+pub async fn fetch_conjugation(verb: &str, lang: &str) -> Result<String, reqwest::Error> {
+    fetch_page(&get_conjugation_url(verb, lang)).await
+}
+
+/// Parses a conjugator page into moods and tenses.
+///
+/// Moods are the `<h4>` headings and every `table.neoConj` that follows one is a
+/// tense: a header cell with the tense name, then one `<th>person</th><td>form</td>`
+/// row per person. Returns None if the page has no conjugation tables.
+pub fn parse_conjugation(verb: &str, body: &str) -> Option<ConjugationTable> {
+    let doc = Html::parse_document(body);
+    let block_sel = Selector::parse("h4, table.neoConj").unwrap();
+    let row_sel = Selector::parse("tr").unwrap();
+    let th_sel = Selector::parse("th").unwrap();
+    let td_sel = Selector::parse("td").unwrap();
+
+    let mut mood = String::new();
+    let mut tenses = Vec::new();
+
+    for block in doc.select(&block_sel) {
+        if block.value().name() == "h4" {
+            mood = collapse_whitespace(&block.text().collect::<String>());
+            continue;
+        }
+
+        let mut tense = String::new();
+        let mut forms = Vec::new();
+
+        for row in block.select(&row_sel) {
+            let th = row.select(&th_sel).next();
+            let td = row.select(&td_sel).next();
+
+            match (th, td) {
+                (Some(th), None) if tense.is_empty() => {
+                    tense = collapse_whitespace(&th.text().collect::<String>());
+                }
+                (Some(th), Some(td)) => forms.push(PersonForm {
+                    person: collapse_whitespace(&th.text().collect::<String>()),
+                    form: collapse_whitespace(&td.text().collect::<Vec<_>>().join(" ")),
+                }),
+                _ => {}
+            }
+        }
+
+        if !forms.is_empty() {
+            tenses.push(ConjugatedTense {
+                mood: mood.clone(),
+                tense,
+                forms,
+            });
+        }
+    }
+
+    if tenses.is_empty() {
+        return None;
+    }
+
+    Some(ConjugationTable {
+        verb: verb.to_string(),
+        tenses,
+    })
+}
+
+/// Formats one tense of the table for a Telegram HTML message.
+pub fn format_tense(table: &ConjugationTable, index: usize) -> Option<String> {
+    let tense = table.tenses.get(index)?;
+
+    let mut text = format!(
+        "<b>{}</b> — {} · {}\n",
+        escape_html(&table.verb),
+        escape_html(&tense.mood),
+        escape_html(&tense.tense)
+    );
+    for form in &tense.forms {
+        if form.person.is_empty() {
+            text.push_str(&format!("{}\n", escape_html(&form.form)));
+        } else {
+            text.push_str(&format!(
+                "<i>{}</i> {}\n",
+                escape_html(&form.person),
+                escape_html(&form.form)
+            ));
+        }
+    }
+
+    Some(text)
+}
@@ -6,3 +6,13 @@ pub const LANG_EN_IT: &str = "enit";
 
 // Default language direction
 pub const DEFAULT_LANG_DIRECTION: &str = LANG_PT_EN;
+
+// SQLite database shared by the bot and the admin CLI
+pub const DATABASE_PATH: &str = "./cache/translations.db";
+pub const DATABASE_URL: &str = "sqlite://cache/translations.db";
+
+// Decompressed copies of compressed offline dictionaries
+pub const OFFLINE_DATA_DIR: &str = "./cache/offline";
+
+// Synthesized pronunciations, one directory per voice
+pub const AUDIO_CACHE_DIR: &str = "./cache/audio";
@@ -0,0 +1,131 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::cached_articles::Model;
+use crate::fetch_translations::{TranslationEntry, get_translation_entries};
+use crate::render::escape_html;
+
+/// A cached article in parsed form, as written to dictionary exports.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DictionaryEntry {
+    pub headword: String,
+    pub lang_direction: String,
+    pub entries: Vec<TranslationEntry>,
+    pub updated_at: NaiveDateTime,
+}
+
+impl DictionaryEntry {
+    /// Parses a cached article. Returns None for articles without translations,
+    /// such as cached "not found" lookups.
+    pub fn from_article(article: &Model) -> Option<Self> {
+        let entries = get_translation_entries(&article.html);
+        if entries.is_empty() {
+            return None;
+        }
+        Some(Self {
+            headword: article.word.clone(),
+            lang_direction: article.lang_direction.clone(),
+            entries,
+            updated_at: article.updated_at,
+        })
+    }
+
+    /// The entry as a small HTML article, one translation per line.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        for entry in &self.entries {
+            if !html.is_empty() {
+                html.push_str("<br>");
+            }
+            html.push_str(&format!("<b>{}</b>", escape_html(&entry.word)));
+            if !entry.part_of_speech.is_empty() {
+                html.push_str(&format!(" <i>{}</i>", escape_html(&entry.part_of_speech)));
+            }
+            if !entry.sense.is_empty() {
+                html.push_str(&format!(" {}", escape_html(&entry.sense)));
+            }
+            html.push_str(&format!(" — {}", escape_html(&entry.translation)));
+            for example in &entry.examples {
+                html.push_str(&format!("<br>&nbsp;&nbsp;<i>{}</i>", escape_html(example)));
+            }
+        }
+        html
+    }
+}
+
+/// The three files of a StarDict dictionary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StarDict {
+    pub ifo: String,
+    pub idx: Vec<u8>,
+    pub dict: Vec<u8>,
+}
+
+/// Builds a StarDict 2.4.2 dictionary with HTML articles (`sametypesequence=h`).
+///
+/// Entries sharing a headword are merged into one article. The index is sorted
+/// the way StarDict expects: ASCII case-insensitively, then byte-wise.
+pub fn build_stardict(book_name: &str, description: &str, entries: &[DictionaryEntry]) -> StarDict {
+    let mut articles: Vec<(String, String)> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for entry in entries {
+        let html = entry.to_html();
+        match positions.get(entry.headword.as_str()) {
+            Some(&i) => {
+                articles[i].1.push_str("<br>");
+                articles[i].1.push_str(&html);
+            }
+            None => {
+                positions.insert(&entry.headword, articles.len());
+                articles.push((entry.headword.clone(), html));
+            }
+        }
+    }
+    articles.sort_by(|a, b| stardict_cmp(&a.0, &b.0));
+
+    let mut idx = Vec::new();
+    let mut dict = Vec::new();
+    for (word, article) in &articles {
+        idx.extend_from_slice(word.as_bytes());
+        idx.push(0);
+        idx.extend_from_slice(&(dict.len() as u32).to_be_bytes());
+        idx.extend_from_slice(&(article.len() as u32).to_be_bytes());
+        dict.extend_from_slice(article.as_bytes());
+    }
+
+    let ifo = format!(
+        "StarDict's dict ifo file\n\
+         version=2.4.2\n\
+         bookname={}\n\
+         wordcount={}\n\
+         idxfilesize={}\n\
+         sametypesequence=h\n\
+         description={}\n",
+        ifo_value(book_name),
+        articles.len(),
+        idx.len(),
+        ifo_value(description),
+    );
+
+    StarDict { ifo, idx, dict }
+}
+
+/// StarDict's index order: `g_ascii_strcasecmp`, then `strcmp` to break ties.
+pub fn stardict_cmp(a: &str, b: &str) -> Ordering {
+    let folded = |s: &str| {
+        s.bytes()
+            .map(|b| b.to_ascii_lowercase())
+            .collect::<Vec<_>>()
+    };
+    folded(a)
+        .cmp(&folded(b))
+        .then_with(|| a.as_bytes().cmp(b.as_bytes()))
+}
+
+/// `.ifo` values are single lines.
+fn ifo_value(value: &str) -> String {
+    value.replace(['\n', '\r'], " ")
+}
@@ -10,6 +10,8 @@ pub struct Model {
     pub html: String,
     #[sea_orm(nullable)]
     pub formatted: Option<String>,
+    #[sea_orm(nullable)]
+    pub pronunciation: Option<String>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
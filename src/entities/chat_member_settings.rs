@@ -0,0 +1,19 @@
+use sea_orm::entity::prelude::*;
+
+/// A member's own settings inside a group, overriding the group's.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "chat_member_settings")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub chat_id: String,
+    pub user_id: i64,
+    pub translation_direction: String,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
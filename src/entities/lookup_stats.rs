@@ -0,0 +1,17 @@
+use sea_orm::entity::prelude::*;
+
+/// Lookup counters for one UTC day.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "lookup_stats")]
+pub struct Model {
+    /// `YYYY-MM-DD`
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub day: String,
+    pub lookups: i64,
+    pub cache_hits: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
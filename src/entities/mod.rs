@@ -1,2 +1,11 @@
 pub mod cached_articles;
+pub mod cached_conjugations;
+pub mod chat_member_settings;
+pub mod lookup_stats;
+pub mod offline_dictionaries;
+pub mod offline_headwords;
+pub mod saved_words;
 pub mod users;
+pub mod wiktionary_entries;
+pub mod wiktionary_imports;
+pub mod wiktionary_senses;
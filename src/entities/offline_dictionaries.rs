@@ -0,0 +1,29 @@
+use sea_orm::entity::prelude::*;
+
+/// A local dictionary file whose headwords are in `offline_headwords`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "offline_dictionaries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    /// The configured `.ifo` or `.dsl` file
+    #[sea_orm(unique)]
+    pub path: String,
+    pub lang_direction: String,
+    /// `stardict` or `dsl`
+    pub format: String,
+    pub name: String,
+    /// File the article offsets point into, decompressed when the original is
+    pub data_path: String,
+    /// StarDict `sametypesequence`, empty when absent and for DSL
+    pub content_type: String,
+    /// Size and modification time (Unix seconds) of `path` when it was indexed
+    pub file_size: i64,
+    pub modified_at: i64,
+    pub indexed_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+
+/// Where a headword's article is stored in an offline dictionary.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "offline_headwords")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub dictionary_id: i32,
+    /// Normalized with `query::normalize_query`
+    pub headword: String,
+    /// Byte range of the article in the dictionary's data file
+    pub offset: i64,
+    pub size: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -6,11 +6,20 @@ use serde::{Deserialize, Serialize};
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub chat_id: String,
-    pub translation_direction: String,
+    /// Unset for groups that only follow their members' own directions
+    #[sea_orm(nullable)]
+    pub translation_direction: Option<String>,
     #[sea_orm(nullable)]
     pub user_id: Option<i64>,
     #[sea_orm(nullable)]
     pub username: Option<String>,
+    pub auto_detect: bool,
+    #[sea_orm(nullable)]
+    pub preferred_variant: Option<String>,
+    pub hide_other_variant: bool,
+    pub group_mode: String,
+    #[sea_orm(nullable)]
+    pub locale: Option<String>,
     pub created_at: DateTime,
     pub updated_at: DateTime,
 }
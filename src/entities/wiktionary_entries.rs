@@ -0,0 +1,30 @@
+use sea_orm::entity::prelude::*;
+
+/// One word, part of speech and etymology of a Wiktionary dump.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wiktionary_entries")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub lang_code: String,
+    pub word: String,
+    /// `word` normalized with `query::normalize_query`, for lookups
+    pub headword: String,
+    pub pos: String,
+    /// 0 when the word has a single etymology
+    pub etymology_number: i32,
+    #[sea_orm(nullable)]
+    pub etymology: Option<String>,
+    /// IPA transcriptions as a JSON array
+    pub ipa: String,
+    /// Inflected forms (`wiktionary::Form`) as a JSON array
+    pub inflections: String,
+    /// SHA-1 of the dump line, to skip unchanged entries on re-import
+    pub checksum: String,
+    pub updated_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,20 @@
+use sea_orm::entity::prelude::*;
+
+/// A Wiktionary dump that was imported, so the same file is skipped next time.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wiktionary_imports")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub path: String,
+    /// SHA-1 of the file
+    #[sea_orm(unique)]
+    pub checksum: String,
+    pub entries: i64,
+    pub imported_at: DateTime,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+
+/// A sense of a Wiktionary entry.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "wiktionary_senses")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = true)]
+    pub id: i32,
+    pub entry_id: i32,
+    /// Order of the sense in its entry, from 0
+    pub position: i32,
+    /// Glosses as a JSON array
+    pub glosses: String,
+    /// Tags such as `colloquial` or `Brazil`, as a JSON array
+    pub tags: String,
+    /// Examples (`wiktionary::Example`) as a JSON array
+    pub examples: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
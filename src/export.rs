@@ -0,0 +1,362 @@
+use std::io::{Cursor, Write};
+
+use chrono::Utc;
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use zip::{ZipWriter, write::SimpleFileOptions};
+
+use crate::fetch_translations::TranslationEntry;
+use crate::render::escape_html;
+
+type ExportError = Box<dyn std::error::Error + Send + Sync>;
+
+// Fixed ids so that re-importing an export updates the same note type and deck in Anki
+const ANKI_MODEL_ID: i64 = 1_607_392_319;
+const ANKI_DECK_ID: i64 = 2_059_400_110;
+
+/// A saved word flattened into flashcard fields.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VocabularyCard {
+    pub front: String,
+    pub back: String,
+    pub part_of_speech: String,
+    pub examples: Vec<String>,
+    pub lang_direction: String,
+}
+
+impl VocabularyCard {
+    /// Builds a card from the structured translation rows of a word.
+    /// Translations and parts of speech are de-duplicated, keeping their order.
+    pub fn from_entries(word: &str, lang_direction: &str, entries: &[TranslationEntry]) -> Self {
+        let mut translations: Vec<String> = Vec::new();
+        let mut parts_of_speech: Vec<String> = Vec::new();
+        let mut examples: Vec<String> = Vec::new();
+
+        for entry in entries {
+            push_unique(&mut translations, &entry.translation);
+            push_unique(&mut parts_of_speech, &entry.part_of_speech);
+            for example in &entry.examples {
+                push_unique(&mut examples, example);
+            }
+        }
+
+        Self {
+            front: word.to_string(),
+            back: translations.join("; "),
+            part_of_speech: parts_of_speech.join(", "),
+            examples,
+            lang_direction: lang_direction.to_string(),
+        }
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: &str) {
+    if !value.is_empty() && !values.iter().any(|v| v == value) {
+        values.push(value.to_string());
+    }
+}
+
+/// Renders cards as CSV with a `front,back,part_of_speech,examples` header.
+/// Examples are joined with ` | ` into a single column.
+pub fn build_csv(cards: &[VocabularyCard]) -> String {
+    let mut csv = String::from("front,back,part_of_speech,examples\n");
+    for card in cards {
+        let row = [
+            card.front.as_str(),
+            card.back.as_str(),
+            card.part_of_speech.as_str(),
+            &card.examples.join(" | "),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",");
+        csv.push_str(&row);
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break.
+pub fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Builds an Anki package (`.apkg`): a zip holding a `collection.anki2` SQLite
+/// database in the legacy (schema 11) format plus an empty media manifest.
+pub async fn build_apkg(cards: &[VocabularyCard], deck_name: &str) -> Result<Vec<u8>, ExportError> {
+    let dir = tempfile::tempdir()?;
+    let db_path = dir.path().join("collection.anki2");
+    write_anki_collection(&db_path.to_string_lossy(), cards, deck_name).await?;
+    let collection = std::fs::read(&db_path)?;
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    zip.start_file("collection.anki2", options)?;
+    zip.write_all(&collection)?;
+    zip.start_file("media", options)?;
+    zip.write_all(b"{}")?;
+
+    Ok(zip.finish()?.into_inner())
+}
+
+async fn write_anki_collection(
+    path: &str,
+    cards: &[VocabularyCard],
+    deck_name: &str,
+) -> Result<(), ExportError> {
+    let db = Database::connect(format!("sqlite://{}?mode=rwc", path)).await?;
+
+    db.execute_unprepared("PRAGMA journal_mode=DELETE").await?;
+    db.execute_unprepared(ANKI_SCHEMA).await?;
+
+    let now = Utc::now();
+    let now_secs = now.timestamp();
+    let now_millis = now.timestamp_millis();
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Sqlite,
+        "INSERT INTO col (id, crt, mod, scm, ver, dty, usn, ls, conf, models, decks, dconf, tags) \
+         VALUES (1, ?, ?, ?, 11, 0, 0, 0, ?, ?, ?, ?, '{}')",
+        [
+            now_secs.into(),
+            now_millis.into(),
+            now_millis.into(),
+            anki_conf().to_string().into(),
+            anki_models(now_secs).to_string().into(),
+            anki_decks(deck_name, now_secs).to_string().into(),
+            anki_deck_conf().to_string().into(),
+        ],
+    ))
+    .await?;
+
+    for (i, card) in cards.iter().enumerate() {
+        let id = now_millis + i as i64;
+        let fields = [
+            escape_html(&card.front),
+            escape_html(&card.back),
+            escape_html(&card.part_of_speech),
+            card.examples
+                .iter()
+                .map(|e| escape_html(e))
+                .collect::<Vec<_>>()
+                .join("<br>"),
+        ]
+        .join("\x1f");
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO notes (id, guid, mid, mod, usn, tags, flds, sfld, csum, flags, data) \
+             VALUES (?, ?, ?, ?, -1, ?, ?, ?, ?, 0, '')",
+            [
+                id.into(),
+                note_guid(card).into(),
+                ANKI_MODEL_ID.into(),
+                now_secs.into(),
+                format!(" {} ", card.lang_direction).into(),
+                fields.into(),
+                card.front.clone().into(),
+                field_checksum(&card.front).into(),
+            ],
+        ))
+        .await?;
+
+        db.execute(Statement::from_sql_and_values(
+            DbBackend::Sqlite,
+            "INSERT INTO cards (id, nid, did, ord, mod, usn, type, queue, due, ivl, factor, reps, \
+             lapses, left, odue, odid, flags, data) \
+             VALUES (?, ?, ?, 0, ?, -1, 0, 0, ?, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+            [
+                id.into(),
+                id.into(),
+                ANKI_DECK_ID.into(),
+                now_secs.into(),
+                (i as i64 + 1).into(),
+            ],
+        ))
+        .await?;
+    }
+
+    db.close().await?;
+    Ok(())
+}
+
+/// Stable note GUID so that re-exporting the same word updates the existing note.
+fn note_guid(card: &VocabularyCard) -> String {
+    let digest = Sha1::digest(format!("{}\x1f{}", card.lang_direction, card.front));
+    digest[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Anki's sort-field checksum: the first 32 bits of the SHA-1 of the field.
+fn field_checksum(field: &str) -> i64 {
+    let digest = Sha1::digest(field);
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+fn anki_conf() -> serde_json::Value {
+    json!({
+        "activeDecks": [1],
+        "curDeck": 1,
+        "newSpread": 0,
+        "collapseTime": 1200,
+        "timeLim": 0,
+        "estTimes": true,
+        "dueCounts": true,
+        "curModel": null,
+        "nextPos": 1,
+        "sortType": "noteFld",
+        "sortBackwards": false,
+        "addToCur": true
+    })
+}
+
+fn anki_models(now_secs: i64) -> serde_json::Value {
+    let field = |name: &str, ord: u32| {
+        json!({
+            "name": name,
+            "ord": ord,
+            "sticky": false,
+            "rtl": false,
+            "font": "Arial",
+            "size": 20,
+            "media": []
+        })
+    };
+
+    json!({
+        ANKI_MODEL_ID.to_string(): {
+            "id": ANKI_MODEL_ID,
+            "name": "pt_dict_bot vocabulary",
+            "type": 0,
+            "mod": now_secs,
+            "usn": -1,
+            "sortf": 0,
+            "did": ANKI_DECK_ID,
+            "tmpls": [{
+                "name": "Card 1",
+                "ord": 0,
+                "qfmt": "{{Front}}",
+                "afmt": "{{FrontSide}}<hr id=answer>{{Back}}<br><i>{{Part of speech}}</i><br>{{Examples}}",
+                "did": null,
+                "bqfmt": "",
+                "bafmt": ""
+            }],
+            "flds": [
+                field("Front", 0),
+                field("Back", 1),
+                field("Part of speech", 2),
+                field("Examples", 3)
+            ],
+            "css": ".card { font-family: arial; font-size: 20px; text-align: center; }",
+            "latexPre": "\\documentclass[12pt]{article}\n\\special{papersize=3in,5in}\n\\usepackage{amssymb,amsmath}\n\\pagestyle{empty}\n\\setlength{\\parindent}{0in}\n\\begin{document}\n",
+            "latexPost": "\\end{document}",
+            "tags": [],
+            "vers": [],
+            "req": [[0, "any", [0]]]
+        }
+    })
+}
+
+fn anki_decks(deck_name: &str, now_secs: i64) -> serde_json::Value {
+    let deck = |id: i64, name: &str| {
+        json!({
+            "id": id,
+            "name": name,
+            "desc": "",
+            "mod": now_secs,
+            "usn": -1,
+            "collapsed": false,
+            "conf": 1,
+            "dyn": 0,
+            "extendNew": 10,
+            "extendRev": 50,
+            "newToday": [0, 0],
+            "revToday": [0, 0],
+            "lrnToday": [0, 0],
+            "timeToday": [0, 0]
+        })
+    };
+
+    json!({
+        "1": deck(1, "Default"),
+        ANKI_DECK_ID.to_string(): deck(ANKI_DECK_ID, deck_name)
+    })
+}
+
+fn anki_deck_conf() -> serde_json::Value {
+    json!({
+        "1": {
+            "id": 1,
+            "name": "Default",
+            "mod": 0,
+            "usn": 0,
+            "maxTaken": 60,
+            "autoplay": true,
+            "timer": 0,
+            "replayq": true,
+            "new": {
+                "bury": true,
+                "delays": [1, 10],
+                "initialFactor": 2500,
+                "ints": [1, 4, 7],
+                "order": 1,
+                "perDay": 20,
+                "separate": true
+            },
+            "lapse": {
+                "delays": [10],
+                "leechAction": 0,
+                "leechFails": 8,
+                "minInt": 1,
+                "mult": 0
+            },
+            "rev": {
+                "bury": true,
+                "ease4": 1.3,
+                "fuzz": 0.05,
+                "ivlFct": 1,
+                "maxIvl": 36500,
+                "minSpace": 1,
+                "perDay": 100
+            }
+        }
+    })
+}
+
+const ANKI_SCHEMA: &str = r#"
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+    ver integer not null, dty integer not null, usn integer not null, ls integer not null,
+    conf text not null, models text not null, decks text not null, dconf text not null,
+    tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null, mod integer not null,
+    usn integer not null, tags text not null, flds text not null, sfld integer not null,
+    csum integer not null, flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null, ord integer not null,
+    mod integer not null, usn integer not null, type integer not null, queue integer not null,
+    due integer not null, ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null, odid integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null, ivl integer not null,
+    lastIvl integer not null, factor integer not null, time integer not null, type integer not null
+);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn on notes (usn);
+CREATE INDEX ix_cards_usn on cards (usn);
+CREATE INDEX ix_revlog_usn on revlog (usn);
+CREATE INDEX ix_cards_nid on cards (nid);
+CREATE INDEX ix_cards_sched on cards (did, queue, due);
+CREATE INDEX ix_revlog_cid on revlog (cid);
+CREATE INDEX ix_notes_csum on notes (csum);
+"#;
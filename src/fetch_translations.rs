@@ -1,16 +1,25 @@
+use std::sync::LazyLock;
+
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
 use reqwest::Client;
 use scraper::{ElementRef, Html, Node, Selector};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::DEFAULT_LANG_DIRECTION;
+use crate::languages;
+use crate::rate_limit::{OutboundLimiter, RateLimitConfig};
+use crate::render::{collapse_whitespace, escape_html};
+use crate::variants::{self, Variant};
 
-use crate::constants::{LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
+/// Paces every request to WordReference, whichever chat triggered it.
+static OUTBOUND_LIMITER: LazyLock<OutboundLimiter> =
+    LazyLock::new(|| OutboundLimiter::new(RateLimitConfig::from_env().fetch_per_second));
 
 pub fn get_translation_table_header(lang_direction: &str) -> &'static str {
-    match lang_direction {
-        s if s == LANG_PT_EN => "Traduções principais",
-        s if s == LANG_EN_PT => "Traduções principais",
-        s if s == LANG_IT_EN => "Principal Translations/Traduzioni principali",
-        s if s == LANG_EN_IT => "Principal Translations/Traduzioni principali",
-        _ => "Traduções principais", // default case
-    }
+    languages::direction(lang_direction)
+        .or_else(|| languages::direction(DEFAULT_LANG_DIRECTION)) // default case
+        .map(|d| d.table_header)
+        .unwrap_or_default()
 }
 
 pub fn get_raw_translations(body: &str, lang_direction: &str) -> String {
@@ -60,33 +69,155 @@ pub fn get_translations(table_html: &str) -> String {
         // TODO: improve formatting
         translations.push_str(&format!(
             "<b>{}</b> {} ⮕ {}\n",
-            get_from_word_text(&tds[0]),
-            tds[1].text().collect::<Vec<_>>().join(" "),
-            get_translation_text(&tds[2])
+            escape_html(&get_from_word_text(&tds[0])),
+            escape_html(&tds[1].text().collect::<Vec<_>>().join(" ")),
+            escape_html(&get_translation_text(&tds[2]))
         ));
     }
 
     translations
 }
 
-pub async fn fetch(word: &str, lang_direction: &str) -> String {
-    let url = format!("https://www.wordreference.com/{}/{}", lang_direction, word);
+/// IPA transcriptions shown in the header of a WordReference page (e.g.
+/// `/ˈkazɐ/`), in page order and without repeats.
+pub fn get_pronunciations(body: &str) -> Vec<String> {
+    let doc = Html::parse_document(body);
+    let pron_sel = Selector::parse("#pronWR, .pronWR, .pronRH").unwrap();
+
+    let mut pronunciations: Vec<String> = Vec::new();
+    for span in doc.select(&pron_sel) {
+        let text = collapse_whitespace(&span.text().collect::<String>());
+        if !text.is_empty() && !pronunciations.contains(&text) {
+            pronunciations.push(text);
+        }
+    }
+    pronunciations
+}
+
+/// One translation row of a WordReference table, in structured form.
+///
+/// Continuation rows (which leave the source column empty) inherit `word` and
+/// `part_of_speech` from the row above. Example sentences that follow a row are
+/// collected into its `examples`. Regional labels such as `(BR)` found in the
+/// row are kept in `variants`; the text keeps them too.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TranslationEntry {
+    pub word: String,
+    pub part_of_speech: String,
+    pub sense: String,
+    pub translation: String,
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub variants: Vec<Variant>,
+}
+
+pub fn get_translation_entries(table_html: &str) -> Vec<TranslationEntry> {
+    let doc = Html::parse_document(table_html);
+    let row_sel = Selector::parse("tr").unwrap();
+    let td_sel = Selector::parse("td").unwrap();
+
+    let mut entries: Vec<TranslationEntry> = Vec::new();
+
+    for row in doc.select(&row_sel) {
+        let class = row.value().attr("class").unwrap_or("");
+        if !class.contains("even") && !class.contains("odd") {
+            continue;
+        }
+
+        let tds: Vec<ElementRef> = row.select(&td_sel).collect();
+
+        // Example rows: <td class="FrEx"> or <td class="ToEx"> spanning two columns
+        if let Some(example_td) = tds.iter().find(|td| {
+            td.value()
+                .attr("class")
+                .is_some_and(|c| c.contains("FrEx") || c.contains("ToEx"))
+        }) {
+            let example = collapse_whitespace(&example_td.text().collect::<String>());
+            if let Some(last) = entries.last_mut()
+                && !example.is_empty()
+            {
+                last.examples.push(example);
+            }
+            continue;
+        }
+
+        if tds.len() != 3 {
+            continue;
+        }
+
+        let mut word = get_from_word_text(&tds[0]);
+        let mut part_of_speech = get_part_of_speech(&tds[0]);
+        if word.is_empty()
+            && let Some(previous) = entries.last()
+        {
+            word = previous.word.clone();
+            if part_of_speech.is_empty() {
+                part_of_speech = previous.part_of_speech.clone();
+            }
+        }
+
+        let row_text: String = tds.iter().flat_map(|td| td.text()).collect();
+        entries.push(TranslationEntry {
+            word,
+            part_of_speech,
+            sense: collapse_whitespace(&tds[1].text().collect::<String>()),
+            translation: get_translation_text(&tds[2]),
+            examples: Vec::new(),
+            variants: variants::parse_labels(&row_text),
+        });
+    }
+
+    entries
+}
+
+/// Characters that must be escaped in a URL path segment. Non-ASCII bytes are
+/// always encoded.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+/// WordReference URL for a word or phrase, with the word percent-encoded as a
+/// single path segment.
+pub fn get_translation_url(word: &str, lang_direction: &str) -> String {
+    let path = languages::direction(lang_direction)
+        .map(|d| d.path)
+        .unwrap_or(lang_direction);
+    format!(
+        "https://www.wordreference.com/{}/{}",
+        path,
+        utf8_percent_encode(word, PATH_SEGMENT)
+    )
+}
+
+pub async fn fetch(word: &str, lang_direction: &str) -> Result<String, reqwest::Error> {
+    fetch_page(&get_translation_url(word, lang_direction)).await
+}
+
+/// Downloads a WordReference page with a browser User-Agent, waiting for the
+/// bot-wide outbound limiter first.
+pub async fn fetch_page(url: &str) -> Result<String, reqwest::Error> {
+    OUTBOUND_LIMITER.acquire().await;
     let client = Client::new();
 
-    let body = client
-        .get(&url)
+    client
+        .get(url)
         .header(
             "User-Agent",
             "Mozilla/5.0 (X11; Linux x86_64; rv:141.0) Gecko/20100101 Firefox/141.0",
         )
         .send()
-        .await
-        .unwrap()
+        .await?
         .text()
         .await
-        .unwrap();
-
-    return body;
 }
 
 fn get_from_word_text(td: &scraper::ElementRef) -> String {
@@ -104,6 +235,24 @@ fn get_from_word_text(td: &scraper::ElementRef) -> String {
         .join(" ")
 }
 
+/// Returns the part-of-speech marker (e.g. `sf`, `vtr`) of a source cell,
+/// without the tooltip text nested inside it.
+fn get_part_of_speech(td: &ElementRef) -> String {
+    let pos_sel = Selector::parse("em.POS2").unwrap();
+
+    td.select(&pos_sel)
+        .next()
+        .map(|em| {
+            em.children()
+                .filter_map(|child| child.value().as_text().map(|t| t.trim().to_string()))
+                .filter(|t| !t.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_default()
+}
+
+#[allow(clippy::collapsible_if)]
 fn get_translation_text(td: &ElementRef) -> String {
     td.children()
         .filter_map(|child| {
@@ -0,0 +1,94 @@
+use teloxide::types::{Message, MessageEntity, MessageEntityKind, MessageEntityRef, UserId};
+
+/// Which messages the bot answers in a group. Commands addressed to the bot
+/// are answered in every mode.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GroupMode {
+    /// Messages that mention the bot
+    #[default]
+    Mention,
+    /// Replies to the bot's own messages
+    Reply,
+    /// Every text message, for groups dedicated to practice
+    All,
+}
+
+impl GroupMode {
+    pub const ALL: [GroupMode; 3] = [GroupMode::Mention, GroupMode::Reply, GroupMode::All];
+
+    /// Code stored in the chat's settings and given to /groupmode.
+    pub fn code(self) -> &'static str {
+        match self {
+            GroupMode::Mention => "mention",
+            GroupMode::Reply => "reply",
+            GroupMode::All => "all",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<GroupMode> {
+        GroupMode::ALL
+            .into_iter()
+            .find(|mode| mode.code() == code.trim().to_lowercase())
+    }
+}
+
+/// Removes every mention of the bot from a message: `@username` mentions
+/// anywhere in the text, and text mentions of users without a username that
+/// point at the bot's id. Returns None when the bot is not mentioned.
+pub fn strip_bot_mentions(
+    text: &str,
+    entities: &[MessageEntity],
+    username: &str,
+    bot_id: UserId,
+) -> Option<String> {
+    let handle = format!("@{}", username);
+    let mentions: Vec<_> = MessageEntityRef::parse(text, entities)
+        .into_iter()
+        .filter(|entity| match entity.kind() {
+            MessageEntityKind::Mention => entity.text().eq_ignore_ascii_case(&handle),
+            MessageEntityKind::TextMention { user } => user.id == bot_id,
+            _ => false,
+        })
+        .map(|entity| entity.range())
+        .collect();
+    if mentions.is_empty() {
+        return None;
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut position = 0;
+    for range in mentions {
+        if range.start < position {
+            continue;
+        }
+        stripped.push_str(&text[position..range.start]);
+        position = range.end;
+    }
+    stripped.push_str(&text[position..]);
+
+    Some(stripped.trim().to_string())
+}
+
+/// Whether a group message is meant for the bot under the group's mode.
+pub fn should_respond(
+    mode: GroupMode,
+    mentioned: bool,
+    replies_to_bot: bool,
+    is_command: bool,
+) -> bool {
+    is_command
+        || match mode {
+            GroupMode::Mention => mentioned,
+            GroupMode::Reply => replies_to_bot,
+            GroupMode::All => true,
+        }
+}
+
+/// The message `msg` replies to. In forum topics Telegram sets every message's
+/// reply to the topic's first message, which is not a reply the user made, so
+/// that one is left out.
+pub fn replied_message(msg: &Message) -> Option<&Message> {
+    msg.reply_to_message().filter(|replied| {
+        !(msg.is_topic_message && msg.thread_id.is_some_and(|thread| thread.0 == replied.id))
+    })
+}
@@ -0,0 +1,172 @@
+use std::sync::LazyLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use fluent_syntax::ast;
+use unic_langid::LanguageIdentifier;
+
+use crate::languages::{self, Direction};
+
+/// A language the bot's interface is translated into. Catalogs are Fluent
+/// files under `locales/`, embedded in the binary.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    English,
+    Portuguese,
+    Italian,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::English, Locale::Portuguese, Locale::Italian];
+
+    /// ISO 639-1 code, stored in the chat's settings and given to Telegram.
+    pub fn code(self) -> &'static str {
+        match self {
+            Locale::English => "en",
+            Locale::Portuguese => "pt",
+            Locale::Italian => "it",
+        }
+    }
+
+    /// Reads a code or an IETF tag such as Telegram's `language_code`
+    /// (`pt-br`, `it`), going by its primary language only.
+    pub fn from_code(code: &str) -> Option<Locale> {
+        let primary = code.split(['-', '_']).next()?.trim().to_lowercase();
+        Locale::ALL.into_iter().find(|l| l.code() == primary)
+    }
+
+    /// The locale for a Telegram user's `language_code`, English when it is
+    /// missing or not translated.
+    pub fn from_language_code(code: Option<&str>) -> Locale {
+        code.and_then(Locale::from_code).unwrap_or_default()
+    }
+
+    /// The language's name in itself, for pickers.
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Portuguese => "Português",
+            Locale::Italian => "Italiano",
+        }
+    }
+
+    fn catalog(self) -> &'static str {
+        match self {
+            Locale::English => include_str!("../locales/en.ftl"),
+            Locale::Portuguese => include_str!("../locales/pt.ftl"),
+            Locale::Italian => include_str!("../locales/it.ftl"),
+        }
+    }
+}
+
+static BUNDLES: LazyLock<Vec<FluentBundle<FluentResource>>> = LazyLock::new(|| {
+    Locale::ALL
+        .into_iter()
+        .map(|locale| {
+            let resource = FluentResource::try_new(locale.catalog().to_string()).unwrap_or_else(
+                |(_, errors)| panic!("Invalid {} catalog: {:?}", locale.code(), errors),
+            );
+            let id: LanguageIdentifier = locale.code().parse().expect("Invalid language code");
+            let mut bundle = FluentBundle::new_concurrent(vec![id]);
+            // Telegram shows the bidi isolation marks as stray characters
+            bundle.set_use_isolating(false);
+            bundle.add_resource(resource).unwrap_or_else(|errors| {
+                panic!("Duplicate {} messages: {:?}", locale.code(), errors)
+            });
+            bundle
+        })
+        .collect()
+});
+
+fn bundle(locale: Locale) -> &'static FluentBundle<FluentResource> {
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    &BUNDLES[index]
+}
+
+/// Translates a message that takes no arguments.
+pub fn t(locale: Locale, id: &str) -> String {
+    t_args(locale, id, &[])
+}
+
+/// Translates a message, filling in its `{ $name }` placeholders. Messages
+/// missing from a catalog fall back to English, then to their id.
+pub fn t_args(locale: Locale, id: &str, args: &[(&str, &str)]) -> String {
+    let mut fluent_args = FluentArgs::new();
+    for (name, value) in args {
+        fluent_args.set(*name, *value);
+    }
+
+    for bundle in [bundle(locale), bundle(Locale::English)] {
+        let Some(pattern) = bundle.get_message(id).and_then(|m| m.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, Some(&fluent_args), &mut errors);
+        if !errors.is_empty() {
+            log::warn!("Errors formatting {} ({}): {:?}", id, locale.code(), errors);
+        }
+        return text.into_owned();
+    }
+
+    log::warn!("Missing message {}", id);
+    id.to_string()
+}
+
+/// Ids of the English messages a locale's catalog does not translate.
+pub fn missing_messages(locale: Locale) -> Vec<String> {
+    let english = FluentResource::try_new(Locale::English.catalog().to_string())
+        .unwrap_or_else(|(resource, _)| resource);
+    english
+        .entries()
+        .filter_map(|entry| match entry {
+            ast::Entry::Message(message) => Some(message.id.name.to_string()),
+            _ => None,
+        })
+        .filter(|id| !bundle(locale).has_message(id))
+        .collect()
+}
+
+/// A language's name, e.g. `Portuguese` or `Portoghese`.
+pub fn language_name(locale: Locale, code: &str) -> String {
+    t(locale, &format!("language-{}", code))
+}
+
+/// The message id of a lemmatizer description: `inflection-` plus the
+/// description in lowercase with dashes, e.g. `inflection-1st-sg-preterite`.
+pub fn inflection_id(description: &str) -> String {
+    let slug: Vec<String> = description
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(str::to_lowercase)
+        .collect();
+    format!("inflection-{}", slug.join("-"))
+}
+
+/// A lemmatizer description such as `1st sg. preterite`, translated.
+/// Descriptions without a message are shown as they are.
+pub fn inflection_name(locale: Locale, description: &str) -> String {
+    let id = inflection_id(description);
+    if bundle(Locale::English).has_message(&id) {
+        t(locale, &id)
+    } else {
+        description.to_string()
+    }
+}
+
+/// Localized direction name, e.g. `🇵🇹 Português → 🇬🇧 Inglês`.
+pub fn direction_name(locale: Locale, direction: &Direction) -> String {
+    match (
+        languages::language(direction.source),
+        languages::language(direction.target),
+    ) {
+        (Some(source), Some(target)) => format!(
+            "{} {} → {} {}",
+            source.flag,
+            language_name(locale, source.code),
+            target.flag,
+            language_name(locale, target.code)
+        ),
+        _ => direction.code.to_string(),
+    }
+}
@@ -0,0 +1,229 @@
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+use crate::conjugations::ConjugationTable;
+use crate::i18n::{self, Locale};
+use crate::languages;
+use crate::message_split;
+use crate::pronunciation;
+use crate::variants::{Variant, VariantPreference};
+
+/// Telegram rejects a whole message if any of its buttons carries more
+/// callback data than this, in bytes.
+pub const CALLBACK_DATA_LIMIT: usize = 64;
+
+/// Whether callback data is short enough for Telegram to accept.
+pub fn fits(data: &str) -> bool {
+    data.len() <= CALLBACK_DATA_LIMIT
+}
+
+/// Buttons switching a conjugation message between tenses, three per row,
+/// with the selected one marked. Callback data has the form
+/// `conj:{lang}:{index}:{verb}`. None when the verb is too long for it.
+pub fn tense_picker(
+    table: &ConjugationTable,
+    lang: &str,
+    selected: usize,
+) -> Option<InlineKeyboardMarkup> {
+    let buttons: Vec<InlineKeyboardButton> = table
+        .tenses
+        .iter()
+        .enumerate()
+        .map(|(i, tense)| {
+            let label = if i == selected {
+                format!("• {}", tense.label())
+            } else {
+                tense.label()
+            };
+            let data = format!("conj:{}:{}:{}", lang, i, table.verb);
+            fits(&data).then(|| InlineKeyboardButton::callback(label, data))
+        })
+        .collect::<Option<_>>()?;
+
+    Some(InlineKeyboardMarkup::new(
+        buttons.chunks(3).map(|row| row.to_vec()),
+    ))
+}
+
+/// Picks one page of a rendered reply and builds its keyboard.
+///
+/// Replies longer than a Telegram message get ◀ ▶ buttons and a "show all"
+/// button; auto-detected replies get a button repeating the lookup in the other
+/// direction, and a 🔊 button speaks the word when a TTS engine is installed.
+/// Callback data has the forms `page:{mode}{page}:{direction}:{word}`,
+/// `all:{mode}:{direction}:{word}`, `look:{direction}:{word}` and
+/// `say:{language}:{word}`, where mode is `a` for auto-detected replies and `p`
+/// otherwise. Buttons whose data would not fit are left out; the page counter
+/// between ◀ and ▶ does nothing and carries `noop`.
+pub fn reply_page(
+    word: &str,
+    direction: &str,
+    auto_detected: bool,
+    full: &str,
+    page: usize,
+    locale: Locale,
+) -> (String, Option<InlineKeyboardMarkup>) {
+    let pages = message_split::split_message(full, message_split::TELEGRAM_MESSAGE_LIMIT);
+    let page = page.min(pages.len().saturating_sub(1));
+    let text = pages.get(page).cloned().unwrap_or_default();
+    let mode = if auto_detected { 'a' } else { 'p' };
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> = Vec::new();
+
+    if pages.len() > 1 {
+        let mut nav = Vec::new();
+        let prev = format!(
+            "page:{}{}:{}:{}",
+            mode,
+            page.saturating_sub(1),
+            direction,
+            word
+        );
+        if page > 0 && fits(&prev) {
+            nav.push(InlineKeyboardButton::callback("◀", prev));
+        }
+        nav.push(InlineKeyboardButton::callback(
+            format!("{}/{}", page + 1, pages.len()),
+            "noop",
+        ));
+        let next = format!("page:{}{}:{}:{}", mode, page + 1, direction, word);
+        if page + 1 < pages.len() && fits(&next) {
+            nav.push(InlineKeyboardButton::callback("▶", next));
+        }
+        rows.push(nav);
+
+        let all = format!("all:{}:{}:{}", mode, direction, word);
+        if fits(&all) {
+            rows.push(vec![InlineKeyboardButton::callback(
+                i18n::t(locale, "show-all"),
+                all,
+            )]);
+        }
+    }
+
+    if pronunciation::synthesizer().is_some()
+        && let Some(source) = languages::direction(direction).map(|d| d.source)
+        && pronunciation::voice_for(source, None).is_some()
+    {
+        let data = format!("say:{}:{}", source, word);
+        if fits(&data) {
+            rows.push(vec![InlineKeyboardButton::callback("🔊", data)]);
+        }
+    }
+
+    if auto_detected
+        && let Some(reversed) = languages::direction(direction).and_then(|d| d.reversed())
+    {
+        let data = format!("look:{}:{}", reversed.code, word);
+        if fits(&data) {
+            rows.push(vec![InlineKeyboardButton::callback(
+                format!("⇄ {}", i18n::direction_name(locale, reversed)),
+                data,
+            )]);
+        }
+    }
+
+    let keyboard = (!rows.is_empty()).then(|| InlineKeyboardMarkup::new(rows));
+    (text, keyboard)
+}
+
+/// Keyboard with one button per word, three per row. Callback data has the
+/// form `pick:{word}`; words too long for it are left out.
+pub fn word_picker(words: &[String]) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = words
+        .iter()
+        .map(|word| (word, format!("pick:{}", word)))
+        .filter(|(_, data)| fits(data))
+        .map(|(word, data)| InlineKeyboardButton::callback(word.clone(), data))
+        .collect();
+
+    InlineKeyboardMarkup::new(buttons.chunks(3).map(|row| row.to_vec()))
+}
+
+/// Keyboard with one button per registered direction, two per row, and a row
+/// choosing the language the bot speaks in the chat, where None follows each
+/// user's Telegram language. Callback data has the forms
+/// `dir:{direction code}` and `loc:{locale code|auto}`.
+pub fn settings_picker(
+    locale: Locale,
+    current: &str,
+    chosen: Option<Locale>,
+) -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = languages::DIRECTIONS
+        .iter()
+        .map(|d| {
+            InlineKeyboardButton::callback(
+                mark(d.code == current, i18n::direction_name(locale, d)),
+                format!("dir:{}", d.code),
+            )
+        })
+        .collect();
+
+    let mut locales: Vec<InlineKeyboardButton> = Locale::ALL
+        .into_iter()
+        .map(|l| {
+            InlineKeyboardButton::callback(
+                mark(chosen == Some(l), l.native_name().to_string()),
+                format!("loc:{}", l.code()),
+            )
+        })
+        .collect();
+    locales.push(InlineKeyboardButton::callback(
+        mark(chosen.is_none(), i18n::t(locale, "locale-auto-button")),
+        "loc:auto",
+    ));
+
+    let mut rows: Vec<Vec<InlineKeyboardButton>> =
+        buttons.chunks(2).map(|row| row.to_vec()).collect();
+    rows.push(locales);
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Keyboard choosing the preferred variant and how the other one is shown.
+/// Callback data has the form `var:{pt|br|any|hide|show}`.
+pub fn variant_picker(locale: Locale, preference: VariantPreference) -> InlineKeyboardMarkup {
+    let mut choices: Vec<InlineKeyboardButton> = [Variant::European, Variant::Brazilian]
+        .into_iter()
+        .map(|v| {
+            InlineKeyboardButton::callback(
+                mark(
+                    preference.preferred == Some(v),
+                    format!("{} {}", v.flag(), v.code().to_uppercase()),
+                ),
+                format!("var:{}", v.code()),
+            )
+        })
+        .collect();
+    choices.push(InlineKeyboardButton::callback(
+        mark(
+            preference.preferred.is_none(),
+            i18n::t(locale, "variant-both"),
+        ),
+        "var:any",
+    ));
+
+    let toggle = if preference.hide_others {
+        InlineKeyboardButton::callback(i18n::t(locale, "variant-show-other"), "var:show")
+    } else {
+        InlineKeyboardButton::callback(i18n::t(locale, "variant-hide-other"), "var:hide")
+    };
+
+    InlineKeyboardMarkup::new(vec![choices, vec![toggle]])
+}
+
+/// Keyboard asking whose direction /flip changes in a group.
+/// Callback data has the form `flip:{me|group}`.
+pub fn flip_picker(locale: Locale) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new(vec![vec![
+        InlineKeyboardButton::callback(i18n::t(locale, "flip-for-me"), "flip:me"),
+        InlineKeyboardButton::callback(i18n::t(locale, "flip-for-group"), "flip:group"),
+    ]])
+}
+
+/// A button label, marked when it is the current choice.
+fn mark(selected: bool, label: String) -> String {
+    if selected {
+        format!("• {}", label)
+    } else {
+        label
+    }
+}
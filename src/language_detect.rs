@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::languages::Direction;
+
+/// Character trigram counts for one language, trained on a bundled list of
+/// common words.
+struct TrigramProfile {
+    counts: HashMap<String, u32>,
+    total: u32,
+}
+
+/// Letters outside a-z that belong to each language's alphabet.
+const EXTRA_LETTERS: &[(&str, &str)] = &[
+    ("pt", "áâãàçéêíóôõú"),
+    ("en", ""),
+    ("it", "àèéìíîòóùú"),
+    ("es", "áéíñóúü"),
+    ("fr", "àâæçéèêëîïôœùûüÿ"),
+    ("de", "äöüß"),
+];
+
+const TRAINING_WORDS: &[(&str, &str)] = &[
+    ("pt", include_str!("../data/common_words/pt.txt")),
+    ("en", include_str!("../data/common_words/en.txt")),
+    ("it", include_str!("../data/common_words/it.txt")),
+    ("es", include_str!("../data/common_words/es.txt")),
+    ("fr", include_str!("../data/common_words/fr.txt")),
+    ("de", include_str!("../data/common_words/de.txt")),
+];
+
+/// Assumed number of distinct trigrams, used for add-one smoothing.
+const TRIGRAM_VOCABULARY: f64 = 8000.0;
+/// Log-probability charged for each letter outside a language's alphabet.
+const FOREIGN_LETTER_PENALTY: f64 = -12.0;
+/// Minimum score difference for a classification to be trusted.
+const MIN_MARGIN: f64 = 0.15;
+
+static PROFILES: LazyLock<HashMap<&'static str, TrigramProfile>> = LazyLock::new(|| {
+    TRAINING_WORDS
+        .iter()
+        .map(|(lang, words)| {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            let mut total = 0;
+            for word in words.split_whitespace() {
+                for trigram in trigrams(&word.to_lowercase()) {
+                    *counts.entry(trigram).or_default() += 1;
+                    total += 1;
+                }
+            }
+            (*lang, TrigramProfile { counts, total })
+        })
+        .collect()
+});
+
+/// Padded character trigrams of a word, e.g. `casa` → ` ca`, `cas`, `asa`, `sa `.
+fn trigrams(word: &str) -> Vec<String> {
+    let chars: Vec<char> = std::iter::once(' ')
+        .chain(word.chars())
+        .chain(std::iter::once(' '))
+        .collect();
+    chars
+        .windows(3)
+        .map(|w| w.iter().collect::<String>())
+        .collect()
+}
+
+fn is_native_letter(lang: &str, c: char) -> bool {
+    c.is_ascii_alphabetic()
+        || EXTRA_LETTERS
+            .iter()
+            .any(|(code, extra)| *code == lang && extra.contains(c))
+}
+
+/// Average per-trigram log-probability of the text under a language's profile.
+/// Returns None for languages without a profile.
+pub fn score(text: &str, lang: &str) -> Option<f64> {
+    let profile = PROFILES.get(lang)?;
+    let text = text.to_lowercase();
+
+    let mut log_prob = 0.0;
+    let mut n = 0;
+    for word in text.split_whitespace() {
+        for trigram in trigrams(word) {
+            let count = profile.counts.get(&trigram).copied().unwrap_or(0) as f64;
+            log_prob += ((count + 1.0) / (profile.total as f64 + TRIGRAM_VOCABULARY)).ln();
+            n += 1;
+        }
+        for c in word.chars().filter(|c| c.is_alphabetic()) {
+            if !is_native_letter(lang, c) {
+                log_prob += FOREIGN_LETTER_PENALTY;
+            }
+        }
+    }
+
+    if n == 0 {
+        return None;
+    }
+    Some(log_prob / n as f64)
+}
+
+/// Picks the most likely language of `text` among `candidates`.
+///
+/// Returns None when no candidate has a profile or the best two scores are too
+/// close to call.
+pub fn detect_language<'a>(text: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let mut scored: Vec<(&str, f64)> = candidates
+        .iter()
+        .filter_map(|lang| score(text, lang).map(|s| (*lang, s)))
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    match scored.as_slice() {
+        [] => None,
+        [(lang, _)] => Some(lang),
+        [(best, best_score), (_, second_score), ..] => {
+            (best_score - second_score >= MIN_MARGIN).then_some(*best)
+        }
+    }
+}
+
+/// Chooses the direction for a word from the chat's stored direction: the
+/// reversed direction when the word looks like the stored target language,
+/// otherwise the stored one.
+pub fn detect_direction(text: &str, stored: &'static Direction) -> &'static Direction {
+    let Some(reversed) = stored.reversed() else {
+        return stored;
+    };
+
+    match detect_language(text, &[stored.source, stored.target]) {
+        Some(lang) if lang == stored.target => reversed,
+        _ => stored,
+    }
+}
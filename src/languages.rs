@@ -0,0 +1,152 @@
+use crate::constants::{LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
+
+/// A language the bot can translate from or to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Language {
+    pub code: &'static str,
+    pub flag: &'static str,
+}
+
+/// A supported WordReference dictionary, identified by its 4-letter code
+/// (`{source}{target}`, e.g. `pten`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Direction {
+    pub code: &'static str,
+    pub source: &'static str,
+    pub target: &'static str,
+    /// Path segment of the dictionary on wordreference.com
+    pub path: &'static str,
+    /// Text found in the header row of the principal translations table
+    pub table_header: &'static str,
+}
+
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        code: "pt",
+        flag: "🇵🇹",
+    },
+    Language {
+        code: "en",
+        flag: "🇬🇧",
+    },
+    Language {
+        code: "it",
+        flag: "🇮🇹",
+    },
+    Language {
+        code: "es",
+        flag: "🇪🇸",
+    },
+    Language {
+        code: "fr",
+        flag: "🇫🇷",
+    },
+    Language {
+        code: "de",
+        flag: "🇩🇪",
+    },
+];
+
+pub const DIRECTIONS: &[Direction] = &[
+    Direction {
+        code: LANG_PT_EN,
+        source: "pt",
+        target: "en",
+        path: "pten",
+        table_header: "Traduções principais",
+    },
+    Direction {
+        code: LANG_EN_PT,
+        source: "en",
+        target: "pt",
+        path: "enpt",
+        table_header: "Traduções principais",
+    },
+    Direction {
+        code: LANG_IT_EN,
+        source: "it",
+        target: "en",
+        path: "iten",
+        table_header: "Principal Translations/Traduzioni principali",
+    },
+    Direction {
+        code: LANG_EN_IT,
+        source: "en",
+        target: "it",
+        path: "enit",
+        table_header: "Principal Translations/Traduzioni principali",
+    },
+    Direction {
+        code: "esen",
+        source: "es",
+        target: "en",
+        path: "esen",
+        table_header: "Traducciones principales",
+    },
+    Direction {
+        code: "enes",
+        source: "en",
+        target: "es",
+        path: "enes",
+        table_header: "Traducciones principales",
+    },
+    Direction {
+        code: "fren",
+        source: "fr",
+        target: "en",
+        path: "fren",
+        table_header: "Principales traductions",
+    },
+    Direction {
+        code: "enfr",
+        source: "en",
+        target: "fr",
+        path: "enfr",
+        table_header: "Principales traductions",
+    },
+    Direction {
+        code: "deen",
+        source: "de",
+        target: "en",
+        path: "deen",
+        table_header: "Hauptübersetzungen",
+    },
+    Direction {
+        code: "ende",
+        source: "en",
+        target: "de",
+        path: "ende",
+        table_header: "Hauptübersetzungen",
+    },
+    Direction {
+        code: "ptes",
+        source: "pt",
+        target: "es",
+        path: "ptes",
+        table_header: "Traduções principais",
+    },
+    Direction {
+        code: "espt",
+        source: "es",
+        target: "pt",
+        path: "espt",
+        table_header: "Traducciones principales",
+    },
+];
+
+pub fn language(code: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.code == code)
+}
+
+pub fn direction(code: &str) -> Option<&'static Direction> {
+    DIRECTIONS.iter().find(|d| d.code == code)
+}
+
+impl Direction {
+    /// The registered direction with source and target swapped.
+    pub fn reversed(&self) -> Option<&'static Direction> {
+        DIRECTIONS
+            .iter()
+            .find(|d| d.source == self.target && d.target == self.source)
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::i18n::{self, Locale};
+use crate::render::escape_html;
+
+/// A possible dictionary form of an inflected word.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LemmaCandidate {
+    pub lemma: String,
+    pub description: String,
+    /// True when the form comes from the bundled inflection list rather than a
+    /// suffix rule. Listed forms are unambiguous enough to look up directly.
+    pub listed: bool,
+}
+
+/// Irregular forms bundled with the binary, one `form\tlemma\tdescription` per line.
+static PT_INFLECTIONS: LazyLock<HashMap<String, Vec<(String, String)>>> =
+    LazyLock::new(|| parse_inflections(include_str!("../data/inflections_pt.tsv")));
+static IT_INFLECTIONS: LazyLock<HashMap<String, Vec<(String, String)>>> =
+    LazyLock::new(|| parse_inflections(include_str!("../data/inflections_it.tsv")));
+
+/// Suffix rules for regular forms: (suffix, replacement, description).
+const PT_RULES: &[(&str, &str, &str)] = &[
+    ("aram", "ar", "3rd pl. preterite"),
+    ("eram", "er", "3rd pl. preterite"),
+    ("iram", "ir", "3rd pl. preterite"),
+    ("avam", "ar", "3rd pl. imperfect"),
+    ("ando", "ar", "gerund"),
+    ("endo", "er", "gerund"),
+    ("indo", "ir", "gerund"),
+    ("ados", "ar", "past participle"),
+    ("adas", "ar", "past participle"),
+    ("idos", "er", "past participle"),
+    ("idos", "ir", "past participle"),
+    ("amos", "ar", "1st pl. present / preterite"),
+    ("emos", "er", "1st pl. present"),
+    ("imos", "ir", "1st pl. present / preterite"),
+    ("ões", "ão", "plural"),
+    ("ães", "ão", "plural"),
+    ("ais", "al", "plural"),
+    ("eis", "el", "plural"),
+    ("óis", "ol", "plural"),
+    ("ava", "ar", "1st/3rd sg. imperfect"),
+    ("ado", "ar", "past participle"),
+    ("ada", "ar", "past participle"),
+    ("ido", "er", "past participle"),
+    ("ido", "ir", "past participle"),
+    ("ia", "er", "1st/3rd sg. imperfect"),
+    ("ia", "ir", "1st/3rd sg. imperfect"),
+    ("ou", "ar", "3rd sg. preterite"),
+    ("ei", "ar", "1st sg. preterite"),
+    ("eu", "er", "3rd sg. preterite"),
+    ("iu", "ir", "3rd sg. preterite"),
+    ("ns", "m", "plural"),
+    ("res", "r", "plural"),
+    ("zes", "z", "plural"),
+    ("s", "", "plural"),
+];
+
+const IT_RULES: &[(&str, &str, &str)] = &[
+    ("arono", "are", "3rd pl. passato remoto"),
+    ("erono", "ere", "3rd pl. passato remoto"),
+    ("irono", "ire", "3rd pl. passato remoto"),
+    ("avano", "are", "3rd pl. imperfect"),
+    ("evano", "ere", "3rd pl. imperfect"),
+    ("ivano", "ire", "3rd pl. imperfect"),
+    ("iamo", "are", "1st pl. present"),
+    ("iamo", "ere", "1st pl. present"),
+    ("iamo", "ire", "1st pl. present"),
+    ("ando", "are", "gerund"),
+    ("endo", "ere", "gerund"),
+    ("endo", "ire", "gerund"),
+    ("ava", "are", "3rd sg. imperfect"),
+    ("eva", "ere", "3rd sg. imperfect"),
+    ("iva", "ire", "3rd sg. imperfect"),
+    ("ato", "are", "past participle"),
+    ("ata", "are", "past participle"),
+    ("ati", "are", "past participle"),
+    ("ate", "are", "past participle"),
+    ("uto", "ere", "past participle"),
+    ("uta", "ere", "past participle"),
+    ("ito", "ire", "past participle"),
+    ("ita", "ire", "past participle"),
+    ("che", "ca", "plural"),
+    ("chi", "co", "plural"),
+    ("ghe", "ga", "plural"),
+    ("ghi", "go", "plural"),
+    ("ò", "are", "3rd sg. passato remoto"),
+    ("ì", "ire", "3rd sg. passato remoto"),
+    ("i", "o", "plural"),
+    ("i", "e", "plural"),
+    ("e", "a", "plural"),
+];
+
+/// Shortest stem a suffix rule may leave behind, to avoid mangling short words.
+const MIN_STEM_CHARS: usize = 2;
+
+fn parse_inflections(data: &str) -> HashMap<String, Vec<(String, String)>> {
+    let mut map: HashMap<String, Vec<(String, String)>> = HashMap::new();
+    for line in data.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split('\t');
+        if let (Some(form), Some(lemma), Some(description)) =
+            (fields.next(), fields.next(), fields.next())
+        {
+            map.entry(form.to_string())
+                .or_default()
+                .push((lemma.to_string(), description.to_string()));
+        }
+    }
+    map
+}
+
+/// Maps an inflected Portuguese (`pt`) or Italian (`it`) word to candidate lemmas.
+///
+/// Forms found in the bundled inflection list come first, followed by guesses
+/// from the suffix rules (longest suffix first). The word itself is never
+/// returned as a candidate. Other languages yield no candidates.
+pub fn lemmatize(word: &str, lang: &str) -> Vec<LemmaCandidate> {
+    let (inflections, rules) = match lang {
+        "pt" => (&*PT_INFLECTIONS, PT_RULES),
+        "it" => (&*IT_INFLECTIONS, IT_RULES),
+        _ => return Vec::new(),
+    };
+
+    let mut candidates: Vec<LemmaCandidate> = Vec::new();
+
+    if let Some(forms) = inflections.get(word) {
+        for (lemma, description) in forms {
+            candidates.push(LemmaCandidate {
+                lemma: lemma.clone(),
+                description: description.clone(),
+                listed: true,
+            });
+        }
+    }
+
+    let mut matching: Vec<&(&str, &str, &str)> = rules
+        .iter()
+        .filter(|(suffix, _, _)| {
+            word.strip_suffix(suffix)
+                .is_some_and(|stem| stem.chars().count() >= MIN_STEM_CHARS)
+        })
+        .collect();
+    // Stable sort keeps the listed order for suffixes of equal length
+    matching.sort_by_key(|(suffix, _, _)| std::cmp::Reverse(suffix.chars().count()));
+
+    for (suffix, replacement, description) in matching {
+        let lemma = format!("{}{}", &word[..word.len() - suffix.len()], replacement);
+        if lemma != word && !candidates.iter().any(|c| c.lemma == lemma) {
+            candidates.push(LemmaCandidate {
+                lemma,
+                description: description.to_string(),
+                listed: false,
+            });
+        }
+    }
+
+    candidates
+}
+
+/// How many suffix-rule lemma guesses to try when a word has no translations.
+pub const MAX_RULE_CANDIDATES: usize = 3;
+
+/// The words a lookup tries in turn: the word itself, then its listed lemmas,
+/// then up to [`MAX_RULE_CANDIDATES`] suffix-rule guesses. Each lemma comes
+/// with the candidate it stands for; the word itself has none.
+///
+/// Forms that are also headwords (`visto`, `feito`) thus get their own entry
+/// before the lemma's.
+pub fn lookup_order(word: &str, lang: &str) -> Vec<(String, Option<LemmaCandidate>)> {
+    let candidates = lemmatize(word, lang);
+    let guesses = candidates
+        .iter()
+        .filter(|c| !c.listed)
+        .take(MAX_RULE_CANDIDATES);
+
+    std::iter::once((word.to_string(), None))
+        .chain(
+            candidates
+                .iter()
+                .filter(|c| c.listed)
+                .chain(guesses)
+                .map(|c| (c.lemma.clone(), Some(c.clone()))),
+        )
+        .collect()
+}
+
+/// Every description a lemma candidate can carry, for checking that the
+/// catalogs translate them all.
+pub fn descriptions() -> Vec<&'static str> {
+    let mut descriptions: Vec<&'static str> = [&*PT_INFLECTIONS, &*IT_INFLECTIONS]
+        .into_iter()
+        .flat_map(|inflections| inflections.values().flatten())
+        .map(|(_, description)| description.as_str())
+        .chain(
+            PT_RULES
+                .iter()
+                .chain(IT_RULES)
+                .map(|(_, _, description)| *description),
+        )
+        .collect();
+    descriptions.sort_unstable();
+    descriptions.dedup();
+    descriptions
+}
+
+/// Formats the note shown above translations of a lemma, e.g.
+/// `fiz → form of fazer (1st sg. preterite)`.
+pub fn format_lemma_note(word: &str, candidate: &LemmaCandidate, locale: Locale) -> String {
+    let note = i18n::t_args(
+        locale,
+        "lemma-note",
+        &[
+            ("form", &escape_html(word)),
+            ("lemma", &escape_html(&candidate.lemma)),
+            (
+                "description",
+                &escape_html(&i18n::inflection_name(locale, &candidate.description)),
+            ),
+        ],
+    );
+    format!("{}\n\n", note)
+}
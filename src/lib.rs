@@ -1,17 +1,42 @@
+pub mod batch;
 pub mod cache_repository;
+pub mod conjugation_repository;
+pub mod conjugations;
 pub mod constants;
+pub mod dictionary_export;
 pub mod entities;
+pub mod export;
 pub mod fetch_translations;
+pub mod group_chat;
+pub mod i18n;
+pub mod keyboards;
+pub mod language_detect;
+pub mod languages;
+pub mod lemmatizer;
+pub mod lookup;
+pub mod message_split;
 pub mod migration;
+pub mod miss_cache;
+pub mod offline_dictionary;
+pub mod offline_index_repository;
+pub mod pronunciation;
+pub mod query;
+pub mod rate_limit;
+pub mod render;
+pub mod reply_tracker;
+pub mod saved_words_repository;
+pub mod service;
+pub mod stats_repository;
 pub mod user_repository;
-
-use constants::{LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
+pub mod variants;
+pub mod warmup;
+pub mod wiktionary;
+pub mod wiktionary_repository;
 
 /// Reverses a translation direction by swapping source and target languages.
 ///
-/// Takes a 4-letter direction code (format: `{source}{target}` where each is 2 letters)
-/// and returns the reversed direction. Returns None if the input is not exactly 4 letters
-/// or if the direction is not in the allowed list (enpt, pten, enit, iten).
+/// Returns None if the direction, or its reverse, is not registered in
+/// [`languages::DIRECTIONS`].
 ///
 /// # Examples
 ///
@@ -22,24 +47,12 @@ use constants::{LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
 /// assert_eq!(flip_direction("enpt"), Some("pten".to_string()));
 /// assert_eq!(flip_direction("iten"), Some("enit".to_string()));
 /// assert_eq!(flip_direction("enit"), Some("iten".to_string()));
-/// assert_eq!(flip_direction("fres"), None); // not in allowed list
-/// assert_eq!(flip_direction("invalid"), None); // not 4 letters
+/// assert_eq!(flip_direction("ptes"), Some("espt".to_string()));
+/// assert_eq!(flip_direction("fres"), None); // not registered
+/// assert_eq!(flip_direction("invalid"), None); // not a direction code
 /// ```
 pub fn flip_direction(current: &str) -> Option<String> {
-    // Direction must be exactly 4 characters
-    if current.len() != 4 {
-        return None;
-    }
-
-    // Only allow specific directions
-    const ALLOWED_DIRECTIONS: [&str; 4] = [LANG_PT_EN, LANG_EN_PT, LANG_IT_EN, LANG_EN_IT];
-    if !ALLOWED_DIRECTIONS.contains(&current) {
-        return None;
-    }
-
-    // Split into two 2-character parts and swap them
-    let source = &current[0..2];
-    let target = &current[2..4];
-
-    Some(format!("{}{}", target, source))
+    languages::direction(current)?
+        .reversed()
+        .map(|d| d.code.to_string())
 }
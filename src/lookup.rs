@@ -0,0 +1,286 @@
+use std::sync::{LazyLock, OnceLock};
+
+use crate::cache_repository::CacheRepository;
+use crate::fetch_translations::{self, TranslationEntry};
+use crate::miss_cache::{MISS_TTL, MissCache};
+use crate::offline_dictionary::{OfflineMode, OfflineProvider};
+use crate::rate_limit::{CircuitBreaker, OUTAGE_BACKOFF};
+use crate::wiktionary::{self, WiktionaryMode, WiktionaryProvider};
+
+pub type LookupError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Local dictionaries consulted alongside WordReference, installed at startup
+/// when any are configured.
+static OFFLINE_PROVIDER: OnceLock<OfflineProvider> = OnceLock::new();
+
+pub fn set_offline_provider(provider: OfflineProvider) {
+    if OFFLINE_PROVIDER.set(provider).is_err() {
+        log::warn!("Offline provider already set");
+    }
+}
+
+/// Imported Wiktionary entries, installed at startup unless `WIKTIONARY_MODE=off`.
+static WIKTIONARY_PROVIDER: OnceLock<WiktionaryProvider> = OnceLock::new();
+
+pub fn set_wiktionary_provider(provider: WiktionaryProvider) {
+    if WIKTIONARY_PROVIDER.set(provider).is_err() {
+        log::warn!("Wiktionary provider already set");
+    }
+}
+
+/// Words WordReference recently had no entry for.
+static MISSES: LazyLock<MissCache> = LazyLock::new(|| MissCache::new(MISS_TTL));
+
+/// Opened when a WordReference request fails, so that lookups during an
+/// outage go straight to the local sources.
+static WORDREFERENCE_BREAKER: LazyLock<CircuitBreaker> =
+    LazyLock::new(|| CircuitBreaker::new(OUTAGE_BACKOFF));
+
+/// Where the answer to a lookup of a word that is not cached would come from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UncachedSource {
+    /// A request to WordReference
+    WordReference,
+    /// An offline dictionary or Wiktionary, without a request
+    Local,
+    /// Nowhere: WordReference is skipped and no local source has the word
+    Nothing,
+}
+
+/// Works out where [`lookup_uncached`] would find a word, without sending a
+/// request, so that only lookups that reach WordReference are rate limited.
+pub async fn uncached_source(word: &str, lang_direction: &str) -> UncachedSource {
+    let offline = OFFLINE_PROVIDER.get();
+
+    if let Some(provider) = offline
+        && provider.mode() == OfflineMode::Primary
+        && lookup_offline(provider, word, lang_direction)
+            .await
+            .is_some()
+    {
+        return UncachedSource::Local;
+    }
+
+    if !skips_wordreference(word, lang_direction) {
+        return UncachedSource::WordReference;
+    }
+
+    if let Some(provider) = offline
+        && provider.mode() == OfflineMode::Fallback
+        && lookup_offline(provider, word, lang_direction)
+            .await
+            .is_some()
+    {
+        return UncachedSource::Local;
+    }
+    if lookup_wiktionary(word, lang_direction).await.is_some() {
+        return UncachedSource::Local;
+    }
+    UncachedSource::Nothing
+}
+
+/// Whether WordReference recently had no entry for the word, or is down.
+fn skips_wordreference(word: &str, lang_direction: &str) -> bool {
+    MISSES.contains(word, lang_direction) || WORDREFERENCE_BREAKER.is_open()
+}
+
+/// Where an article came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArticleSource {
+    WordReference,
+    Offline,
+    Wiktionary,
+}
+
+/// An article for a word that was not cached: the translation table HTML
+/// (empty for other sources), its formatted translations and its rows.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FetchedArticle {
+    pub html: String,
+    pub formatted: String,
+    pub entries: Vec<TranslationEntry>,
+    pub source: ArticleSource,
+}
+
+/// Looks up a word that is not cached, asking WordReference and the offline
+/// dictionaries in the configured order, then Wiktionary. Only WordReference
+/// articles are cached; the local sources are read every time. WordReference
+/// is skipped for words it recently had no entry for, and for a while after
+/// a request to it fails.
+pub async fn lookup_uncached(
+    cache_repo: &CacheRepository,
+    word: &str,
+    lang_direction: &str,
+) -> Result<Option<FetchedArticle>, LookupError> {
+    let offline = OFFLINE_PROVIDER.get();
+
+    if let Some(provider) = offline
+        && provider.mode() == OfflineMode::Primary
+        && let Some(article) = lookup_offline(provider, word, lang_direction).await
+    {
+        return Ok(Some(article));
+    }
+
+    let result = if WORDREFERENCE_BREAKER.is_open() {
+        Err("WordReference is unavailable".into())
+    } else {
+        let result = fetch_and_cache(cache_repo, word, lang_direction).await;
+        match &result {
+            Ok(_) => WORDREFERENCE_BREAKER.record_success(),
+            Err(_) => WORDREFERENCE_BREAKER.record_failure(),
+        }
+        result
+    };
+    if let Ok(Some(_)) = result {
+        return result;
+    }
+
+    if let Some(provider) = offline
+        && provider.mode() == OfflineMode::Fallback
+        && let Some(article) = lookup_offline(provider, word, lang_direction).await
+    {
+        if let Err(e) = &result {
+            log::warn!("WordReference failed for {}, answered offline: {}", word, e);
+        }
+        return Ok(Some(article));
+    }
+
+    if let Some(article) = lookup_wiktionary(word, lang_direction).await {
+        if let Err(e) = &result {
+            log::warn!(
+                "WordReference failed for {}, answered from Wiktionary: {}",
+                word,
+                e
+            );
+        }
+        return Ok(Some(article));
+    }
+    result
+}
+
+/// Looks a word up in the offline dictionaries and Wiktionary only, in
+/// whichever mode they are configured, without asking WordReference.
+pub async fn lookup_local(word: &str, lang_direction: &str) -> Option<FetchedArticle> {
+    if let Some(provider) = OFFLINE_PROVIDER.get()
+        && let Some(article) = lookup_offline(provider, word, lang_direction).await
+    {
+        return Some(article);
+    }
+    lookup_wiktionary(word, lang_direction).await
+}
+
+/// Appends the word's Wiktionary entries to a WordReference article when
+/// `WIKTIONARY_MODE=merge`; otherwise returns the article unchanged.
+pub async fn merge_wiktionary(word: &str, lang_direction: &str, formatted: String) -> String {
+    match WIKTIONARY_PROVIDER.get() {
+        Some(provider) if provider.mode() == WiktionaryMode::Merge => {
+            match lookup_wiktionary(word, lang_direction).await {
+                Some(article) => format!("{}\n{}", formatted, article.formatted),
+                None => formatted,
+            }
+        }
+        _ => formatted,
+    }
+}
+
+async fn lookup_wiktionary(word: &str, lang_direction: &str) -> Option<FetchedArticle> {
+    let provider = WIKTIONARY_PROVIDER.get()?;
+    if provider.mode() == WiktionaryMode::Off {
+        return None;
+    }
+    match provider.lookup(word, lang_direction).await {
+        Ok(entries) if entries.is_empty() => None,
+        Ok(entries) => Some(FetchedArticle {
+            html: String::new(),
+            formatted: wiktionary::format_entries(&entries),
+            entries: wiktionary::translation_entries(&entries),
+            source: ArticleSource::Wiktionary,
+        }),
+        Err(e) => {
+            log::error!(
+                "Wiktionary lookup of {} ({}) failed: {}",
+                word,
+                lang_direction,
+                e
+            );
+            None
+        }
+    }
+}
+
+async fn lookup_offline(
+    provider: &OfflineProvider,
+    word: &str,
+    lang_direction: &str,
+) -> Option<FetchedArticle> {
+    match provider.lookup(word, lang_direction).await {
+        Ok(article) => article.map(|article| FetchedArticle {
+            html: String::new(),
+            formatted: article.to_html(),
+            entries: article.entries(),
+            source: ArticleSource::Offline,
+        }),
+        Err(e) => {
+            log::error!(
+                "Offline lookup of {} ({}) failed: {}",
+                word,
+                lang_direction,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Fetches a word's article from WordReference and caches it.
+///
+/// Returns None when the page has no translations. That is not cached but
+/// remembered for [`MISS_TTL`], during which the word is not fetched again.
+/// The cache is best-effort: a failed write is logged and the article still
+/// returned.
+pub async fn fetch_and_cache(
+    cache_repo: &CacheRepository,
+    word: &str,
+    lang_direction: &str,
+) -> Result<Option<FetchedArticle>, LookupError> {
+    if MISSES.contains(word, lang_direction) {
+        return Ok(None);
+    }
+
+    let body = fetch_translations::fetch(word, lang_direction).await?;
+
+    let html = fetch_translations::get_raw_translations(&body, lang_direction);
+    if html.is_empty() {
+        MISSES.record(word, lang_direction);
+        return Ok(None);
+    }
+
+    let formatted = fetch_translations::get_translations(&html);
+    if formatted.is_empty() {
+        MISSES.record(word, lang_direction);
+        return Ok(None);
+    }
+
+    let pronunciations = fetch_translations::get_pronunciations(&body);
+    let pronunciation = (!pronunciations.is_empty()).then(|| pronunciations.join(", "));
+    if let Err(e) = cache_repo
+        .store_article(
+            word,
+            lang_direction,
+            &html,
+            Some(&formatted),
+            pronunciation.as_deref(),
+        )
+        .await
+    {
+        log::error!("Failed to cache {} ({}): {}", word, lang_direction, e);
+    }
+
+    let entries = fetch_translations::get_translation_entries(&html);
+    Ok(Some(FetchedArticle {
+        html,
+        formatted,
+        entries,
+        source: ArticleSource::WordReference,
+    }))
+}
@@ -1,26 +1,104 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use chrono::Utc;
 use dotenv::dotenv;
+use futures::{StreamExt, stream};
+use pt_dict_bot::batch::{self, BatchItem, BatchOutcome};
+use pt_dict_bot::cache_repository::CacheRepository;
+use pt_dict_bot::conjugation_repository::ConjugationRepository;
+use pt_dict_bot::conjugations;
 use pt_dict_bot::constants::{
-    DEFAULT_LANG_DIRECTION, LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN,
+    AUDIO_CACHE_DIR, DATABASE_PATH, DATABASE_URL, DEFAULT_LANG_DIRECTION, OFFLINE_DATA_DIR,
 };
-use pt_dict_bot::fetch_translations;
+use pt_dict_bot::entities::users;
+use pt_dict_bot::export::{self, VocabularyCard};
+use pt_dict_bot::fetch_translations::{self, TranslationEntry};
 use pt_dict_bot::flip_direction;
+use pt_dict_bot::group_chat::{self, GroupMode};
+use pt_dict_bot::i18n::{self, Locale};
+use pt_dict_bot::keyboards;
+use pt_dict_bot::language_detect;
+use pt_dict_bot::languages;
+use pt_dict_bot::lemmatizer;
+use pt_dict_bot::lookup;
+use pt_dict_bot::message_split;
 use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::offline_dictionary::{OfflineConfig, OfflineProvider};
+use pt_dict_bot::offline_index_repository::OfflineIndexRepository;
+use pt_dict_bot::pronunciation::{self, Synthesizer, TtsConfig};
+use pt_dict_bot::query;
+use pt_dict_bot::rate_limit::{RateLimitConfig, RateLimiter};
+use pt_dict_bot::render;
+use pt_dict_bot::reply_tracker::{REPLY_TTL, ReplyTracker};
+use pt_dict_bot::saved_words_repository::SavedWordsRepository;
+use pt_dict_bot::service::{self, SHUTDOWN_DEADLINE};
+use pt_dict_bot::stats_repository::StatsRepository;
 use pt_dict_bot::user_repository::UserRepository;
+use pt_dict_bot::variants::{self, Variant, VariantPreference};
+use pt_dict_bot::wiktionary::{WiktionaryMode, WiktionaryProvider};
+use pt_dict_bot::wiktionary_repository::WiktionaryRepository;
 use sea_orm_migration::MigratorTrait;
 use teloxide::{
+    ApiError, RequestError,
     prelude::*,
-    types::{ChatKind, ParseMode},
+    types::{
+        BotCommand, BotCommandScope, InlineKeyboardMarkup, InputFile, MessageId, ParseMode,
+        Recipient, ThreadId, User,
+    },
     update_listeners::webhooks,
     utils::command::BotCommands,
 };
 
+/// Commands for the users listed in `ADMIN_USER_IDS`. As with [`Command`],
+/// the descriptions are translated in the catalogs as `cmd-{command}`.
+#[derive(BotCommands, Clone)]
+#[command(rename_rule = "lowercase", description = "Admin commands:")]
+enum AdminCommand {
+    #[command(description = "Show user, lookup and cache statistics")]
+    Stats,
+    #[command(description = "Delete a cached article: /purge <word> [direction]")]
+    Purge(String),
+    #[command(description = "Show a chat's settings row: /user <chat_id>")]
+    User(String),
+    #[command(description = "Send a message to every chat: /broadcast <text>")]
+    Broadcast(String),
+}
+
+/// Pause between broadcast messages, keeping well under Telegram's 30 messages a second.
+const BROADCAST_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Commands for everyone. The descriptions here are the English ones; each
+/// is translated in the catalogs as `cmd-{command}`.
 #[derive(BotCommands, Clone)]
 #[command(rename_rule = "lowercase", description = "Supported commands:")]
 enum Command {
+    #[command(description = "Start here and choose the translation direction")]
+    Start,
+    #[command(description = "Show what I can do")]
+    Help,
     #[command(description = "Toggle translation direction")]
     Flip,
+    #[command(description = "Choose the translation direction")]
+    Settings,
+    #[command(description = "Toggle automatic detection of the word's language")]
+    Autodetect,
+    #[command(description = "Prefer European or Brazilian Portuguese translations")]
+    Variant,
+    #[command(description = "Save a word to your vocabulary")]
+    Save(String),
+    #[command(description = "Export saved words: /export anki or /export csv")]
+    Export(String),
+    #[command(description = "Show the conjugation of a Portuguese or Italian verb")]
+    Conj(String),
+    #[command(description = "Look up a list of words, one per line, as a glossary")]
+    Batch(String),
+    #[command(description = "Look up a word, or reply with /t to pick words from a message")]
+    T(String),
+    #[command(
+        description = "Choose which group messages I answer: /groupmode mention, reply or all"
+    )]
+    Groupmode(String),
 }
 
 #[tokio::main]
@@ -33,14 +111,14 @@ async fn main() {
     {
         use std::fs;
         use std::path::Path;
-        let db_path = Path::new("./cache/translations.db");
+        let db_path = Path::new(DATABASE_PATH);
         if let Some(parent) = db_path.parent() {
             fs::create_dir_all(parent).expect("Failed to create cache directory");
         }
     }
 
     // Connect SeaORM (user configs & cache migrations)
-    let sea_orm_db = sea_orm::Database::connect("sqlite://cache/translations.db")
+    let sea_orm_db = sea_orm::Database::connect(DATABASE_URL)
         .await
         .expect("Failed to connect to database with SeaORM");
 
@@ -49,10 +127,58 @@ async fn main() {
         .await
         .expect("Failed to run migrations");
 
+    let db = sea_orm_db.clone();
     let user_repo = UserRepository::new(sea_orm_db);
-    let cache_repo = pt_dict_bot::cache_repository::CacheRepository::new(user_repo.db.clone());
+    let cache_repo = CacheRepository::new(user_repo.db.clone());
+    let saved_words_repo = SavedWordsRepository::new(user_repo.db.clone());
+    let conjugation_repo = ConjugationRepository::new(user_repo.db.clone());
+    let rate_limit_config = RateLimitConfig::from_env();
+    let admin_ids = rate_limit_config.admin_ids.clone();
+    let limiter = RateLimiter::new(rate_limit_config);
+    let replies = ReplyTracker::new(REPLY_TTL);
+    let stats_repo = StatsRepository::new(user_repo.db.clone());
+
+    let offline_config = OfflineConfig::from_env();
+    if !offline_config.dictionaries.is_empty() {
+        let provider = OfflineProvider::new(
+            OfflineIndexRepository::new(user_repo.db.clone()),
+            offline_config.mode,
+        );
+        match provider
+            .prepare(
+                &offline_config.dictionaries,
+                std::path::Path::new(OFFLINE_DATA_DIR),
+            )
+            .await
+        {
+            Ok(indexed) => log::info!(
+                "{} offline dictionaries configured, {} re-indexed",
+                offline_config.dictionaries.len(),
+                indexed
+            ),
+            Err(e) => log::error!("Failed to prepare offline dictionaries: {}", e),
+        }
+        lookup::set_offline_provider(provider);
+    }
+
+    let wiktionary_mode = WiktionaryMode::from_env();
+    if wiktionary_mode != WiktionaryMode::Off {
+        lookup::set_wiktionary_provider(WiktionaryProvider::new(
+            WiktionaryRepository::new(user_repo.db.clone()),
+            wiktionary_mode,
+        ));
+    }
+
+    let synthesizer =
+        Synthesizer::new(TtsConfig::from_env(), std::path::Path::new(AUDIO_CACHE_DIR));
+    if synthesizer.is_available().await {
+        pronunciation::set_synthesizer(synthesizer);
+    } else {
+        log::info!("espeak-ng or ffmpeg not found, pronunciation audio is disabled");
+    }
 
     let bot = Bot::from_env();
+    register_commands(&bot, &admin_ids).await;
 
     let addr = ([127, 0, 0, 1], 3030).into();
     let webhook_address = std::env::var("WEBHOOK_ADDRESS").expect("WEBHOOK_ADDRESS must be set");
@@ -62,204 +188,2458 @@ async fn main() {
         .await
         .expect("Couldn't setup webhook");
 
-    teloxide::repl_with_listener(
-        bot,
-        move |bot: Bot, msg: Message| {
-            let user_repo = user_repo.clone(); // clone the user repository
-            let cache_repo = cache_repo.clone(); // clone the cache repository
-
-            async move {
-                // Log chat ID and message ID for debugging
-                log::info!(
-                    "Received message in chat {} from user {}",
-                    msg.chat.id,
-                    msg.chat.username().unwrap_or("unknown")
-                );
-                let word = msg.text().unwrap_or("").trim().to_string().to_lowercase();
-                // If word is empty, do nothing
-                if word.is_empty() {
-                    return Ok(());
-                }
+    let handler = dptree::entry()
+        .branch(Update::filter_message().endpoint(handle_message))
+        .branch(Update::filter_edited_message().endpoint(handle_edited_message))
+        .branch(Update::filter_callback_query().endpoint(handle_callback_query));
+
+    let mut dispatcher = Dispatcher::builder(bot, handler)
+        .dependencies(dptree::deps![
+            user_repo,
+            cache_repo,
+            saved_words_repo,
+            conjugation_repo,
+            limiter,
+            stats_repo,
+            replies
+        ])
+        .default_handler(|_upd| Box::pin(async {}))
+        .build();
 
-                let bot_name = bot.get_me().await.unwrap().username.clone();
-                let bot_name_str = format!("@{}", bot_name.as_deref().unwrap_or(""));
-                // If it is a message in the group and the message is NOT addressed to the bot, do nothing
-                if matches!(msg.chat.kind, ChatKind::Public(_)) && !word.starts_with(&bot_name_str)
+    // On SIGTERM or SIGINT the dispatcher stops taking updates and waits for
+    // the handlers already running. The deadline fires if they take too long.
+    let shutdown = dispatcher.shutdown_token();
+    let (deadline_tx, deadline_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let signal = service::shutdown_signal().await;
+        log::info!("Received {}, finishing in-flight updates...", signal);
+        service::notify_stopping();
+        match shutdown.shutdown() {
+            Ok(drained) => {
+                if tokio::time::timeout(SHUTDOWN_DEADLINE, drained)
+                    .await
+                    .is_err()
                 {
-                    return Ok(());
+                    let _ = deadline_tx.send(());
                 }
+            }
+            // The dispatcher is not running yet, so nothing is in flight
+            Err(_) => {
+                let _ = deadline_tx.send(());
+            }
+        }
+    });
 
-                // If it is a message in the group and the message is addressed to the bot, remove the bot's name from the word
-                let word = if matches!(msg.chat.kind, ChatKind::Public(_))
-                    && word.starts_with(&bot_name_str)
-                {
-                    word.replacen(&bot_name_str, "", 1).trim().to_string()
-                } else {
-                    word
-                };
+    service::spawn_watchdog(db.clone());
+    service::notify_ready();
 
-                // Get translation direction from database with DEFAULT_LANG_DIRECTION fallback
-                // Note: chat_id represents chat context (group ID for groups, user ID for private chats)
-                let chat_id = Arc::new(msg.chat.id.to_string());
-
-                if let Ok(cmd) = Command::parse(&word, bot_name.as_deref().unwrap_or("")) {
-                    match cmd {
-                        Command::Flip => {
-                            if let Err(e) = handle_flip_command(
-                                bot.clone(),
-                                msg.clone(),
-                                user_repo.clone(),
-                                chat_id.clone(),
-                            )
-                            .await
-                            {
-                                log::error!("Error in flip command handler: {}", e);
-                            }
-                            return Ok(());
-                        }
-                    }
-                }
+    tokio::select! {
+        _ = dispatcher.dispatch_with_listener(
+            listener,
+            LoggingErrorHandler::with_custom_text("An error from the update listener"),
+        ) => {}
+        Ok(()) = deadline_rx => {
+            log::warn!(
+                "In-flight updates did not finish within {:?}, stopping anyway",
+                SHUTDOWN_DEADLINE
+            );
+        }
+    }
 
-                let chat_translation_direction = match user_repo.get_user(&chat_id).await {
-                    Ok(Some(user)) => user.translation_direction,
-                    _ => DEFAULT_LANG_DIRECTION.to_string(), // Default fallback
-                };
+    if let Err(e) = db.close().await {
+        log::error!("Failed to close the database: {}", e);
+    }
+    log::info!("Bot stopped");
+}
 
-                // Check if cached in DB
-                if let Some(cached) = cache_repo
-                    .get_cached_formatted(&word, &chat_translation_direction)
-                    .await
-                    .unwrap()
+/// Which of Telegram's command menus a list of commands is for.
+#[derive(Clone, Copy, PartialEq)]
+enum MenuScope {
+    Private,
+    Group,
+    GroupAdmin,
+}
+
+impl MenuScope {
+    const ALL: [MenuScope; 3] = [MenuScope::Private, MenuScope::Group, MenuScope::GroupAdmin];
+
+    /// /start only makes sense in private chats, and only group admins can
+    /// use /groupmode.
+    fn shows(self, command: &str) -> bool {
+        match command {
+            "start" => self == MenuScope::Private,
+            "groupmode" => self == MenuScope::GroupAdmin,
+            _ => true,
+        }
+    }
+
+    fn telegram_scope(self) -> BotCommandScope {
+        match self {
+            MenuScope::Private => BotCommandScope::AllPrivateChats,
+            MenuScope::Group => BotCommandScope::AllGroupChats,
+            MenuScope::GroupAdmin => BotCommandScope::AllChatAdministrators,
+        }
+    }
+}
+
+/// Registers the command menus with Telegram in every interface language:
+/// one for private chats, one for group members and one for group admins.
+/// Bot admins also get the admin commands in their private chats. The
+/// English menus are the default for users of other languages.
+async fn register_commands(bot: &Bot, admin_ids: &[u64]) {
+    for locale in Locale::ALL {
+        let language_code = (locale != Locale::English).then(|| locale.code());
+        let mut menus: Vec<(BotCommandScope, Vec<BotCommand>)> = MenuScope::ALL
+            .into_iter()
+            .map(|scope| (scope.telegram_scope(), localized_commands(locale, scope)))
+            .collect();
+        for id in admin_ids {
+            let mut commands = localized_commands(locale, MenuScope::Private);
+            commands.extend(localized_admin_commands(locale));
+            let chat_id = Recipient::Id(ChatId(*id as i64));
+            menus.push((BotCommandScope::Chat { chat_id }, commands));
+        }
+
+        for (scope, commands) in menus {
+            let mut request = bot.set_my_commands(commands).scope(scope);
+            if let Some(code) = language_code {
+                request = request.language_code(code);
+            }
+            if let Err(e) = request.await {
+                log::error!("Failed to register {} commands: {}", locale.code(), e);
+            }
+        }
+    }
+}
+
+/// The commands of a menu, described in the given language.
+fn localized_commands(locale: Locale, scope: MenuScope) -> Vec<BotCommand> {
+    Command::bot_commands()
+        .into_iter()
+        .map(|cmd| cmd.command.trim_start_matches('/').to_string())
+        .filter(|name| scope.shows(name))
+        .map(|name| {
+            let description = i18n::t(locale, &format!("cmd-{}", name));
+            BotCommand::new(name, description)
+        })
+        .collect()
+}
+
+/// The admin commands, described in the given language.
+fn localized_admin_commands(locale: Locale) -> Vec<BotCommand> {
+    AdminCommand::bot_commands()
+        .into_iter()
+        .map(|cmd| {
+            let name = cmd.command.trim_start_matches('/').to_string();
+            let description = i18n::t(locale, &format!("cmd-{}", name));
+            BotCommand::new(name, description)
+        })
+        .collect()
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_message(
+    bot: Bot,
+    msg: Message,
+    user_repo: UserRepository,
+    cache_repo: CacheRepository,
+    saved_words_repo: SavedWordsRepository,
+    conjugation_repo: ConjugationRepository,
+    limiter: RateLimiter,
+    stats_repo: StatsRepository,
+    replies: ReplyTracker,
+) -> ResponseResult<()> {
+    // Log chat ID and message ID for debugging
+    log::info!(
+        "Received message in chat {} from user {}",
+        msg.chat.id,
+        msg.chat.username().unwrap_or("unknown")
+    );
+    let text = msg.text().unwrap_or("").trim();
+    // If word is empty, do nothing
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let me = bot.get_me().await?;
+    let bot_name = me.username.clone();
+    let bot_username = bot_name.as_deref().unwrap_or("");
+
+    // In groups (private supergroups included) the bot's mentions, wherever they
+    // are in the text, are removed from the lookup
+    let is_group = !msg.chat.is_private();
+    let mention = if is_group {
+        group_chat::strip_bot_mentions(
+            msg.text().unwrap_or(""),
+            msg.entities().unwrap_or(&[]),
+            bot_username,
+            me.id,
+        )
+    } else {
+        None
+    };
+    let word = mention.as_deref().unwrap_or(text).to_lowercase();
+
+    let command = Command::parse(&word, bot_username);
+    if is_group {
+        let is_command = command.is_ok() || AdminCommand::parse(text, bot_username).is_ok();
+        let replies_to_bot = group_chat::replied_message(&msg)
+            .and_then(|m| m.from.as_ref())
+            .is_some_and(|u| u.id == me.id);
+        let mode = get_group_mode(&user_repo, &msg.chat.id.to_string()).await;
+        // If the message is NOT addressed to the bot under the group's mode, do nothing
+        if !group_chat::should_respond(mode, mention.is_some(), replies_to_bot, is_command) {
+            return Ok(());
+        }
+    }
+    let translate_command = command.ok().and_then(|cmd| match cmd {
+        Command::T(args) => Some(args.trim().to_string()),
+        _ => None,
+    });
+
+    // Get translation direction from database with DEFAULT_LANG_DIRECTION fallback
+    // Note: chat_id represents chat context (group ID for groups, user ID for private chats)
+    let chat_id = Arc::new(msg.chat.id.to_string());
+    let locale = get_locale(&user_repo, &chat_id, msg.from.as_ref()).await;
+
+    // A bare mention or /t in reply to a message offers that message's words;
+    // /t with a word is a plain lookup
+    let word = match translate_command {
+        Some(args) if !args.is_empty() => args,
+        Some(_) => {
+            if let Err(e) = send_word_picker(&bot, &msg, locale).await {
+                log::error!("Error in translate command handler: {}", e);
+            }
+            return Ok(());
+        }
+        None if word.is_empty() && group_chat::replied_message(&msg).is_some() => {
+            if let Err(e) = send_word_picker(&bot, &msg, locale).await {
+                log::error!("Error in translate command handler: {}", e);
+            }
+            return Ok(());
+        }
+        None => word,
+    };
+
+    // Admin commands are parsed from the original text so /broadcast keeps its case
+    if let Ok(cmd) = AdminCommand::parse(text, bot_username) {
+        if !limiter.is_admin(msg.from.as_ref().map(|u| u.id.0)) {
+            reply_to(&bot, &msg, i18n::t(locale, "admins-only")).await?;
+            return Ok(());
+        }
+        if let Err(e) = handle_admin_command(
+            bot.clone(),
+            msg.clone(),
+            &user_repo,
+            &cache_repo,
+            &stats_repo,
+            cmd,
+            locale,
+        )
+        .await
+        {
+            log::error!("Error in admin command handler: {}", e);
+        }
+        return Ok(());
+    }
+
+    if let Ok(cmd) = Command::parse(&word, bot_username) {
+        match cmd {
+            Command::Start => {
+                if let Err(e) = handle_start_command(&bot, &msg, &user_repo, &chat_id, locale).await
                 {
-                    bot.send_message(msg.chat.id, cached)
-                        .parse_mode(ParseMode::Html)
-                        .await?;
-                    return Ok(());
+                    log::error!("Error in start command handler: {}", e);
                 }
-
-                // check if cached raw HTML exists without formatted translation
-                if let Some(cached_html) = cache_repo
-                    .get_cached_html(&word, &chat_translation_direction)
-                    .await
-                    .unwrap()
+                return Ok(());
+            }
+            Command::Help => {
+                let is_admin = limiter.is_admin(msg.from.as_ref().map(|u| u.id.0));
+                if let Err(e) = reply_to(&bot, &msg, help_text(&msg, is_admin, locale)).await {
+                    log::error!("Error in help command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Flip => {
+                if let Err(e) = handle_flip_command(
+                    bot.clone(),
+                    msg.clone(),
+                    user_repo.clone(),
+                    chat_id.clone(),
+                    locale,
+                )
+                .await
                 {
-                    let translations = fetch_translations::get_translations(&cached_html);
-                    // Store the formatted translation in the database
-                    let _ = cache_repo
-                        .update_formatted(&word, &chat_translation_direction, &translations)
-                        .await;
-                    bot.send_message(msg.chat.id, translations)
-                        .parse_mode(ParseMode::Html)
-                        .await?;
-                    return Ok(());
+                    log::error!("Error in flip command handler: {}", e);
                 }
+                return Ok(());
+            }
+            Command::Settings => {
+                if let Err(e) =
+                    handle_settings_command(bot.clone(), msg.clone(), &user_repo, &chat_id, locale)
+                        .await
+                {
+                    log::error!("Error in settings command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Autodetect => {
+                if let Err(e) = handle_autodetect_command(
+                    bot.clone(),
+                    msg.clone(),
+                    &user_repo,
+                    &chat_id,
+                    locale,
+                )
+                .await
+                {
+                    log::error!("Error in autodetect command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Variant => {
+                if let Err(e) =
+                    handle_variant_command(bot.clone(), msg.clone(), &user_repo, &chat_id, locale)
+                        .await
+                {
+                    log::error!("Error in variant command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Save(save_word) => {
+                if let Err(e) = handle_save_command(
+                    bot.clone(),
+                    msg.clone(),
+                    &user_repo,
+                    &cache_repo,
+                    &saved_words_repo,
+                    &limiter,
+                    save_word.trim(),
+                    locale,
+                )
+                .await
+                {
+                    log::error!("Error in save command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Export(format) => {
+                if let Err(e) = handle_export_command(
+                    bot.clone(),
+                    msg.clone(),
+                    &cache_repo,
+                    &saved_words_repo,
+                    &chat_id,
+                    format.trim(),
+                    locale,
+                )
+                .await
+                {
+                    log::error!("Error in export command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Conj(verb) => {
+                if let Err(e) = handle_conj_command(
+                    bot.clone(),
+                    msg.clone(),
+                    &user_repo,
+                    &conjugation_repo,
+                    &limiter,
+                    &chat_id,
+                    verb.trim(),
+                    locale,
+                )
+                .await
+                {
+                    log::error!("Error in conj command handler: {}", e);
+                }
+                return Ok(());
+            }
+            // Handled above, before any other command
+            Command::T(_) => return Ok(()),
+            Command::Groupmode(mode) => {
+                if let Err(e) =
+                    handle_group_mode_command(&bot, &msg, &user_repo, &chat_id, mode.trim(), locale)
+                        .await
+                {
+                    log::error!("Error in group mode command handler: {}", e);
+                }
+                return Ok(());
+            }
+            Command::Batch(words) => {
+                if let Err(e) = handle_batch_command(
+                    bot.clone(),
+                    msg.clone(),
+                    &user_repo,
+                    &cache_repo,
+                    &limiter,
+                    &stats_repo,
+                    &words,
+                    locale,
+                )
+                .await
+                {
+                    log::error!("Error in batch command handler: {}", e);
+                }
+                return Ok(());
+            }
+        }
+    }
 
-                // Not cached, fetch
-                let body = fetch_translations::fetch(&word, &chat_translation_direction).await;
-
-                let raw_translations =
-                    fetch_translations::get_raw_translations(&body, &chat_translation_direction);
+    // A message with several lines is a batch of separate lookups
+    let (queries, dropped) = query::split_queries(&word);
+    if queries.len() > 1 {
+        let auto_detect = user_repo
+            .get_user(&chat_id)
+            .await
+            .ok()
+            .flatten()
+            .is_some_and(|u| u.auto_detect);
+        let chat_translation_direction =
+            get_lookup_direction(&user_repo, &chat_id, msg.from.as_ref()).await;
+        send_batch_lookup(
+            &bot,
+            &msg,
+            &cache_repo,
+            &limiter,
+            &stats_repo,
+            &queries,
+            dropped,
+            &chat_translation_direction,
+            auto_detect,
+            locale,
+        )
+        .await?;
+        return Ok(());
+    }
 
-                if raw_translations.is_empty() {
-                    bot.send_message(msg.chat.id, "No translations found.")
-                        .parse_mode(ParseMode::Html)
-                        .await?;
-                    return Ok(());
-                }
+    let Some((text, keyboard)) = lookup_reply(
+        &msg,
+        &user_repo,
+        &cache_repo,
+        &limiter,
+        &stats_repo,
+        &word,
+        locale,
+    )
+    .await
+    else {
+        reply_to(&bot, &msg, i18n::t(locale, "slow-down")).await?;
+        return Ok(());
+    };
 
-                // Store fetched HTML and formatted translation
+    let reply = send_html(&bot, &msg, &text, keyboard).await?;
+    // Editing the message later updates this reply
+    replies.record(msg.chat.id.0, msg.id.0, reply.id.0);
 
-                let translations = fetch_translations::get_translations(&raw_translations);
+    Ok(())
+}
 
-                if translations.is_empty() {
-                    bot.send_message(msg.chat.id, "No translations found.")
-                        .parse_mode(ParseMode::Html)
-                        .await?;
-                    return Ok(());
-                }
+/// Looks a single query up for the sender of `msg` and renders the first page
+/// of the reply, following the chat's auto-detection and variant settings.
+/// Returns None when the lookup was refused by the rate limiter.
+async fn lookup_reply(
+    msg: &Message,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    stats_repo: &StatsRepository,
+    word: &str,
+    locale: Locale,
+) -> Option<(String, Option<InlineKeyboardMarkup>)> {
+    let chat_id = msg.chat.id.to_string();
+    let chat = user_repo.get_user(&chat_id).await.ok().flatten();
+    let auto_detect = chat.as_ref().is_some_and(|u| u.auto_detect);
+    let variant = chat
+        .as_ref()
+        .map(VariantPreference::from_user)
+        .unwrap_or_default();
+    let chat_translation_direction =
+        get_lookup_direction(user_repo, &chat_id, msg.from.as_ref()).await;
 
-                // store in DB
-                let _ = cache_repo
-                    .insert_html(&word, &chat_translation_direction, &raw_translations)
-                    .await;
-                let _ = cache_repo
-                    .update_formatted(&word, &chat_translation_direction, &translations)
-                    .await;
+    let word = query::normalize_query(word);
+    let direction = if auto_detect {
+        detect_lookup_direction(cache_repo, &word, &chat_translation_direction).await
+    } else {
+        chat_translation_direction
+    };
 
-                bot.send_message(msg.chat.id, translations)
-                    .parse_mode(ParseMode::Html)
-                    .await?;
+    // Only lookups that reach WordReference count against the limits
+    let payer = Payer {
+        limiter,
+        chat_id: msg.chat.id,
+        user_id: msg.from.as_ref().map(|u| u.id.0),
+    };
+    let cache_hit = !needs_fetch(cache_repo, &word, &direction).await;
+    if !cache_hit && !payer.charge() {
+        return None;
+    }
+    if let Err(e) = stats_repo.record_lookup(cache_hit).await {
+        log::error!("Failed to record lookup stats: {}", e);
+    }
 
-                Ok(())
-            }
-        },
-        listener,
+    let full = render_lookup(
+        cache_repo,
+        payer,
+        &word,
+        &direction,
+        auto_detect,
+        variant,
+        locale,
     )
     .await;
+    Some(keyboards::reply_page(
+        &word,
+        &direction,
+        auto_detect,
+        &full,
+        0,
+        locale,
+    ))
 }
 
-/// Handles the /flip command by toggling user's translation direction.
-/// Logs errors and falls back to default silently.
-async fn handle_flip_command(
+/// Handles an edited message the bot answered recently: looks the corrected
+/// text up again and edits the earlier reply in place. Edits of other
+/// messages, and edits turning a lookup into a command or a list, are ignored.
+async fn handle_edited_message(
     bot: Bot,
     msg: Message,
     user_repo: UserRepository,
-    chat_id: Arc<String>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (current_direction, user_exists) = match user_repo.get_user(&chat_id).await {
-        Ok(Some(user)) => (user.translation_direction, true),
-        Ok(None) => (DEFAULT_LANG_DIRECTION.to_string(), false),
-        Err(e) => {
-            log::error!("Database error getting user for flip command: {}", e);
-            (DEFAULT_LANG_DIRECTION.to_string(), false)
+    cache_repo: CacheRepository,
+    limiter: RateLimiter,
+    stats_repo: StatsRepository,
+    replies: ReplyTracker,
+) -> ResponseResult<()> {
+    let Some(reply_id) = replies.get(msg.chat.id.0, msg.id.0) else {
+        return Ok(());
+    };
+    let text = msg.text().unwrap_or("").trim();
+
+    let me = bot.get_me().await?;
+    let bot_name = me.username.clone();
+    let bot_username = bot_name.as_deref().unwrap_or("");
+    let mention = if msg.chat.is_private() {
+        None
+    } else {
+        group_chat::strip_bot_mentions(
+            msg.text().unwrap_or(""),
+            msg.entities().unwrap_or(&[]),
+            bot_username,
+            me.id,
+        )
+    };
+    let word = mention.as_deref().unwrap_or(text).to_lowercase();
+
+    let word = match Command::parse(&word, bot_username) {
+        Ok(Command::T(args)) if !args.trim().is_empty() => args.trim().to_string(),
+        Ok(_) => return Ok(()),
+        Err(_) => word,
+    };
+    if word.is_empty() || query::split_queries(&word).0.len() > 1 {
+        return Ok(());
+    }
+
+    let locale = get_locale(&user_repo, &msg.chat.id.to_string(), msg.from.as_ref()).await;
+    let Some((text, keyboard)) = lookup_reply(
+        &msg,
+        &user_repo,
+        &cache_repo,
+        &limiter,
+        &stats_repo,
+        &word,
+        locale,
+    )
+    .await
+    else {
+        reply_to(&bot, &msg, i18n::t(locale, "slow-down")).await?;
+        return Ok(());
+    };
+
+    match edit_html(&bot, msg.chat.id, MessageId(reply_id), &text, keyboard).await {
+        // An edit that doesn't change the lookup leaves the reply as it is
+        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Looks up the words of a batch concurrently and answers with a compact
+/// glossary plus a CSV of each word's top translations. Words that could not
+/// be looked up, or that the rate limits had no tokens left for, are listed at
+/// the end instead of failing the whole batch, and `dropped` lines past the
+/// batch limit are mentioned.
+#[allow(clippy::too_many_arguments)]
+async fn send_batch_lookup(
+    bot: &Bot,
+    msg: &Message,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    stats_repo: &StatsRepository,
+    queries: &[String],
+    dropped: usize,
+    stored_direction: &str,
+    auto_detect: bool,
+    locale: Locale,
+) -> ResponseResult<()> {
+    let chat_id = msg.chat.id;
+
+    // Each word that is not cached in a direction it may be looked up in costs a token
+    let reversed = flip_direction(stored_direction).filter(|_| auto_detect);
+    let mut uncached_words = Vec::with_capacity(queries.len());
+    for word in queries {
+        let mut uncached = needs_fetch(cache_repo, word, stored_direction).await;
+        if let Some(reversed) = &reversed {
+            uncached = uncached && needs_fetch(cache_repo, word, reversed).await;
         }
+        uncached_words.push(uncached);
+    }
+    let payer = Payer {
+        limiter,
+        chat_id,
+        user_id: msg.from.as_ref().map(|u| u.id.0),
     };
+    // A batch bigger than the buckets is looked up as far as they go
+    let cost = uncached_words.iter().filter(|u| **u).count() as u32;
+    let mut allowance = limiter.take_up_to(chat_id.0, payer.user_id, cost);
+    if cost > 0 && allowance == 0 && uncached_words.iter().all(|u| *u) {
+        reply_to(bot, msg, i18n::t(locale, "slow-down")).await?;
+        return Ok(());
+    }
+    let mut allowed = Vec::with_capacity(queries.len());
+    for uncached in uncached_words {
+        let paid = !uncached || allowance > 0;
+        if uncached && paid {
+            allowance -= 1;
+        }
+        if paid && let Err(e) = stats_repo.record_lookup(!uncached).await {
+            log::error!("Failed to record lookup stats: {}", e);
+        }
+        allowed.push(paid);
+    }
 
-    let new_direction = match flip_direction(&current_direction) {
-        Some(dir) => dir,
-        None => {
+    let items: Vec<BatchItem> = stream::iter(queries.iter().cloned().zip(allowed))
+        .map(|(word, allowed)| async move {
+            if !allowed {
+                return BatchItem {
+                    word,
+                    lang_direction: stored_direction.to_string(),
+                    outcome: BatchOutcome::RateLimited,
+                };
+            }
+            let direction = if auto_detect {
+                detect_lookup_direction(cache_repo, &word, stored_direction).await
+            } else {
+                stored_direction.to_string()
+            };
+            let outcome = match lookup_batch_word(cache_repo, payer, &word, &direction).await {
+                Ok(Some((translations, lemma))) => BatchOutcome::Found {
+                    translations,
+                    lemma,
+                },
+                Ok(None) => BatchOutcome::NotFound,
+                Err(e) => {
+                    log::error!("Batch lookup of {} failed: {}", word, e);
+                    BatchOutcome::Failed(e.to_string())
+                }
+            };
+            BatchItem {
+                word,
+                lang_direction: direction,
+                outcome,
+            }
+        })
+        .buffered(batch::MAX_CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+
+    let glossary = batch::format_glossary(&items, dropped, locale);
+    for chunk in message_split::split_message(&glossary, message_split::TELEGRAM_MESSAGE_LIMIT) {
+        send_html(bot, msg, &chunk, None).await?;
+    }
+
+    if items
+        .iter()
+        .any(|item| matches!(item.outcome, BatchOutcome::Found { .. }))
+    {
+        document_to(
+            bot,
+            msg,
+            InputFile::memory(batch::build_glossary_csv(&items)).file_name("glossary.csv"),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Top translations of one batch word, through the cache and then
+/// WordReference. Inflected forms fall back to their lemmas like single lookups;
+/// the lemma is returned when it was used.
+async fn lookup_batch_word(
+    cache_repo: &CacheRepository,
+    payer: Payer<'_>,
+    word: &str,
+    lang_direction: &str,
+) -> Result<Option<(Vec<String>, Option<String>)>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut fetched = false;
+    for (lookup, candidate) in lemmatizer::lookup_order(word, source_language(lang_direction)) {
+        if !may_fetch(cache_repo, payer, &lookup, lang_direction, &mut fetched).await {
+            break;
+        }
+        let entries = lookup_entries(cache_repo, &lookup, lang_direction).await?;
+        let translations = batch::top_translations(&entries, batch::TOP_TRANSLATIONS);
+        if !translations.is_empty() {
+            return Ok(Some((translations, candidate.map(|c| c.lemma))));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Structured translation rows of a word, fetching and caching its article
+/// when it is not cached yet.
+async fn lookup_entries(
+    cache_repo: &CacheRepository,
+    word: &str,
+    lang_direction: &str,
+) -> Result<Vec<TranslationEntry>, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(html) = cache_repo.get_cached_html(word, lang_direction).await? {
+        return Ok(fetch_translations::get_translation_entries(&html));
+    }
+
+    Ok(lookup::lookup_uncached(cache_repo, word, lang_direction)
+        .await?
+        .map(|article| article.entries)
+        .unwrap_or_default())
+}
+
+/// The forum topic a message was sent in, so replies land in the same topic.
+fn topic_thread(msg: &Message) -> Option<ThreadId> {
+    msg.thread_id.filter(|_| msg.is_topic_message)
+}
+
+/// Starts a message to the chat of `msg`, in the same forum topic.
+fn reply_to(bot: &Bot, msg: &Message, text: impl Into<String>) -> <Bot as Requester>::SendMessage {
+    let request = bot.send_message(msg.chat.id, text);
+    match topic_thread(msg) {
+        Some(thread) => request.message_thread_id(thread),
+        None => request,
+    }
+}
+
+/// Starts a document to the chat of `msg`, in the same forum topic.
+fn document_to(bot: &Bot, msg: &Message, document: InputFile) -> <Bot as Requester>::SendDocument {
+    let request = bot.send_document(msg.chat.id, document);
+    match topic_thread(msg) {
+        Some(thread) => request.message_thread_id(thread),
+        None => request,
+    }
+}
+
+/// Sends an HTML message to the chat (and forum topic) of `msg`. When Telegram
+/// rejects the markup, the message is resent as plain text so the user still
+/// gets a reply.
+async fn send_html(
+    bot: &Bot,
+    msg: &Message,
+    text: &str,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> ResponseResult<Message> {
+    let mut request = reply_to(bot, msg, text).parse_mode(ParseMode::Html);
+    if let Some(keyboard) = keyboard.clone() {
+        request = request.reply_markup(keyboard);
+    }
+
+    match request.await {
+        Err(RequestError::Api(ApiError::CantParseEntities(error))) => {
             log::warn!(
-                "Cannot flip invalid direction '{}' for chat {}. Using flipped default.",
-                current_direction,
-                chat_id
+                "Telegram rejected HTML ({}), resending as plain text",
+                error
             );
-            flip_direction(DEFAULT_LANG_DIRECTION)
-                .unwrap_or_else(|| DEFAULT_LANG_DIRECTION.to_string())
+            let mut request = reply_to(bot, msg, render::to_plain_text(text));
+            if let Some(keyboard) = keyboard {
+                request = request.reply_markup(keyboard);
+            }
+            request.await
         }
-    };
+        result => result,
+    }
+}
 
-    let update_result = if user_exists {
-        user_repo
-            .update_translation_direction(&chat_id, &new_direction)
-            .await
-    } else {
-        let user_id = msg.from.as_ref().map(|u| u.id.0 as i64);
-        let username = msg.from.as_ref().and_then(|u| u.username.clone());
-        user_repo
-            .create_or_update_user(&chat_id, &new_direction, user_id, username.as_deref())
-            .await
+/// Edits a message to new HTML text, falling back to plain text like `send_html`.
+async fn edit_html(
+    bot: &Bot,
+    chat_id: ChatId,
+    message_id: MessageId,
+    text: &str,
+    keyboard: Option<InlineKeyboardMarkup>,
+) -> ResponseResult<Message> {
+    let mut request = bot
+        .edit_message_text(chat_id, message_id, text)
+        .parse_mode(ParseMode::Html);
+    if let Some(keyboard) = keyboard.clone() {
+        request = request.reply_markup(keyboard);
+    }
+
+    match request.await {
+        Err(RequestError::Api(ApiError::CantParseEntities(error))) => {
+            log::warn!("Telegram rejected HTML ({}), editing as plain text", error);
+            let mut request =
+                bot.edit_message_text(chat_id, message_id, render::to_plain_text(text));
+            if let Some(keyboard) = keyboard {
+                request = request.reply_markup(keyboard);
+            }
+            request.await
+        }
+        result => result,
+    }
+}
+
+/// Returns the chat's stored translation direction, falling back to DEFAULT_LANG_DIRECTION.
+async fn get_chat_direction(user_repo: &UserRepository, chat_id: &str) -> String {
+    match user_repo.get_user(chat_id).await {
+        Ok(Some(user)) => user
+            .translation_direction
+            .unwrap_or_else(|| DEFAULT_LANG_DIRECTION.to_string()),
+        _ => DEFAULT_LANG_DIRECTION.to_string(), // Default fallback
+    }
+}
+
+/// Returns the direction a user's lookups in a chat use: their own setting in
+/// the chat, the chat's, their global one, then DEFAULT_LANG_DIRECTION.
+async fn get_lookup_direction(
+    user_repo: &UserRepository,
+    chat_id: &str,
+    from: Option<&User>,
+) -> String {
+    match user_repo
+        .resolve_direction(chat_id, from.map(|u| u.id.0 as i64))
+        .await
+    {
+        Ok(direction) => direction,
+        Err(e) => {
+            log::error!(
+                "Database error resolving direction for chat {}: {}",
+                chat_id,
+                e
+            );
+            DEFAULT_LANG_DIRECTION.to_string()
+        }
+    }
+}
+
+/// The language to answer in: the chat's choice from /settings, otherwise the
+/// user's Telegram language.
+async fn get_locale(user_repo: &UserRepository, chat_id: &str, from: Option<&User>) -> Locale {
+    let chosen = match user_repo.get_user(chat_id).await {
+        Ok(Some(user)) => user.locale.as_deref().and_then(Locale::from_code),
+        _ => None,
     };
+    chosen.unwrap_or_else(|| {
+        Locale::from_language_code(from.and_then(|u| u.language_code.as_deref()))
+    })
+}
 
-    match update_result {
-        Ok(_) => {
-            let direction_name = match new_direction.as_str() {
-                LANG_PT_EN => "Portuguese → English",
-                LANG_EN_PT => "English → Portuguese",
-                LANG_IT_EN => "Italian → English",
-                LANG_EN_IT => "English → Italian",
-                _ => &new_direction,
-            };
+async fn get_group_mode(user_repo: &UserRepository, chat_id: &str) -> GroupMode {
+    match user_repo.get_user(chat_id).await {
+        Ok(Some(user)) => GroupMode::from_code(&user.group_mode).unwrap_or_default(),
+        _ => GroupMode::default(),
+    }
+}
 
-            bot.send_message(
-                msg.chat.id,
-                format!("✅ Translation direction changed to: {}", direction_name),
-            )
-            .await?;
+async fn get_chat_variant(user_repo: &UserRepository, chat_id: &str) -> VariantPreference {
+    match user_repo.get_user(chat_id).await {
+        Ok(Some(user)) => VariantPreference::from_user(&user),
+        _ => VariantPreference::default(),
+    }
+}
+
+/// Looks a word up through the cache, fetching and caching it on a miss.
+/// Returns the formatted translations, or None when nothing was found.
+async fn lookup_translations(
+    cache_repo: &CacheRepository,
+    word: &str,
+    lang_direction: &str,
+) -> Option<String> {
+    // Check if cached in DB
+    if let Some(cached) = cache_repo
+        .get_cached_formatted(word, lang_direction)
+        .await
+        .unwrap()
+    {
+        return Some(lookup::merge_wiktionary(word, lang_direction, cached).await);
+    }
+
+    // check if cached raw HTML exists without formatted translation
+    if let Some(cached_html) = cache_repo
+        .get_cached_html(word, lang_direction)
+        .await
+        .unwrap()
+    {
+        let translations = fetch_translations::get_translations(&cached_html);
+        // Store the formatted translation in the database
+        let _ = cache_repo
+            .update_formatted(word, lang_direction, &translations)
+            .await;
+        return Some(lookup::merge_wiktionary(word, lang_direction, translations).await);
+    }
+
+    // Not cached, fetch
+    match lookup::lookup_uncached(cache_repo, word, lang_direction).await {
+        Ok(Some(article)) if article.source == lookup::ArticleSource::WordReference => {
+            Some(lookup::merge_wiktionary(word, lang_direction, article.formatted).await)
         }
+        Ok(article) => article.map(|a| a.formatted),
         Err(e) => {
-            log::error!("Failed to update direction for chat {}: {}", chat_id, e);
+            log::error!("Failed to fetch {} ({}): {}", word, lang_direction, e);
+            None
         }
     }
+}
+
+/// The language a direction translates from, empty for unknown directions.
+fn source_language(lang_direction: &str) -> &'static str {
+    languages::direction(lang_direction)
+        .map(|d| d.source)
+        .unwrap_or("")
+}
+
+/// The chat and user whose rate limits pay for a lookup's requests to
+/// WordReference.
+#[derive(Clone, Copy)]
+struct Payer<'a> {
+    limiter: &'a RateLimiter,
+    chat_id: ChatId,
+    user_id: Option<u64>,
+}
+
+impl Payer<'_> {
+    /// Takes a token for one more request, or returns false when refused.
+    fn charge(&self) -> bool {
+        self.limiter.check(self.chat_id.0, self.user_id, 1)
+    }
+}
 
-    Ok(())
+/// Whether a word of a lookup order may be looked up. The first request to
+/// WordReference is paid for before the lookup starts (see [`needs_fetch`]);
+/// each further one takes a token of its own, and the walk stops once the
+/// rate limiter refuses.
+async fn may_fetch(
+    cache_repo: &CacheRepository,
+    payer: Payer<'_>,
+    lookup: &str,
+    lang_direction: &str,
+    fetched: &mut bool,
+) -> bool {
+    if matches!(
+        cache_repo.get_cached_html(lookup, lang_direction).await,
+        Ok(Some(_))
+    ) || lookup::uncached_source(lookup, lang_direction).await
+        != lookup::UncachedSource::WordReference
+    {
+        return true;
+    }
+    if !*fetched {
+        *fetched = true;
+        return true;
+    }
+    payer.charge()
+}
+
+/// Whether looking a word up would reach WordReference: going through the
+/// word and its lemmas in lookup order, one that would be fetched comes before
+/// any that is cached or answered by a local source. Words WordReference
+/// recently had no entry for are skipped, so a form without an entry of its
+/// own costs nothing once its lemma is cached, and nothing is fetched while
+/// WordReference is down.
+async fn needs_fetch(cache_repo: &CacheRepository, word: &str, lang_direction: &str) -> bool {
+    for (lookup, _) in lemmatizer::lookup_order(word, source_language(lang_direction)) {
+        if matches!(
+            cache_repo.get_cached_html(&lookup, lang_direction).await,
+            Ok(Some(_))
+        ) {
+            return false;
+        }
+        match lookup::uncached_source(&lookup, lang_direction).await {
+            lookup::UncachedSource::WordReference => return true,
+            lookup::UncachedSource::Local => return false,
+            lookup::UncachedSource::Nothing => {}
+        }
+    }
+    false
+}
+
+/// Looks a word up, resolving inflected forms to their lemma.
+///
+/// The word itself is tried first, then its listed lemmas and suffix-rule
+/// guesses, so forms that are also headwords keep their own entry. Lemma
+/// results are prefixed with a note naming the form. Each request to
+/// WordReference after the first is charged to `payer`.
+async fn lookup_with_lemmas(
+    cache_repo: &CacheRepository,
+    payer: Payer<'_>,
+    word: &str,
+    lang_direction: &str,
+    locale: Locale,
+) -> Option<String> {
+    let mut fetched = false;
+    for (lookup, candidate) in lemmatizer::lookup_order(word, source_language(lang_direction)) {
+        if !may_fetch(cache_repo, payer, &lookup, lang_direction, &mut fetched).await {
+            break;
+        }
+        if let Some(translations) = lookup_translations(cache_repo, &lookup, lang_direction).await {
+            return Some(match candidate {
+                Some(candidate) => {
+                    lemmatizer::format_lemma_note(word, &candidate, locale) + &translations
+                }
+                None => translations,
+            });
+        }
+    }
+
+    None
+}
+
+/// Picks the lookup direction when auto-detection is on. A word cached in only
+/// one of the two directions uses that one; otherwise the local classifier
+/// decides between the stored direction and its reverse.
+async fn detect_lookup_direction(cache_repo: &CacheRepository, word: &str, stored: &str) -> String {
+    let Some(stored_direction) = languages::direction(stored) else {
+        return stored.to_string();
+    };
+    let Some(reversed) = stored_direction.reversed() else {
+        return stored.to_string();
+    };
+
+    let in_stored = matches!(cache_repo.get_cached_html(word, stored).await, Ok(Some(_)));
+    let in_reversed = matches!(
+        cache_repo.get_cached_html(word, reversed.code).await,
+        Ok(Some(_))
+    );
+
+    match (in_stored, in_reversed) {
+        (true, false) => stored.to_string(),
+        (false, true) => reversed.code.to_string(),
+        _ => language_detect::detect_direction(word, stored_direction)
+            .code
+            .to_string(),
+    }
+}
+
+/// Renders the full reply to a lookup: the translations (or "No translations
+/// found.") under the word's IPA transcription when WordReference gave one,
+/// preceded by a header naming the direction for auto-detected lookups.
+/// Regional variants are marked or filtered by the chat's preference.
+async fn render_lookup(
+    cache_repo: &CacheRepository,
+    payer: Payer<'_>,
+    word: &str,
+    direction: &str,
+    auto_detected: bool,
+    variant: VariantPreference,
+    locale: Locale,
+) -> String {
+    let translations = match lookup_with_lemmas(cache_repo, payer, word, direction, locale).await {
+        Some(translations) => {
+            let translations = variants::apply_preference(&translations, direction, variant);
+            match cache_repo.get_cached_pronunciation(word, direction).await {
+                Ok(Some(ipa)) => format!("🗣 {}\n\n{}", render::escape_html(&ipa), translations),
+                _ => translations,
+            }
+        }
+        None => i18n::t(locale, "no-translations"),
+    };
+
+    // Cached replies may predate escaping, so sanitize everything that is sent
+    let reply = match languages::direction(direction) {
+        Some(current) if auto_detected => format!(
+            "{}\n\n{}",
+            i18n::t_args(
+                locale,
+                "detected-direction",
+                &[("direction", &i18n::direction_name(locale, current))]
+            ),
+            translations
+        ),
+        _ => translations,
+    };
+    render::sanitize_html(&reply)
+}
+
+/// Edits a lookup reply in place to show the given page.
+#[allow(clippy::too_many_arguments)]
+async fn edit_lookup_reply(
+    bot: &Bot,
+    message: &Message,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    payer: Payer<'_>,
+    word: &str,
+    direction: &str,
+    auto_detected: bool,
+    page: usize,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let variant = get_chat_variant(user_repo, &message.chat.id.to_string()).await;
+    let full = render_lookup(
+        cache_repo,
+        payer,
+        word,
+        direction,
+        auto_detected,
+        variant,
+        locale,
+    )
+    .await;
+    let (text, keyboard) =
+        keyboards::reply_page(word, direction, auto_detected, &full, page, locale);
+
+    edit_html(bot, message.chat.id, message.id, &text, keyboard).await?;
+
+    Ok(())
+}
+
+/// Repeats an auto-detected lookup in the direction picked from its flip button.
+/// Returns false when the lookup was refused by the rate limiter.
+async fn handle_lookup_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    data: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let Some((direction, word)) = data.split_once(':') else {
+        return Ok(true);
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+
+    let payer = Payer {
+        limiter,
+        chat_id: message.chat.id,
+        user_id: Some(q.from.id.0),
+    };
+    if needs_fetch(cache_repo, word, direction).await && !payer.charge() {
+        return Ok(false);
+    }
+
+    edit_lookup_reply(
+        bot, message, user_repo, cache_repo, payer, word, direction, true, 0, locale,
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Sends a voice note pronouncing a word, in the chat's preferred variant for
+/// Portuguese. Returns false when synthesis was refused by the rate limiter;
+/// clips already on disk are always sent.
+async fn handle_say_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    limiter: &RateLimiter,
+    data: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let Some((lang, word)) = data.split_once(':') else {
+        return Ok(true);
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+    let Some(synthesizer) = pronunciation::synthesizer() else {
+        return Ok(true);
+    };
+    let variant = get_chat_variant(user_repo, &message.chat.id.to_string()).await;
+    let Some(voice) = pronunciation::voice_for(lang, variant.preferred) else {
+        return Ok(true);
+    };
+
+    if !synthesizer.cache_path(word, voice).exists()
+        && !limiter.check(message.chat.id.0, Some(q.from.id.0), 1)
+    {
+        return Ok(false);
+    }
+
+    let path = synthesizer.synthesize(word, voice).await?;
+    let mut request = bot.send_voice(message.chat.id, InputFile::file(path));
+    if let Some(thread) = topic_thread(message) {
+        request = request.message_thread_id(thread);
+    }
+    request.await?;
+
+    Ok(true)
+}
+
+/// Answers /t (or a bare mention) sent in reply to a message with a keyboard
+/// of the words in that message.
+async fn send_word_picker(
+    bot: &Bot,
+    msg: &Message,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(replied) = group_chat::replied_message(msg) else {
+        reply_to(bot, msg, i18n::t(locale, "pick-hint")).await?;
+        return Ok(());
+    };
+
+    let words = query::tokenize_words(replied.text().or(replied.caption()).unwrap_or(""));
+    if words.is_empty() {
+        reply_to(bot, msg, i18n::t(locale, "pick-no-words")).await?;
+        return Ok(());
+    }
+
+    reply_to(bot, msg, i18n::t(locale, "pick-prompt"))
+        .reply_markup(keyboards::word_picker(&words))
+        .await?;
+
+    Ok(())
+}
+
+/// Looks up a word picked from a /t keyboard in the chat's direction and sends
+/// the reply as a new message, so several words can be picked in turn.
+/// Returns false when the lookup was refused by the rate limiter.
+#[allow(clippy::too_many_arguments)]
+async fn handle_pick_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    stats_repo: &StatsRepository,
+    word: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+
+    let chat = user_repo.get_user(&message.chat.id.to_string()).await?;
+    let auto_detect = chat.as_ref().is_some_and(|u| u.auto_detect);
+    let variant = chat
+        .as_ref()
+        .map(VariantPreference::from_user)
+        .unwrap_or_default();
+    let stored = get_lookup_direction(user_repo, &message.chat.id.to_string(), Some(&q.from)).await;
+    let direction = if auto_detect {
+        detect_lookup_direction(cache_repo, word, &stored).await
+    } else {
+        stored
+    };
+
+    let payer = Payer {
+        limiter,
+        chat_id: message.chat.id,
+        user_id: Some(q.from.id.0),
+    };
+    let cache_hit = !needs_fetch(cache_repo, word, &direction).await;
+    if !cache_hit && !payer.charge() {
+        return Ok(false);
+    }
+    if let Err(e) = stats_repo.record_lookup(cache_hit).await {
+        log::error!("Failed to record lookup stats: {}", e);
+    }
+
+    let full = render_lookup(
+        cache_repo,
+        payer,
+        word,
+        &direction,
+        auto_detect,
+        variant,
+        locale,
+    )
+    .await;
+    let (text, keyboard) = keyboards::reply_page(word, &direction, auto_detect, &full, 0, locale);
+    send_html(bot, message, &text, keyboard).await?;
+
+    Ok(true)
+}
+
+/// Shows another page of a long lookup reply. Returns false when re-rendering
+/// it was refused by the rate limiter.
+async fn handle_page_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    data: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let mut parts = data.splitn(3, ':');
+    let (Some(mode_page), Some(direction), Some(word)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(true);
+    };
+    let auto_detected = mode_page.starts_with('a');
+    let Some(Ok(page)) = mode_page.get(1..).map(str::parse::<usize>) else {
+        return Ok(true);
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+    let payer = Payer {
+        limiter,
+        chat_id: message.chat.id,
+        user_id: Some(q.from.id.0),
+    };
+    // The article may have been purged since the reply was sent
+    if needs_fetch(cache_repo, word, direction).await && !payer.charge() {
+        return Ok(false);
+    }
+
+    edit_lookup_reply(
+        bot,
+        message,
+        user_repo,
+        cache_repo,
+        payer,
+        word,
+        direction,
+        auto_detected,
+        page,
+        locale,
+    )
+    .await?;
+    Ok(true)
+}
+
+/// Sends the whole of a long lookup reply as an HTML document. Returns false
+/// when re-rendering it was refused by the rate limiter.
+async fn handle_show_all_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    data: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let mut parts = data.splitn(3, ':');
+    let (Some(mode), Some(direction), Some(word)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Ok(true);
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+
+    let payer = Payer {
+        limiter,
+        chat_id: message.chat.id,
+        user_id: Some(q.from.id.0),
+    };
+    // The article may have been purged since the reply was sent
+    if needs_fetch(cache_repo, word, direction).await && !payer.charge() {
+        return Ok(false);
+    }
+
+    let variant = get_chat_variant(user_repo, &message.chat.id.to_string()).await;
+    let full = render_lookup(
+        cache_repo,
+        payer,
+        word,
+        direction,
+        mode == "a",
+        variant,
+        locale,
+    )
+    .await;
+    let document = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{}</title></head>\n\
+         <body style=\"white-space: pre-wrap; font-family: sans-serif\">{}</body></html>\n",
+        render::escape_html(word),
+        full
+    );
+
+    document_to(
+        bot,
+        message,
+        InputFile::memory(document).file_name(format!("{}.html", word)),
+    )
+    .await?;
+
+    Ok(true)
+}
+
+/// Whether the user who pressed a button is an admin of the message's chat.
+/// Everyone is in their private chat.
+async fn pressed_by_chat_admin(
+    bot: &Bot,
+    q: &CallbackQuery,
+    message: &Message,
+) -> ResponseResult<bool> {
+    if message.chat.is_private() {
+        return Ok(true);
+    }
+    Ok(bot
+        .get_chat_member(message.chat.id, q.from.id)
+        .await?
+        .is_privileged())
+}
+
+/// Whether the sender of a message is an admin of its chat. Admins posting
+/// anonymously send as the chat itself.
+async fn is_chat_admin(bot: &Bot, msg: &Message) -> ResponseResult<bool> {
+    if msg
+        .sender_chat
+        .as_ref()
+        .is_some_and(|c| c.id == msg.chat.id)
+    {
+        return Ok(true);
+    }
+    let Some(user) = msg.from.as_ref() else {
+        return Ok(false);
+    };
+    Ok(bot
+        .get_chat_member(msg.chat.id, user.id)
+        .await?
+        .is_privileged())
+}
+
+/// Handles the /groupmode command: shows which messages the bot answers in a
+/// group, or lets a group admin change it.
+async fn handle_group_mode_command(
+    bot: &Bot,
+    msg: &Message,
+    user_repo: &UserRepository,
+    chat_id: &str,
+    mode: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if msg.chat.is_private() {
+        reply_to(bot, msg, i18n::t(locale, "group-mode-private")).await?;
+        return Ok(());
+    }
+
+    if mode.is_empty() {
+        let current = get_group_mode(user_repo, chat_id).await;
+        reply_to(
+            bot,
+            msg,
+            i18n::t_args(
+                locale,
+                "group-mode-current",
+                &[("mode", &group_mode_name(locale, current))],
+            ),
+        )
+        .await?;
+        return Ok(());
+    }
+
+    let Some(mode) = GroupMode::from_code(mode) else {
+        reply_to(bot, msg, i18n::t(locale, "group-mode-usage")).await?;
+        return Ok(());
+    };
+    if !is_chat_admin(bot, msg).await? {
+        reply_to(bot, msg, i18n::t(locale, "group-mode-admins-only")).await?;
+        return Ok(());
+    }
+
+    if user_repo.get_user(chat_id).await?.is_none() {
+        ensure_chat(user_repo, chat_id, msg.from.as_ref()).await?;
+    }
+    user_repo.update_group_mode(chat_id, mode).await?;
+    reply_to(
+        bot,
+        msg,
+        i18n::t_args(
+            locale,
+            "group-mode-changed",
+            &[("mode", &group_mode_name(locale, mode))],
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Which messages a group mode answers, e.g. "replies to my messages".
+fn group_mode_name(locale: Locale, mode: GroupMode) -> String {
+    i18n::t(locale, &format!("group-mode-{}", mode.code()))
+}
+
+/// Handles the /autodetect command by toggling per-message language detection.
+async fn handle_autodetect_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    chat_id: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let enabled = match user_repo.get_user(chat_id).await? {
+        Some(user) => !user.auto_detect,
+        None => {
+            ensure_chat(user_repo, chat_id, msg.from.as_ref()).await?;
+            true
+        }
+    };
+
+    user_repo.update_auto_detect(chat_id, enabled).await?;
+
+    let text = if enabled {
+        "autodetect-on"
+    } else {
+        "autodetect-off"
+    };
+    reply_to(&bot, &msg, i18n::t(locale, text)).await?;
+
+    Ok(())
+}
+
+/// Handles the /save command: looks the word up (so its article is cached)
+/// and adds it to the sender's vocabulary in the chat. An inflected form is
+/// saved as the lemma it was found under.
+#[allow(clippy::too_many_arguments)]
+async fn handle_save_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    saved_words_repo: &SavedWordsRepository,
+    limiter: &RateLimiter,
+    word: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if word.is_empty() {
+        reply_to(&bot, &msg, i18n::t(locale, "save-usage")).await?;
+        return Ok(());
+    }
+
+    let chat_id = msg.chat.id.to_string();
+    let direction = get_lookup_direction(user_repo, &chat_id, msg.from.as_ref()).await;
+
+    let payer = Payer {
+        limiter,
+        chat_id: msg.chat.id,
+        user_id: msg.from.as_ref().map(|u| u.id.0),
+    };
+    if needs_fetch(cache_repo, word, &direction).await && !payer.charge() {
+        reply_to(&bot, &msg, i18n::t(locale, "slow-down")).await?;
+        return Ok(());
+    }
+
+    let mut found = None;
+    let mut fetched = false;
+    for (lookup, _) in lemmatizer::lookup_order(word, source_language(&direction)) {
+        if !may_fetch(cache_repo, payer, &lookup, &direction, &mut fetched).await {
+            break;
+        }
+        if lookup_translations(cache_repo, &lookup, &direction)
+            .await
+            .is_some()
+        {
+            found = Some(lookup);
+            break;
+        }
+    }
+    let Some(found) = found else {
+        reply_to(&bot, &msg, i18n::t(locale, "no-translations")).await?;
+        return Ok(());
+    };
+
+    let user_id = msg.from.as_ref().map_or(msg.chat.id.0, |u| u.id.0 as i64);
+    saved_words_repo
+        .save_word(&chat_id, user_id, &found, &direction)
+        .await?;
+    reply_to(
+        &bot,
+        &msg,
+        i18n::t_args(locale, "word-saved", &[("word", &found)]),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handles the /export command by sending the words the sender saved in the
+/// chat as an Anki package or a CSV file. Cards are built from the cached
+/// article, or from the offline dictionaries and Wiktionary for words found
+/// there; words that no longer have translations are left out and listed.
+async fn handle_export_command(
+    bot: Bot,
+    msg: Message,
+    cache_repo: &CacheRepository,
+    saved_words_repo: &SavedWordsRepository,
+    chat_id: &str,
+    format: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if format != "anki" && format != "csv" {
+        reply_to(&bot, &msg, i18n::t(locale, "export-usage")).await?;
+        return Ok(());
+    }
+
+    let user_id = msg.from.as_ref().map_or(msg.chat.id.0, |u| u.id.0 as i64);
+    let saved_words = saved_words_repo.get_saved_words(chat_id, user_id).await?;
+    if saved_words.is_empty() {
+        reply_to(&bot, &msg, i18n::t(locale, "export-empty")).await?;
+        return Ok(());
+    }
+
+    let mut cards = Vec::with_capacity(saved_words.len());
+    let mut skipped: Vec<&str> = Vec::new();
+    for saved in &saved_words {
+        let entries = match cache_repo
+            .get_cached_html(&saved.word, &saved.lang_direction)
+            .await?
+        {
+            Some(html) => fetch_translations::get_translation_entries(&html),
+            None => lookup::lookup_local(&saved.word, &saved.lang_direction)
+                .await
+                .map(|article| article.entries)
+                .unwrap_or_default(),
+        };
+        let card = VocabularyCard::from_entries(&saved.word, &saved.lang_direction, &entries);
+        if card.back.is_empty() {
+            skipped.push(&saved.word);
+        } else {
+            cards.push(card);
+        }
+    }
+
+    if !skipped.is_empty() {
+        let words = skipped.join(", ");
+        reply_to(
+            &bot,
+            &msg,
+            i18n::t_args(locale, "export-skipped", &[("words", &words)]),
+        )
+        .await?;
+    }
+    if cards.is_empty() {
+        return Ok(());
+    }
+
+    let document = if format == "anki" {
+        let apkg = export::build_apkg(&cards, "Portuguese dict bot").await?;
+        InputFile::memory(apkg).file_name("vocabulary.apkg")
+    } else {
+        InputFile::memory(export::build_csv(&cards)).file_name("vocabulary.csv")
+    };
+
+    document_to(&bot, &msg, document).await?;
+
+    Ok(())
+}
+
+/// Runs an admin command. Callers check that the sender is an admin.
+async fn handle_admin_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    stats_repo: &StatsRepository,
+    cmd: AdminCommand,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    match cmd {
+        AdminCommand::Stats => {
+            let today = stats_repo.get_day(Utc::now().date_naive()).await?;
+            let (lookups, hits) = stats_repo.get_totals().await?;
+            let cache = cache_repo.get_stats().await?;
+            let text = i18n::t_args(
+                locale,
+                "admin-stats",
+                &[
+                    ("users", &user_repo.count_users().await?.to_string()),
+                    ("chats", &user_repo.count_chats().await?.to_string()),
+                    ("lookups", &today.lookups.to_string()),
+                    ("hits", &today.cache_hits.to_string()),
+                    ("rate", &hit_rate(today.cache_hits, today.lookups, locale)),
+                    ("total_lookups", &lookups.to_string()),
+                    ("total_hits", &hits.to_string()),
+                    ("total_rate", &hit_rate(hits, lookups, locale)),
+                    ("articles", &cache.articles.to_string()),
+                    (
+                        "megabytes",
+                        &format!("{:.1}", cache.bytes as f64 / 1_000_000.0),
+                    ),
+                ],
+            );
+            send_html(&bot, &msg, &text, None).await?;
+        }
+        AdminCommand::Purge(args) => {
+            let mut args = args.split_whitespace();
+            let Some(word) = args.next() else {
+                reply_to(&bot, &msg, i18n::t(locale, "admin-purge-usage")).await?;
+                return Ok(());
+            };
+            let word = word.to_lowercase();
+            let direction = args.next();
+            let deleted = cache_repo.purge(&word, direction).await?;
+            reply_to(
+                &bot,
+                &msg,
+                i18n::t_args(
+                    locale,
+                    "admin-purged",
+                    &[("count", &deleted.to_string()), ("word", &word)],
+                ),
+            )
+            .await?;
+        }
+        AdminCommand::User(chat_id) => {
+            let chat_id = chat_id.trim();
+            if chat_id.is_empty() {
+                reply_to(&bot, &msg, i18n::t(locale, "admin-user-usage")).await?;
+                return Ok(());
+            }
+            let text = match user_repo.get_user(chat_id).await? {
+                Some(user) => format!(
+                    "<pre>{}</pre>",
+                    render::escape_html(&format!("{:#?}", user))
+                ),
+                None => i18n::t_args(
+                    locale,
+                    "admin-no-user",
+                    &[("chat", &render::escape_html(chat_id))],
+                ),
+            };
+            send_html(&bot, &msg, &text, None).await?;
+        }
+        AdminCommand::Broadcast(text) => {
+            let text = text.trim().to_string();
+            if text.is_empty() {
+                reply_to(&bot, &msg, i18n::t(locale, "admin-broadcast-usage")).await?;
+                return Ok(());
+            }
+            let chat_ids = user_repo.get_chat_ids().await?;
+            reply_to(
+                &bot,
+                &msg,
+                i18n::t_args(
+                    locale,
+                    "admin-broadcasting",
+                    &[("count", &chat_ids.len().to_string())],
+                ),
+            )
+            .await?;
+
+            // Runs in the background so the admin's chat isn't blocked meanwhile
+            tokio::spawn(broadcast(bot, msg.chat.id, chat_ids, text, locale));
+        }
+    }
+
+    Ok(())
+}
+
+/// Percentage of lookups answered from the cache.
+fn hit_rate(hits: i64, lookups: i64, locale: Locale) -> String {
+    if lookups == 0 {
+        return i18n::t(locale, "admin-no-hit-rate");
+    }
+    format!("{:.0}%", hits as f64 * 100.0 / lookups as f64)
+}
+
+/// Sends `text` to every chat, one at a time, then reports the delivery
+/// counts to the admin's chat. Chats that have blocked the bot count as failed.
+async fn broadcast(
+    bot: Bot,
+    report_chat: ChatId,
+    chat_ids: Vec<String>,
+    text: String,
+    locale: Locale,
+) {
+    let mut delivered = 0;
+    let mut failed = 0;
+
+    for chat_id in chat_ids {
+        let Ok(id) = chat_id.parse::<i64>() else {
+            failed += 1;
+            continue;
+        };
+
+        let mut result = bot.send_message(ChatId(id), &text).await;
+        if let Err(RequestError::RetryAfter(retry_after)) = &result {
+            tokio::time::sleep(retry_after.duration()).await;
+            result = bot.send_message(ChatId(id), &text).await;
+        }
+        match result {
+            Ok(_) => delivered += 1,
+            Err(e) => {
+                log::warn!("Broadcast to chat {} failed: {}", chat_id, e);
+                failed += 1;
+            }
+        }
+
+        tokio::time::sleep(BROADCAST_INTERVAL).await;
+    }
+
+    if let Err(e) = bot
+        .send_message(
+            report_chat,
+            i18n::t_args(
+                locale,
+                "admin-broadcast-finished",
+                &[
+                    ("delivered", &delivered.to_string()),
+                    ("failed", &failed.to_string()),
+                ],
+            ),
+        )
+        .await
+    {
+        log::error!("Failed to send broadcast report: {}", e);
+    }
+}
+
+/// Handles the /batch command: looks up the words listed after it, one per line.
+#[allow(clippy::too_many_arguments)]
+async fn handle_batch_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    cache_repo: &CacheRepository,
+    limiter: &RateLimiter,
+    stats_repo: &StatsRepository,
+    words: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let (queries, dropped) = query::split_queries(words);
+    if queries.is_empty() {
+        reply_to(&bot, &msg, i18n::t(locale, "batch-usage")).await?;
+        return Ok(());
+    }
+
+    let chat_id = msg.chat.id.to_string();
+    let auto_detect = user_repo
+        .get_user(&chat_id)
+        .await?
+        .is_some_and(|u| u.auto_detect);
+    let direction = get_lookup_direction(user_repo, &chat_id, msg.from.as_ref()).await;
+
+    send_batch_lookup(
+        &bot,
+        &msg,
+        cache_repo,
+        limiter,
+        stats_repo,
+        &queries,
+        dropped,
+        &direction,
+        auto_detect,
+        locale,
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Handles the /conj command: shows the first tense of the verb's conjugation
+/// together with a picker for the other tenses.
+#[allow(clippy::too_many_arguments)]
+async fn handle_conj_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    conjugation_repo: &ConjugationRepository,
+    limiter: &RateLimiter,
+    chat_id: &str,
+    verb: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if verb.is_empty() {
+        reply_to(&bot, &msg, i18n::t(locale, "conj-usage")).await?;
+        return Ok(());
+    }
+
+    let direction = get_lookup_direction(user_repo, chat_id, msg.from.as_ref()).await;
+    let Some(lang) = conjugations::conjugation_language(&direction) else {
+        reply_to(&bot, &msg, i18n::t(locale, "conj-unsupported")).await?;
+        return Ok(());
+    };
+
+    let table = match conjugation_repo.get_conjugation(verb, lang).await? {
+        Some(table) => table,
+        None => {
+            if !limiter.check(msg.chat.id.0, msg.from.as_ref().map(|u| u.id.0), 1) {
+                reply_to(&bot, &msg, i18n::t(locale, "slow-down")).await?;
+                return Ok(());
+            }
+            let body = conjugations::fetch_conjugation(verb, lang).await?;
+            match conjugations::parse_conjugation(verb, &body) {
+                Some(table) => {
+                    let _ = conjugation_repo.store_conjugation(lang, &table).await;
+                    table
+                }
+                None => {
+                    reply_to(&bot, &msg, i18n::t(locale, "conj-not-found")).await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let text = conjugations::format_tense(&table, 0).unwrap_or_default();
+    send_html(&bot, &msg, &text, keyboards::tense_picker(&table, lang, 0)).await?;
+
+    Ok(())
+}
+
+/// Dispatches inline keyboard presses by the prefix of their callback data
+/// (`look:`, `say:`, `pick:`, `page:`, `conj:`...), then answers the
+/// callback, with a notice when the press was rate limited or failed.
+async fn handle_callback_query(
+    bot: Bot,
+    q: CallbackQuery,
+    user_repo: UserRepository,
+    cache_repo: CacheRepository,
+    conjugation_repo: ConjugationRepository,
+    limiter: RateLimiter,
+    stats_repo: StatsRepository,
+) -> ResponseResult<()> {
+    let data = q.data.clone().unwrap_or_default();
+    let locale = match q.regular_message() {
+        Some(message) => get_locale(&user_repo, &message.chat.id.to_string(), Some(&q.from)).await,
+        None => Locale::from_language_code(q.from.language_code.as_deref()),
+    };
+    // Shown to the user in place of the callback's usual silent answer
+    let mut notice = None;
+
+    if let Some(rest) = data.strip_prefix("look:") {
+        match handle_lookup_callback(&bot, &q, &user_repo, &cache_repo, &limiter, rest, locale)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => notice = Some("slow-down"),
+            Err(e) => log::error!("Error in lookup callback handler: {}", e),
+        }
+    }
+
+    if let Some(rest) = data.strip_prefix("say:") {
+        match handle_say_callback(&bot, &q, &user_repo, &limiter, rest).await {
+            Ok(true) => {}
+            Ok(false) => notice = Some("slow-down"),
+            Err(e) => {
+                log::error!("Error in pronunciation callback handler: {}", e);
+                notice = Some("tts-failed");
+            }
+        }
+    }
+
+    if let Some(word) = data.strip_prefix("pick:") {
+        match handle_pick_callback(
+            &bot,
+            &q,
+            &user_repo,
+            &cache_repo,
+            &limiter,
+            &stats_repo,
+            word,
+            locale,
+        )
+        .await
+        {
+            Ok(true) => {}
+            Ok(false) => notice = Some("slow-down"),
+            Err(e) => log::error!("Error in pick callback handler: {}", e),
+        }
+    }
+
+    if let Some(rest) = data.strip_prefix("page:") {
+        match handle_page_callback(&bot, &q, &user_repo, &cache_repo, &limiter, rest, locale).await
+        {
+            Ok(true) => {}
+            Ok(false) => notice = Some("slow-down"),
+            Err(e) => log::error!("Error in page callback handler: {}", e),
+        }
+    }
+
+    if let Some(rest) = data.strip_prefix("all:") {
+        match handle_show_all_callback(&bot, &q, &user_repo, &cache_repo, &limiter, rest, locale)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => notice = Some("slow-down"),
+            Err(e) => log::error!("Error in show all callback handler: {}", e),
+        }
+    }
+
+    if let Some(code) = data.strip_prefix("dir:")
+        && let Err(e) = handle_direction_callback(&bot, &q, &user_repo, code, locale).await
+    {
+        log::error!("Error in direction callback handler: {}", e);
+    }
+
+    if let Some(choice) = data.strip_prefix("loc:") {
+        match handle_locale_callback(&bot, &q, &user_repo, choice).await {
+            Ok(true) => {}
+            Ok(false) => notice = Some("locale-admins-only"),
+            Err(e) => log::error!("Error in locale callback handler: {}", e),
+        }
+    }
+
+    if let Some(choice) = data.strip_prefix("var:") {
+        match handle_variant_callback(&bot, &q, &user_repo, choice, locale).await {
+            Ok(true) => {}
+            Ok(false) => notice = Some("variant-admins-only"),
+            Err(e) => log::error!("Error in variant callback handler: {}", e),
+        }
+    }
+
+    if let Some(target) = data.strip_prefix("flip:") {
+        match handle_flip_callback(&bot, &q, &user_repo, target, locale).await {
+            Ok(true) => {}
+            Ok(false) => notice = Some("flip-admins-only"),
+            Err(e) => log::error!("Error in flip callback handler: {}", e),
+        }
+    }
+
+    if let Some(rest) = data.strip_prefix("conj:")
+        && let Err(e) = handle_conj_callback(&bot, &q, &conjugation_repo, rest).await
+    {
+        log::error!("Error in conj callback handler: {}", e);
+    }
+
+    let mut answer = bot.answer_callback_query(q.id);
+    if let Some(id) = notice {
+        answer = answer.text(i18n::t(locale, id));
+    }
+    answer.await?;
+    Ok(())
+}
+
+/// Switches a conjugation message to the tense picked from its keyboard.
+async fn handle_conj_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    conjugation_repo: &ConjugationRepository,
+    data: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut parts = data.splitn(3, ':');
+    let (Some(lang), Some(index), Some(verb)) = (parts.next(), parts.next(), parts.next()) else {
+        return Ok(());
+    };
+    let Ok(index) = index.parse::<usize>() else {
+        return Ok(());
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+    let Some(table) = conjugation_repo.get_conjugation(verb, lang).await? else {
+        return Ok(());
+    };
+    let Some(text) = conjugations::format_tense(&table, index) else {
+        return Ok(());
+    };
+
+    edit_html(
+        bot,
+        message.chat.id,
+        message.id,
+        &text,
+        keyboards::tense_picker(&table, lang, index),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Creates the chat's row on first use, without a direction of its own, for
+/// settings other than the direction.
+async fn ensure_chat(
+    user_repo: &UserRepository,
+    chat_id: &str,
+    from: Option<&User>,
+) -> Result<(), sea_orm::DbErr> {
+    if user_repo.get_user(chat_id).await?.is_none() {
+        let user_id = from.map(|u| u.id.0 as i64);
+        let username = from.and_then(|u| u.username.clone());
+        user_repo
+            .create_chat(chat_id, user_id, username.as_deref())
+            .await?;
+    }
+    Ok(())
+}
+
+/// Stores the chat's translation direction, creating the chat's row on first use.
+async fn store_chat_direction(
+    user_repo: &UserRepository,
+    chat_id: &str,
+    direction: &str,
+    from: Option<&User>,
+) -> Result<users::Model, sea_orm::DbErr> {
+    if user_repo.get_user(chat_id).await?.is_some() {
+        user_repo
+            .update_translation_direction(chat_id, direction)
+            .await
+    } else {
+        let user_id = from.map(|u| u.id.0 as i64);
+        let username = from.and_then(|u| u.username.clone());
+        user_repo
+            .create_or_update_user(chat_id, direction, user_id, username.as_deref())
+            .await
+    }
+}
+
+fn direction_name(locale: Locale, direction: &str) -> String {
+    languages::direction(direction)
+        .map(|d| i18n::direction_name(locale, d))
+        .unwrap_or_else(|| direction.to_string())
+}
+
+fn direction_changed_text(locale: Locale, direction: &str) -> String {
+    i18n::t_args(
+        locale,
+        "direction-changed",
+        &[("direction", &direction_name(locale, direction))],
+    )
+}
+
+/// The interface language chosen for a chat in /settings, if any.
+fn chat_locale(user: &users::Model) -> Option<Locale> {
+    user.locale.as_deref().and_then(Locale::from_code)
+}
+
+/// Handles the /start command: greets the user and offers the direction and
+/// language picker, so the first lookup already goes the right way.
+async fn handle_start_command(
+    bot: &Bot,
+    msg: &Message,
+    user_repo: &UserRepository,
+    chat_id: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let chat = user_repo.get_user(chat_id).await?;
+    let direction = get_lookup_direction(user_repo, chat_id, msg.from.as_ref()).await;
+    let chosen = chat.as_ref().and_then(chat_locale);
+
+    let name = msg.from.as_ref().map_or("", |u| u.first_name.as_str());
+    let text = format!(
+        "{}\n\n{}",
+        i18n::t_args(locale, "start-welcome", &[("name", name)]),
+        i18n::t(locale, "start-choose")
+    );
+    reply_to(bot, msg, text)
+        .reply_markup(keyboards::settings_picker(locale, &direction, chosen))
+        .await?;
+
+    Ok(())
+}
+
+/// The /help reply: every command of the chat's menu with its description,
+/// plus the admin commands for bot admins in private chats.
+fn help_text(msg: &Message, is_admin: bool, locale: Locale) -> String {
+    let scope = if msg.chat.is_private() {
+        MenuScope::Private
+    } else {
+        MenuScope::Group
+    };
+    let mut text = i18n::t(locale, "help-intro");
+    for cmd in localized_commands(locale, scope) {
+        text.push_str(&format!("\n/{} — {}", cmd.command, cmd.description));
+    }
+    if is_admin && msg.chat.is_private() {
+        text.push_str(&format!("\n\n{}", i18n::t(locale, "help-admin")));
+        for cmd in localized_admin_commands(locale) {
+            text.push_str(&format!("\n/{} — {}", cmd.command, cmd.description));
+        }
+    }
+    text
+}
+
+/// Handles the /settings command by showing the direction and language picker.
+async fn handle_settings_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    chat_id: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let chat = user_repo.get_user(chat_id).await?;
+    let direction = get_lookup_direction(user_repo, chat_id, msg.from.as_ref()).await;
+    let chosen = chat.as_ref().and_then(chat_locale);
+
+    reply_to(&bot, &msg, i18n::t(locale, "settings-prompt"))
+        .reply_markup(keyboards::settings_picker(locale, &direction, chosen))
+        .await?;
+
+    Ok(())
+}
+
+/// Applies a direction picked from the /settings keyboard. In groups it
+/// becomes the member's own direction; the group's is set with /flip.
+async fn handle_direction_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    code: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(direction) = languages::direction(code) else {
+        return Ok(());
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(());
+    };
+
+    let chat_id = message.chat.id.to_string();
+    let (text, chat) = if message.chat.is_private() {
+        let chat = store_chat_direction(user_repo, &chat_id, direction.code, Some(&q.from)).await?;
+        (direction_changed_text(locale, direction.code), chat)
+    } else {
+        ensure_chat(user_repo, &chat_id, Some(&q.from)).await?;
+        user_repo
+            .set_member_direction(&chat_id, q.from.id.0 as i64, direction.code)
+            .await?;
+        let text = i18n::t_args(
+            locale,
+            "direction-changed-for-user",
+            &[
+                ("name", &q.from.first_name),
+                ("direction", &direction_name(locale, direction.code)),
+            ],
+        );
+        let chat = user_repo
+            .get_user(&chat_id)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("Chat not found".to_string()))?;
+        (text, chat)
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .reply_markup(keyboards::settings_picker(
+            locale,
+            direction.code,
+            chat_locale(&chat),
+        ))
+        .await?;
+
+    Ok(())
+}
+
+/// Applies an interface language picked from the /settings keyboard, or goes
+/// back to following each user's Telegram language. In groups only admins may
+/// change it; returns false when someone else tried to.
+async fn handle_locale_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    choice: &str,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let chosen = match choice {
+        "auto" => None,
+        code => match Locale::from_code(code) {
+            Some(locale) => Some(locale),
+            None => return Ok(true),
+        },
+    };
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+    if !pressed_by_chat_admin(bot, q, message).await? {
+        return Ok(false);
+    }
+    let chat_id = message.chat.id.to_string();
+
+    if user_repo.get_user(&chat_id).await?.is_none() {
+        ensure_chat(user_repo, &chat_id, Some(&q.from)).await?;
+    }
+    user_repo
+        .update_locale(&chat_id, chosen.map(Locale::code))
+        .await?;
+    let direction = get_lookup_direction(user_repo, &chat_id, Some(&q.from)).await;
+
+    let (locale, text) = match chosen {
+        Some(locale) => (locale, "locale-changed"),
+        None => (
+            Locale::from_language_code(q.from.language_code.as_deref()),
+            "locale-auto",
+        ),
+    };
+    bot.edit_message_text(message.chat.id, message.id, i18n::t(locale, text))
+        .reply_markup(keyboards::settings_picker(locale, &direction, chosen))
+        .await?;
+
+    Ok(true)
+}
+
+fn variant_text(locale: Locale, preference: VariantPreference) -> String {
+    let Some(variant) = preference.preferred else {
+        return i18n::t(locale, "variant-any");
+    };
+    let id = if preference.hide_others {
+        "variant-only"
+    } else {
+        "variant-preferred"
+    };
+    let name = i18n::t(locale, &format!("variant-{}", variant.code()));
+    i18n::t_args(locale, id, &[("flag", variant.flag()), ("variant", &name)])
+}
+
+/// Handles the /variant command by showing the variant picker.
+async fn handle_variant_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: &UserRepository,
+    chat_id: &str,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let preference = get_chat_variant(user_repo, chat_id).await;
+
+    reply_to(&bot, &msg, variant_text(locale, preference))
+        .reply_markup(keyboards::variant_picker(locale, preference))
+        .await?;
+
+    Ok(())
+}
+
+/// Applies a choice from the /variant keyboard. In groups only admins may
+/// change the preference; returns false when someone else tried to.
+async fn handle_variant_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    choice: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+    if !pressed_by_chat_admin(bot, q, message).await? {
+        return Ok(false);
+    }
+    let chat_id = message.chat.id.to_string();
+
+    let mut preference = match user_repo.get_user(&chat_id).await? {
+        Some(user) => VariantPreference::from_user(&user),
+        None => {
+            ensure_chat(user_repo, &chat_id, Some(&q.from)).await?;
+            VariantPreference::default()
+        }
+    };
+    match choice {
+        "any" => preference.preferred = None,
+        "hide" => preference.hide_others = true,
+        "show" => preference.hide_others = false,
+        code => match Variant::from_code(code) {
+            Some(variant) => preference.preferred = Some(variant),
+            None => return Ok(true),
+        },
+    }
+
+    user_repo
+        .update_variant_preference(
+            &chat_id,
+            preference.preferred.map(Variant::code),
+            preference.hide_others,
+        )
+        .await?;
+
+    bot.edit_message_text(
+        message.chat.id,
+        message.id,
+        variant_text(locale, preference),
+    )
+    .reply_markup(keyboards::variant_picker(locale, preference))
+    .await?;
+
+    Ok(true)
+}
+
+/// Handles the /flip command by toggling user's translation direction.
+/// Logs errors and falls back to default silently.
+async fn handle_flip_command(
+    bot: Bot,
+    msg: Message,
+    user_repo: UserRepository,
+    chat_id: Arc<String>,
+    locale: Locale,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // In a group, ask whether to flip the sender's own direction or the group's
+    if !msg.chat.is_private() {
+        reply_to(&bot, &msg, i18n::t(locale, "flip-prompt"))
+            .reply_markup(keyboards::flip_picker(locale))
+            .await?;
+        return Ok(());
+    }
+
+    let current_direction = get_chat_direction(&user_repo, &chat_id).await;
+    let new_direction = flipped_direction(&current_direction, &chat_id);
+
+    let update_result =
+        store_chat_direction(&user_repo, &chat_id, &new_direction, msg.from.as_ref()).await;
+
+    match update_result {
+        Ok(_) => {
+            reply_to(&bot, &msg, direction_changed_text(locale, &new_direction)).await?;
+        }
+        Err(e) => {
+            log::error!("Failed to update direction for chat {}: {}", chat_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// The opposite of a direction, or of the default one when it is not valid.
+fn flipped_direction(current_direction: &str, chat_id: &str) -> String {
+    match flip_direction(current_direction) {
+        Some(dir) => dir,
+        None => {
+            log::warn!(
+                "Cannot flip invalid direction '{}' for chat {}. Using flipped default.",
+                current_direction,
+                chat_id
+            );
+            flip_direction(DEFAULT_LANG_DIRECTION)
+                .unwrap_or_else(|| DEFAULT_LANG_DIRECTION.to_string())
+        }
+    }
+}
+
+/// Flips either the direction of the user who pressed the button in this
+/// group, or the group's own default. Only admins may change the latter.
+/// Returns false when a non-admin tried to.
+async fn handle_flip_callback(
+    bot: &Bot,
+    q: &CallbackQuery,
+    user_repo: &UserRepository,
+    target: &str,
+    locale: Locale,
+) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(message) = q.regular_message() else {
+        return Ok(true);
+    };
+    let chat_id = message.chat.id.to_string();
+
+    let text = match target {
+        "me" => {
+            let current = get_lookup_direction(user_repo, &chat_id, Some(&q.from)).await;
+            let new_direction = flipped_direction(&current, &chat_id);
+            user_repo
+                .set_member_direction(&chat_id, q.from.id.0 as i64, &new_direction)
+                .await?;
+            i18n::t_args(
+                locale,
+                "direction-changed-for-user",
+                &[
+                    ("name", &q.from.first_name),
+                    ("direction", &direction_name(locale, &new_direction)),
+                ],
+            )
+        }
+        "group" => {
+            if !pressed_by_chat_admin(bot, q, message).await? {
+                return Ok(false);
+            }
+            let current = get_chat_direction(user_repo, &chat_id).await;
+            let new_direction = flipped_direction(&current, &chat_id);
+            store_chat_direction(user_repo, &chat_id, &new_direction, Some(&q.from)).await?;
+            i18n::t_args(
+                locale,
+                "direction-changed-for-group",
+                &[("direction", &direction_name(locale, &new_direction))],
+            )
+        }
+        _ => return Ok(true),
+    };
+
+    bot.edit_message_text(message.chat.id, message.id, text)
+        .await?;
+
+    Ok(true)
 }
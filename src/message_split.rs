@@ -0,0 +1,145 @@
+/// Maximum length of a Telegram message, in UTF-16 code units.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+
+/// Length as Telegram counts it. Measured on the HTML source, which is never
+/// shorter than the rendered text, so a chunk under the limit always fits.
+pub fn telegram_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// Splits a Telegram HTML message into chunks of at most `limit` units.
+///
+/// Chunks break between lines, so translation entries (one per line) stay
+/// whole. A single line longer than the limit is broken at a space outside any
+/// tag; tags still open at the break are closed at the end of the chunk and
+/// reopened at the start of the next.
+pub fn split_message(html: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for line in html.split_inclusive('\n') {
+        if telegram_len(&current) + telegram_len(line) <= limit {
+            current.push_str(line);
+            continue;
+        }
+
+        if !current.is_empty() {
+            chunks.push(std::mem::take(&mut current));
+        }
+
+        if telegram_len(line) <= limit {
+            current.push_str(line);
+        } else {
+            let mut pieces = split_long_line(line, limit);
+            current = pieces.pop().unwrap_or_default();
+            chunks.extend(pieces);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// A piece of an HTML line: either a whole tag or a run of text.
+enum Token<'a> {
+    Tag(&'a str),
+    Text(&'a str),
+}
+
+fn tokenize(line: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+
+    while !rest.is_empty() {
+        if rest.starts_with('<')
+            && let Some(end) = rest.find('>')
+        {
+            tokens.push(Token::Tag(&rest[..=end]));
+            rest = &rest[end + 1..];
+            continue;
+        }
+        let end = rest
+            .char_indices()
+            .skip(1)
+            .find(|(_, c)| *c == '<')
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        tokens.push(Token::Text(&rest[..end]));
+        rest = &rest[end..];
+    }
+
+    tokens
+}
+
+/// Name of a tag, e.g. `b` for `<b>`, `</b>` and `<a href="...">`.
+fn tag_name(tag: &str) -> &str {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .trim_end_matches('>')
+        .split_whitespace()
+        .next()
+        .unwrap_or("")
+}
+
+fn split_long_line(line: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    // Open tags, as written, so they can be reopened in the next piece
+    let mut open_tags: Vec<&str> = Vec::new();
+
+    let closing = |open_tags: &[&str]| -> String {
+        open_tags
+            .iter()
+            .rev()
+            .map(|t| format!("</{}>", tag_name(t)))
+            .collect()
+    };
+
+    for token in tokenize(line) {
+        match token {
+            Token::Tag(tag) => {
+                if tag.starts_with("</") {
+                    if let Some(pos) = open_tags.iter().rposition(|t| tag_name(t) == tag_name(tag))
+                    {
+                        open_tags.remove(pos);
+                    }
+                } else if !tag.ends_with("/>") {
+                    // Leave room to close this tag as well as the ones already open
+                    let reserve = telegram_len(&closing(&open_tags)) + tag_name(tag).len() + 3;
+                    if telegram_len(&current) + telegram_len(tag) + reserve > limit
+                        && !current.is_empty()
+                    {
+                        current.push_str(&closing(&open_tags));
+                        pieces.push(std::mem::take(&mut current));
+                        current = open_tags.concat();
+                    }
+                    open_tags.push(tag);
+                }
+                current.push_str(tag);
+            }
+            Token::Text(text) => {
+                // Words keep their trailing space so the text can be rejoined as is
+                for word in text.split_inclusive(' ') {
+                    let reserve = telegram_len(&closing(&open_tags));
+                    if telegram_len(&current) + telegram_len(word) + reserve > limit
+                        && !current.is_empty()
+                    {
+                        current.push_str(&closing(&open_tags));
+                        pieces.push(std::mem::take(&mut current));
+                        current = open_tags.concat();
+                    }
+                    current.push_str(word);
+                }
+            }
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
@@ -8,9 +8,35 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20251011_000001_create_users_table::Migration),
             Box::new(m20251011_000002_create_cached_articles_table::Migration),
+            Box::new(m20251020_000003_create_saved_words_table::Migration),
+            Box::new(m20251021_000004_create_cached_conjugations_table::Migration),
+            Box::new(m20251022_000005_add_auto_detect_to_users::Migration),
+            Box::new(m20251023_000006_create_lookup_stats_table::Migration),
+            Box::new(m20251024_000007_create_offline_index_tables::Migration),
+            Box::new(m20251025_000008_create_wiktionary_tables::Migration),
+            Box::new(m20251026_000009_add_variant_to_users::Migration),
+            Box::new(m20251027_000010_add_pronunciation_to_cached_articles::Migration),
+            Box::new(m20251028_000011_add_group_mode_to_users::Migration),
+            Box::new(m20251029_000012_create_chat_member_settings_table::Migration),
+            Box::new(m20251030_000013_add_locale_to_users::Migration),
+            Box::new(m20251031_000014_make_users_direction_nullable::Migration),
+            Box::new(m20251101_000015_add_user_id_to_saved_words::Migration),
         ]
     }
 }
 
 pub mod m20251011_000001_create_users_table;
 pub mod m20251011_000002_create_cached_articles_table;
+pub mod m20251020_000003_create_saved_words_table;
+pub mod m20251021_000004_create_cached_conjugations_table;
+pub mod m20251022_000005_add_auto_detect_to_users;
+pub mod m20251023_000006_create_lookup_stats_table;
+pub mod m20251024_000007_create_offline_index_tables;
+pub mod m20251025_000008_create_wiktionary_tables;
+pub mod m20251026_000009_add_variant_to_users;
+pub mod m20251027_000010_add_pronunciation_to_cached_articles;
+pub mod m20251028_000011_add_group_mode_to_users;
+pub mod m20251029_000012_create_chat_member_settings_table;
+pub mod m20251030_000013_add_locale_to_users;
+pub mod m20251031_000014_make_users_direction_nullable;
+pub mod m20251101_000015_add_user_id_to_saved_words;
@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedWords::Table)
+                    .if_not_exists()
+                    .col(pk_auto(SavedWords::Id))
+                    .col(string(SavedWords::ChatId))
+                    .col(string(SavedWords::Word))
+                    .col(string(SavedWords::LangDirection))
+                    .col(timestamp(SavedWords::CreatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_saved_words_chat_word_dir")
+                    .table(SavedWords::Table)
+                    .col(SavedWords::ChatId)
+                    .col(SavedWords::Word)
+                    .col(SavedWords::LangDirection)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(SavedWords::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavedWords {
+    Table,
+    Id,
+    ChatId,
+    Word,
+    LangDirection,
+    CreatedAt,
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CachedConjugations::Table)
+                    .if_not_exists()
+                    .col(pk_auto(CachedConjugations::Id))
+                    .col(string(CachedConjugations::Verb))
+                    .col(string(CachedConjugations::Lang))
+                    .col(text(CachedConjugations::Data))
+                    .col(
+                        timestamp(CachedConjugations::CreatedAt).default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(CachedConjugations::UpdatedAt).default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_cached_conjugations_verb_lang")
+                    .table(CachedConjugations::Table)
+                    .col(CachedConjugations::Verb)
+                    .col(CachedConjugations::Lang)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(CachedConjugations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum CachedConjugations {
+    Table,
+    Id,
+    Verb,
+    Lang,
+    Data,
+    CreatedAt,
+    UpdatedAt,
+}
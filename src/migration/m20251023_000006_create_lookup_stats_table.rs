@@ -0,0 +1,35 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(LookupStats::Table)
+                    .if_not_exists()
+                    .col(string(LookupStats::Day).primary_key())
+                    .col(big_integer(LookupStats::Lookups).default(0))
+                    .col(big_integer(LookupStats::CacheHits).default(0))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LookupStats::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LookupStats {
+    Table,
+    Day,
+    Lookups,
+    CacheHits,
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(OfflineDictionaries::Table)
+                    .if_not_exists()
+                    .col(pk_auto(OfflineDictionaries::Id))
+                    .col(string_uniq(OfflineDictionaries::Path))
+                    .col(string(OfflineDictionaries::LangDirection))
+                    .col(string(OfflineDictionaries::Format))
+                    .col(string(OfflineDictionaries::Name))
+                    .col(string(OfflineDictionaries::DataPath))
+                    .col(string(OfflineDictionaries::ContentType))
+                    .col(big_integer(OfflineDictionaries::FileSize))
+                    .col(big_integer(OfflineDictionaries::ModifiedAt))
+                    .col(
+                        timestamp(OfflineDictionaries::IndexedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(OfflineHeadwords::Table)
+                    .if_not_exists()
+                    .col(pk_auto(OfflineHeadwords::Id))
+                    .col(integer(OfflineHeadwords::DictionaryId))
+                    .col(string(OfflineHeadwords::Headword))
+                    .col(big_integer(OfflineHeadwords::Offset))
+                    .col(big_integer(OfflineHeadwords::Size))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_offline_headwords_headword")
+                    .table(OfflineHeadwords::Table)
+                    .col(OfflineHeadwords::Headword)
+                    .col(OfflineHeadwords::DictionaryId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_offline_headwords_dictionary_id")
+                    .table(OfflineHeadwords::Table)
+                    .col(OfflineHeadwords::DictionaryId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OfflineHeadwords::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(OfflineDictionaries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OfflineDictionaries {
+    Table,
+    Id,
+    Path,
+    LangDirection,
+    Format,
+    Name,
+    DataPath,
+    ContentType,
+    FileSize,
+    ModifiedAt,
+    IndexedAt,
+}
+
+#[derive(DeriveIden)]
+enum OfflineHeadwords {
+    Table,
+    Id,
+    DictionaryId,
+    Headword,
+    Offset,
+    Size,
+}
@@ -0,0 +1,147 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(WiktionaryImports::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WiktionaryImports::Id))
+                    .col(string(WiktionaryImports::Path))
+                    .col(string_uniq(WiktionaryImports::Checksum))
+                    .col(big_integer(WiktionaryImports::Entries))
+                    .col(
+                        timestamp(WiktionaryImports::ImportedAt).default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WiktionaryEntries::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WiktionaryEntries::Id))
+                    .col(string(WiktionaryEntries::LangCode))
+                    .col(string(WiktionaryEntries::Word))
+                    .col(string(WiktionaryEntries::Headword))
+                    .col(string(WiktionaryEntries::Pos))
+                    .col(integer(WiktionaryEntries::EtymologyNumber))
+                    .col(text_null(WiktionaryEntries::Etymology))
+                    .col(text(WiktionaryEntries::Ipa))
+                    .col(text(WiktionaryEntries::Inflections))
+                    .col(string(WiktionaryEntries::Checksum))
+                    .col(timestamp(WiktionaryEntries::UpdatedAt).default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_wiktionary_entries_key")
+                    .table(WiktionaryEntries::Table)
+                    .col(WiktionaryEntries::LangCode)
+                    .col(WiktionaryEntries::Word)
+                    .col(WiktionaryEntries::Pos)
+                    .col(WiktionaryEntries::EtymologyNumber)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_wiktionary_entries_headword")
+                    .table(WiktionaryEntries::Table)
+                    .col(WiktionaryEntries::LangCode)
+                    .col(WiktionaryEntries::Headword)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(WiktionarySenses::Table)
+                    .if_not_exists()
+                    .col(pk_auto(WiktionarySenses::Id))
+                    .col(integer(WiktionarySenses::EntryId))
+                    .col(integer(WiktionarySenses::Position))
+                    .col(text(WiktionarySenses::Glosses))
+                    .col(text(WiktionarySenses::Tags))
+                    .col(text(WiktionarySenses::Examples))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_wiktionary_senses_entry_id")
+                    .table(WiktionarySenses::Table)
+                    .col(WiktionarySenses::EntryId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(WiktionarySenses::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WiktionaryEntries::Table).to_owned())
+            .await?;
+        manager
+            .drop_table(Table::drop().table(WiktionaryImports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum WiktionaryImports {
+    Table,
+    Id,
+    Path,
+    Checksum,
+    Entries,
+    ImportedAt,
+}
+
+#[derive(DeriveIden)]
+enum WiktionaryEntries {
+    Table,
+    Id,
+    LangCode,
+    Word,
+    Headword,
+    Pos,
+    EtymologyNumber,
+    Etymology,
+    Ipa,
+    Inflections,
+    Checksum,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum WiktionarySenses {
+    Table,
+    Id,
+    EntryId,
+    Position,
+    Glosses,
+    Tags,
+    Examples,
+}
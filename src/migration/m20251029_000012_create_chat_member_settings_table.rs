@@ -0,0 +1,58 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ChatMemberSettings::Table)
+                    .if_not_exists()
+                    .col(pk_auto(ChatMemberSettings::Id))
+                    .col(string(ChatMemberSettings::ChatId))
+                    .col(big_integer(ChatMemberSettings::UserId))
+                    .col(string(ChatMemberSettings::TranslationDirection))
+                    .col(
+                        timestamp(ChatMemberSettings::CreatedAt).default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp(ChatMemberSettings::UpdatedAt).default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_chat_member_settings_chat_user")
+                    .table(ChatMemberSettings::Table)
+                    .col(ChatMemberSettings::ChatId)
+                    .col(ChatMemberSettings::UserId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ChatMemberSettings::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ChatMemberSettings {
+    Table,
+    Id,
+    ChatId,
+    UserId,
+    TranslationDirection,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,101 @@
+use crate::constants::DEFAULT_LANG_DIRECTION;
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Makes a chat's translation direction optional: a group only has one once
+/// it is set for the whole group, and its members' own directions apply
+/// until then. SQLite cannot change a column's nullability, so the table is
+/// rebuilt.
+///
+/// Existing rows keep their direction. Groups stored before this had the
+/// default written by any setting, which cannot be told apart from one an
+/// admin chose with /flip, so only groups created from now on start unset.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        rebuild_users(manager, string_null(Users::TranslationDirection)).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(&format!(
+                "UPDATE users SET translation_direction = '{}' \
+                 WHERE translation_direction IS NULL",
+                DEFAULT_LANG_DIRECTION
+            ))
+            .await?;
+        rebuild_users(
+            manager,
+            string(Users::TranslationDirection)
+                .default(DEFAULT_LANG_DIRECTION)
+                .to_owned(),
+        )
+        .await
+    }
+}
+
+/// Copies the users table into one whose direction column is `direction`.
+async fn rebuild_users(manager: &SchemaManager<'_>, direction: ColumnDef) -> Result<(), DbErr> {
+    manager
+        .create_table(
+            Table::create()
+                .table(UsersNew::Table)
+                .col(string(Users::ChatId).primary_key())
+                .col(direction)
+                .col(big_integer_null(Users::UserId))
+                .col(string_null(Users::Username))
+                .col(timestamp(Users::CreatedAt).default(Expr::current_timestamp()))
+                .col(timestamp(Users::UpdatedAt).default(Expr::current_timestamp()))
+                .col(boolean(Users::AutoDetect).default(false))
+                .col(string_null(Users::PreferredVariant))
+                .col(boolean(Users::HideOtherVariant).default(false))
+                .col(string(Users::GroupMode).default("mention"))
+                .col(string_null(Users::Locale))
+                .to_owned(),
+        )
+        .await?;
+
+    let columns = "chat_id, translation_direction, user_id, username, created_at, updated_at, \
+                   auto_detect, preferred_variant, hide_other_variant, group_mode, locale";
+    manager
+        .get_connection()
+        .execute_unprepared(&format!(
+            "INSERT INTO users_new ({columns}) SELECT {columns} FROM users"
+        ))
+        .await?;
+
+    manager
+        .drop_table(Table::drop().table(Users::Table).to_owned())
+        .await?;
+    manager
+        .rename_table(
+            Table::rename()
+                .table(UsersNew::Table, Users::Table)
+                .to_owned(),
+        )
+        .await
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    ChatId,
+    TranslationDirection,
+    UserId,
+    Username,
+    CreatedAt,
+    UpdatedAt,
+    AutoDetect,
+    PreferredVariant,
+    HideOtherVariant,
+    GroupMode,
+    Locale,
+}
+
+#[derive(DeriveIden)]
+enum UsersNew {
+    Table,
+}
@@ -0,0 +1,104 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+/// Keys saved words by the member who saved them, so that each person in a
+/// group keeps their own vocabulary. Words saved in private chats belong to
+/// the chat's user; words saved in groups before now have no owner and stay
+/// shared by the whole group.
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SavedWords::Table)
+                    .add_column(big_integer_null(SavedWords::UserId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // A private chat's id is its user's id
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE saved_words SET user_id = CAST(chat_id AS INTEGER) \
+                 WHERE chat_id NOT LIKE '-%'",
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_saved_words_chat_word_dir")
+                    .table(SavedWords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_saved_words_chat_user_word_dir")
+                    .table(SavedWords::Table)
+                    .col(SavedWords::ChatId)
+                    .col(SavedWords::UserId)
+                    .col(SavedWords::Word)
+                    .col(SavedWords::LangDirection)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Members of a group may have saved the same word; keep the first
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "DELETE FROM saved_words WHERE id NOT IN \
+                 (SELECT MIN(id) FROM saved_words GROUP BY chat_id, word, lang_direction)",
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_saved_words_chat_user_word_dir")
+                    .table(SavedWords::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(SavedWords::Table)
+                    .drop_column(SavedWords::UserId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_saved_words_chat_word_dir")
+                    .table(SavedWords::Table)
+                    .col(SavedWords::ChatId)
+                    .col(SavedWords::Word)
+                    .col(SavedWords::LangDirection)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavedWords {
+    Table,
+    ChatId,
+    UserId,
+    Word,
+    LangDirection,
+}
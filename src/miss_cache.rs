@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long WordReference's answer that a word has no entry is trusted.
+pub const MISS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Remembers the words WordReference has no entry for, so that looking one
+/// up again (or an inflected form that has no entry of its own) goes straight
+/// to the next source instead of fetching the page again. Cheap to clone;
+/// clones share the same entries.
+#[derive(Clone)]
+pub struct MissCache {
+    ttl: Duration,
+    /// Keyed by word and direction
+    misses: Arc<Mutex<HashMap<(String, String), Instant>>>,
+}
+
+impl MissCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            misses: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records that WordReference has no entry for a word in a direction.
+    pub fn record(&self, word: &str, lang_direction: &str) {
+        self.record_at(word, lang_direction, Instant::now());
+    }
+
+    pub fn record_at(&self, word: &str, lang_direction: &str, now: Instant) {
+        let mut misses = self.misses.lock().unwrap();
+        // Expired entries are dropped as new ones come in
+        misses.retain(|_, recorded| now.saturating_duration_since(*recorded) < self.ttl);
+        misses.insert((word.to_string(), lang_direction.to_string()), now);
+    }
+
+    /// Whether WordReference recently had no entry for the word.
+    pub fn contains(&self, word: &str, lang_direction: &str) -> bool {
+        self.contains_at(word, lang_direction, Instant::now())
+    }
+
+    pub fn contains_at(&self, word: &str, lang_direction: &str, now: Instant) -> bool {
+        let misses = self.misses.lock().unwrap();
+        misses
+            .get(&(word.to_string(), lang_direction.to_string()))
+            .is_some_and(|recorded| now.saturating_duration_since(*recorded) < self.ttl)
+    }
+
+    /// Number of entries held, expired ones included until the next record.
+    pub fn len(&self) -> usize {
+        self.misses.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
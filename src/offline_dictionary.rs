@@ -0,0 +1,756 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use sha1::{Digest, Sha1};
+
+use crate::fetch_translations::TranslationEntry;
+use crate::languages;
+use crate::offline_index_repository::{HeadwordMatch, NewDictionary, OfflineIndexRepository};
+use crate::query::normalize_query;
+use crate::render::{self, escape_html};
+
+pub type OfflineError = Box<dyn std::error::Error + Send + Sync>;
+
+/// When the offline dictionaries are consulted.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OfflineMode {
+    /// Only when WordReference fails or has no entry
+    Fallback,
+    /// Before WordReference, which is then only asked for words they lack
+    Primary,
+}
+
+/// Local dictionaries to use, read from the environment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfflineConfig {
+    /// `OFFLINE_DICTIONARIES`: `direction=path` pairs separated by `;`, e.g.
+    /// `pten=/dicts/pt-en.ifo;enpt=/dicts/en-pt.dsl`. A direction may repeat.
+    pub dictionaries: Vec<(String, PathBuf)>,
+    /// `OFFLINE_MODE`: `fallback` (default) or `primary`.
+    pub mode: OfflineMode,
+}
+
+impl OfflineConfig {
+    pub fn from_env() -> Self {
+        let mode = match std::env::var("OFFLINE_MODE") {
+            Ok(value) if value.trim().eq_ignore_ascii_case("primary") => OfflineMode::Primary,
+            _ => OfflineMode::Fallback,
+        };
+        Self {
+            dictionaries: parse_offline_dictionaries(
+                &std::env::var("OFFLINE_DICTIONARIES").unwrap_or_default(),
+            ),
+            mode,
+        }
+    }
+}
+
+/// Parses `direction=path` pairs separated by `;`, skipping entries with an
+/// unknown direction or no path.
+pub fn parse_offline_dictionaries(value: &str) -> Vec<(String, PathBuf)> {
+    value
+        .split(';')
+        .filter_map(|entry| {
+            let (direction, path) = entry.split_once('=')?;
+            let (direction, path) = (direction.trim(), path.trim());
+            if languages::direction(direction).is_none() || path.is_empty() {
+                log::warn!("Ignoring offline dictionary entry {:?}", entry.trim());
+                return None;
+            }
+            Some((direction.to_string(), PathBuf::from(path)))
+        })
+        .collect()
+}
+
+/// Supported dictionary formats, told apart by the configured file's extension.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DictionaryFormat {
+    /// `.ifo`, with `.idx` (or `.idx.gz`) and `.dict` (or `.dict.dz`) beside it
+    StarDict,
+    /// ABBYY Lingvo `.dsl` or `.dsl.dz`
+    Dsl,
+}
+
+impl DictionaryFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_lowercase();
+        if name.ends_with(".ifo") {
+            Some(Self::StarDict)
+        } else if name.ends_with(".dsl") || name.ends_with(".dsl.dz") {
+            Some(Self::Dsl)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::StarDict => "stardict",
+            Self::Dsl => "dsl",
+        }
+    }
+}
+
+/// A dictionary file read for indexing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScannedDictionary {
+    pub name: String,
+    /// File the article offsets point into
+    pub data_path: PathBuf,
+    /// StarDict `sametypesequence`, empty when absent and for DSL
+    pub content_type: String,
+    /// Normalized headword, offset and size of its article
+    pub headwords: Vec<(String, i64, i64)>,
+}
+
+/// Reads a dictionary's headwords. Compressed data files are decompressed into
+/// `data_dir` first, so that articles can later be read by offset.
+pub fn scan_dictionary(path: &Path, data_dir: &Path) -> Result<ScannedDictionary, OfflineError> {
+    match DictionaryFormat::from_path(path) {
+        Some(DictionaryFormat::StarDict) => scan_stardict(path, data_dir),
+        Some(DictionaryFormat::Dsl) => scan_dsl(path, data_dir),
+        None => Err(format!("Unsupported dictionary file: {}", path.display()).into()),
+    }
+}
+
+fn scan_stardict(ifo_path: &Path, data_dir: &Path) -> Result<ScannedDictionary, OfflineError> {
+    let ifo = parse_ifo(&std::fs::read_to_string(ifo_path)?)?;
+    let offset_bits: u32 = ifo
+        .get("idxoffsetbits")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(32);
+
+    let base = ifo_path.with_extension("");
+    let idx = read_maybe_gzipped(&with_suffix(&base, ".idx"), &with_suffix(&base, ".idx.gz"))?;
+    let data_path = plain_or_decompressed(
+        &with_suffix(&base, ".dict"),
+        &with_suffix(&base, ".dict.dz"),
+        data_dir,
+    )?;
+
+    let headwords = parse_stardict_idx(&idx, offset_bits)?
+        .into_iter()
+        .map(|(word, offset, size)| (normalize_query(&word), offset as i64, size as i64))
+        .collect();
+
+    Ok(ScannedDictionary {
+        name: ifo
+            .get("bookname")
+            .cloned()
+            .unwrap_or_else(|| file_stem(ifo_path)),
+        data_path,
+        content_type: ifo.get("sametypesequence").cloned().unwrap_or_default(),
+        headwords,
+    })
+}
+
+/// Parses the `key=value` lines of a StarDict `.ifo` file.
+pub fn parse_ifo(text: &str) -> Result<HashMap<String, String>, OfflineError> {
+    let mut lines = text.trim_start_matches('\u{feff}').lines();
+    if lines.next().map(str::trim) != Some("StarDict's dict ifo file") {
+        return Err("Not a StarDict .ifo file".into());
+    }
+    Ok(lines
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect())
+}
+
+/// Splits a StarDict `.idx` file into `(headword, offset, size)` entries.
+pub fn parse_stardict_idx(
+    idx: &[u8],
+    offset_bits: u32,
+) -> Result<Vec<(String, u64, u64)>, OfflineError> {
+    let offset_len = if offset_bits == 64 { 8 } else { 4 };
+    let mut entries = Vec::new();
+    let mut rest = idx;
+
+    while !rest.is_empty() {
+        let end = rest
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or("Truncated .idx file")?;
+        let word = String::from_utf8_lossy(&rest[..end]).into_owned();
+        let fields = rest
+            .get(end + 1..end + 1 + offset_len + 4)
+            .ok_or("Truncated .idx file")?;
+        let offset = if offset_len == 8 {
+            u64::from_be_bytes(fields[..8].try_into().unwrap())
+        } else {
+            u32::from_be_bytes(fields[..4].try_into().unwrap()) as u64
+        };
+        let size = u32::from_be_bytes(fields[offset_len..].try_into().unwrap()) as u64;
+
+        entries.push((word, offset, size));
+        rest = &rest[end + 1 + offset_len + 4..];
+    }
+    Ok(entries)
+}
+
+fn scan_dsl(path: &Path, data_dir: &Path) -> Result<ScannedDictionary, OfflineError> {
+    let data_path = if path.to_string_lossy().to_lowercase().ends_with(".dz") {
+        decompress_to(path, data_dir)?
+    } else {
+        path.to_path_buf()
+    };
+    let bytes = std::fs::read(&data_path)?;
+    let dsl = parse_dsl(&bytes);
+
+    Ok(ScannedDictionary {
+        name: dsl.name.unwrap_or_else(|| file_stem(path)),
+        data_path,
+        content_type: String::new(),
+        headwords: dsl.headwords,
+    })
+}
+
+/// Headwords and name of a DSL file.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DslIndex {
+    /// From the `#NAME` header
+    pub name: Option<String>,
+    /// Normalized headword, byte offset and size of its article body
+    pub headwords: Vec<(String, i64, i64)>,
+}
+
+/// Text encodings DSL files come in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DslEncoding {
+    Utf8,
+    Utf16Le,
+}
+
+impl DslEncoding {
+    /// Detects the encoding from a byte order mark or, lacking one, from NUL
+    /// bytes in the first characters. Returns the length of the BOM too.
+    fn detect(bytes: &[u8]) -> (Self, usize) {
+        if bytes.starts_with(&[0xff, 0xfe]) {
+            (Self::Utf16Le, 2)
+        } else if bytes.starts_with(&[0xef, 0xbb, 0xbf]) {
+            (Self::Utf8, 3)
+        } else if bytes.len() >= 2 && bytes[1] == 0 {
+            (Self::Utf16Le, 0)
+        } else {
+            (Self::Utf8, 0)
+        }
+    }
+
+    fn unit_len(self) -> usize {
+        match self {
+            Self::Utf8 => 1,
+            Self::Utf16Le => 2,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Utf16Le => {
+                let units: Vec<u16> = bytes
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                    .collect();
+                String::from_utf16_lossy(&units)
+            }
+        }
+    }
+}
+
+/// Splits raw DSL bytes into lines, keeping each line's byte offset and length
+/// (without the line break).
+fn dsl_lines(bytes: &[u8]) -> Vec<(usize, usize, String)> {
+    let (encoding, bom) = DslEncoding::detect(bytes);
+    let unit = encoding.unit_len();
+    let is_newline = |i: usize| match encoding {
+        DslEncoding::Utf8 => bytes[i] == b'\n',
+        DslEncoding::Utf16Le => bytes[i] == b'\n' && bytes[i + 1] == 0,
+    };
+
+    let mut lines = Vec::new();
+    let mut start = bom;
+    let mut i = bom;
+    while i + unit <= bytes.len() {
+        if is_newline(i) {
+            lines.push(dsl_line(bytes, encoding, start, i));
+            start = i + unit;
+        }
+        i += unit;
+    }
+    if start < bytes.len() {
+        lines.push(dsl_line(bytes, encoding, start, bytes.len()));
+    }
+    lines
+}
+
+fn dsl_line(
+    bytes: &[u8],
+    encoding: DslEncoding,
+    start: usize,
+    end: usize,
+) -> (usize, usize, String) {
+    let text = encoding.decode(&bytes[start..end]);
+    let trimmed = text.trim_end_matches('\r');
+    let len = end - start - (text.len() - trimmed.len()) * encoding.unit_len();
+    (start, len, trimmed.to_string())
+}
+
+/// Indexes a DSL file: the `#NAME` header and, for each article, its headword
+/// lines and the byte range of its indented body.
+pub fn parse_dsl(bytes: &[u8]) -> DslIndex {
+    let mut index = DslIndex::default();
+    let mut pending: Vec<String> = Vec::new();
+    let mut body: Option<(usize, usize)> = None;
+
+    for (offset, len, line) in dsl_lines(bytes) {
+        if line.starts_with([' ', '\t']) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            body = Some(match body {
+                Some((start, _)) => (start, offset + len),
+                None => (offset, offset + len),
+            });
+        } else if line.trim().is_empty() {
+            flush_dsl_article(&mut pending, &mut body, &mut index);
+        } else if let Some(header) = line.strip_prefix('#') {
+            if let Some(name) = header.strip_prefix("NAME") {
+                index.name = Some(name.trim().trim_matches('"').to_string());
+            }
+        } else {
+            // A headword line after a body starts the next article
+            if body.is_some() {
+                flush_dsl_article(&mut pending, &mut body, &mut index);
+            }
+            pending.push(line);
+        }
+    }
+    flush_dsl_article(&mut pending, &mut body, &mut index);
+
+    index
+}
+
+/// Indexes the pending headwords of a DSL article under its body, if it has one.
+fn flush_dsl_article(
+    pending: &mut Vec<String>,
+    body: &mut Option<(usize, usize)>,
+    index: &mut DslIndex,
+) {
+    if let Some((start, end)) = body.take() {
+        for raw in pending.iter() {
+            for headword in dsl_headwords(raw) {
+                index
+                    .headwords
+                    .push((headword, start as i64, (end - start) as i64));
+            }
+        }
+    }
+    pending.clear();
+}
+
+/// The lookup forms of a DSL headword. `{unsorted}` parts are dropped and
+/// `(optional)` parts give one form with and one without them.
+pub fn dsl_headwords(raw: &str) -> Vec<String> {
+    let mut with_optional = String::new();
+    let mut without_optional = String::new();
+    let mut in_unsorted = false;
+    let mut in_optional = false;
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next()
+                    && !in_unsorted
+                {
+                    with_optional.push(escaped);
+                    if !in_optional {
+                        without_optional.push(escaped);
+                    }
+                }
+            }
+            '{' => in_unsorted = true,
+            '}' => in_unsorted = false,
+            '(' if !in_unsorted => in_optional = true,
+            ')' if !in_unsorted => in_optional = false,
+            _ if in_unsorted => {}
+            _ => {
+                with_optional.push(c);
+                if !in_optional {
+                    without_optional.push(c);
+                }
+            }
+        }
+    }
+
+    let mut forms = vec![normalize_query(&with_optional)];
+    let without_optional = normalize_query(&without_optional);
+    if without_optional != forms[0] {
+        forms.push(without_optional);
+    }
+    forms.retain(|form| !form.is_empty());
+    forms
+}
+
+/// Converts a DSL article body to plain text, one line per body line. `~`
+/// stands for the headword.
+pub fn dsl_to_text(body: &str, headword: &str) -> String {
+    let mut lines = Vec::new();
+
+    for line in body.lines() {
+        let mut text = String::new();
+        let mut chars = line.trim().chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => text.extend(chars.next()),
+                '[' => {
+                    // Markup tag: skip to its closing bracket
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            break;
+                        }
+                    }
+                }
+                '{' if chars.peek() == Some(&'{') => {
+                    // {{comment}}
+                    let mut previous = ' ';
+                    for c in chars.by_ref() {
+                        if previous == '}' && c == '}' {
+                            break;
+                        }
+                        previous = c;
+                    }
+                }
+                '<' if chars.peek() == Some(&'<') => {
+                    chars.next();
+                }
+                '>' if chars.peek() == Some(&'>') => {
+                    chars.next();
+                }
+                '~' => text.push_str(headword),
+                _ => text.push(c),
+            }
+        }
+
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !text.is_empty() {
+            lines.push(text);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Converts a StarDict article to plain text. `content_type` is the
+/// dictionary's `sametypesequence`; without one, each field carries its type.
+pub fn stardict_to_text(data: &[u8], content_type: &str) -> String {
+    let mut fields: Vec<(char, &[u8])> = Vec::new();
+    let mut rest = data;
+
+    let mut types = content_type.chars().peekable();
+    loop {
+        if rest.is_empty() {
+            break;
+        }
+        let (kind, last) = if content_type.is_empty() {
+            let kind = rest[0] as char;
+            rest = &rest[1..];
+            (kind, false)
+        } else {
+            match types.next() {
+                Some(kind) => (kind, types.peek().is_none()),
+                None => break,
+            }
+        };
+
+        if kind.is_ascii_lowercase() {
+            // Text: NUL-terminated, except the last field of a sametypesequence
+            let end = if last {
+                rest.len()
+            } else {
+                rest.iter().position(|&b| b == 0).unwrap_or(rest.len())
+            };
+            fields.push((kind, &rest[..end]));
+            rest = rest.get(end + 1..).unwrap_or_default();
+        } else {
+            // Binary resources are prefixed with their size, except when last
+            if last || rest.len() < 4 {
+                break;
+            }
+            let size = u32::from_be_bytes(rest[..4].try_into().unwrap()) as usize;
+            rest = rest.get(4 + size..).unwrap_or_default();
+        }
+    }
+
+    fields
+        .into_iter()
+        .filter_map(|(kind, bytes)| {
+            let text = String::from_utf8_lossy(bytes);
+            match kind {
+                'm' | 'l' | 't' | 'y' => Some(text.into_owned()),
+                'g' | 'h' | 'x' | 'k' | 'w' => Some(markup_to_text(&text)),
+                _ => None,
+            }
+        })
+        .map(|text| {
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Plain text of HTML-like markup, keeping line and paragraph breaks.
+fn markup_to_text(markup: &str) -> String {
+    let mut text = markup.to_string();
+    for tag in ["<br>", "<br/>", "<br />", "<BR>", "</p>", "</div>", "</li>"] {
+        text = text.replace(tag, "\n");
+    }
+    render::to_plain_text(&text)
+}
+
+/// An article from a local dictionary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfflineArticle {
+    pub headword: String,
+    pub dictionary: String,
+    /// Plain text, one definition or example per line
+    pub text: String,
+}
+
+impl OfflineArticle {
+    /// The article as a Telegram HTML reply, naming the dictionary.
+    pub fn to_html(&self) -> String {
+        format!(
+            "📖 <i>{}</i>\n<b>{}</b>\n{}\n",
+            escape_html(&self.dictionary),
+            escape_html(&self.headword),
+            escape_html(&self.text)
+        )
+    }
+
+    /// One translation row per line, for glossaries.
+    pub fn entries(&self) -> Vec<TranslationEntry> {
+        self.text
+            .lines()
+            .map(|line| TranslationEntry {
+                word: self.headword.clone(),
+                translation: line.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+}
+
+/// Looks words up in the configured local dictionaries through their index.
+#[derive(Clone)]
+pub struct OfflineProvider {
+    index_repo: OfflineIndexRepository,
+    mode: OfflineMode,
+}
+
+impl OfflineProvider {
+    pub fn new(index_repo: OfflineIndexRepository, mode: OfflineMode) -> Self {
+        Self { index_repo, mode }
+    }
+
+    pub fn mode(&self) -> OfflineMode {
+        self.mode
+    }
+
+    /// Indexes every configured dictionary that is new or changed since it
+    /// was last indexed, and drops the index of unconfigured ones. A
+    /// dictionary that fails to index is logged and skipped.
+    /// Returns the number of dictionaries (re)indexed.
+    pub async fn prepare(
+        &self,
+        dictionaries: &[(String, PathBuf)],
+        data_dir: &Path,
+    ) -> Result<usize, OfflineError> {
+        let paths: Vec<String> = dictionaries
+            .iter()
+            .map(|(_, path)| path.to_string_lossy().into_owned())
+            .collect();
+        self.index_repo.remove_except(&paths).await?;
+
+        let mut indexed = 0;
+        for (direction, path) in dictionaries {
+            match self.index_dictionary(direction, path, data_dir).await {
+                Ok(true) => indexed += 1,
+                Ok(false) => {}
+                Err(e) => log::error!("Failed to index {}: {}", path.display(), e),
+            }
+        }
+        Ok(indexed)
+    }
+
+    /// Indexes one dictionary unless its index is current. Returns whether it did.
+    pub async fn index_dictionary(
+        &self,
+        direction: &str,
+        path: &Path,
+        data_dir: &Path,
+    ) -> Result<bool, OfflineError> {
+        let key = path.to_string_lossy().into_owned();
+        let metadata = std::fs::metadata(path)?;
+        let file_size = metadata.len() as i64;
+        let modified_at = metadata
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+
+        if let Some(existing) = self.index_repo.get_dictionary(&key).await?
+            && existing.lang_direction == direction
+            && existing.file_size == file_size
+            && existing.modified_at == modified_at
+        {
+            return Ok(false);
+        }
+
+        let format = DictionaryFormat::from_path(path)
+            .ok_or_else(|| format!("Unsupported dictionary file: {}", path.display()))?;
+        let (path_buf, data_dir) = (path.to_path_buf(), data_dir.to_path_buf());
+        let scanned =
+            tokio::task::spawn_blocking(move || scan_dictionary(&path_buf, &data_dir)).await??;
+
+        let dictionary = NewDictionary {
+            path: key,
+            lang_direction: direction.to_string(),
+            format: format.as_str().to_string(),
+            name: scanned.name,
+            data_path: scanned.data_path.to_string_lossy().into_owned(),
+            content_type: scanned.content_type,
+            file_size,
+            modified_at,
+        };
+        self.index_repo
+            .replace_index(&dictionary, &scanned.headwords)
+            .await?;
+        log::info!(
+            "Indexed {} headwords of {} ({})",
+            scanned.headwords.len(),
+            dictionary.name,
+            direction
+        );
+        Ok(true)
+    }
+
+    /// The word's articles in the direction's dictionaries, joined into one,
+    /// or None when no dictionary has it.
+    pub async fn lookup(
+        &self,
+        word: &str,
+        lang_direction: &str,
+    ) -> Result<Option<OfflineArticle>, OfflineError> {
+        let matches = self
+            .index_repo
+            .find(lang_direction, &normalize_query(word))
+            .await?;
+        let Some(first) = matches.first() else {
+            return Ok(None);
+        };
+        let dictionary = first.dictionary.name.clone();
+
+        let headword = word.to_string();
+        let texts =
+            tokio::task::spawn_blocking(move || read_articles(&matches, &headword)).await??;
+        if texts.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(OfflineArticle {
+            headword: word.to_string(),
+            dictionary,
+            text: texts.join("\n"),
+        }))
+    }
+}
+
+/// Reads and converts the matched articles, skipping empty and repeated ones.
+fn read_articles(matches: &[HeadwordMatch], word: &str) -> Result<Vec<String>, OfflineError> {
+    let mut texts: Vec<String> = Vec::new();
+    for m in matches {
+        let bytes = read_range(
+            Path::new(&m.dictionary.data_path),
+            m.offset as u64,
+            m.size as u64,
+        )?;
+        let text = match m.dictionary.format.as_str() {
+            "dsl" => {
+                let head = read_range(Path::new(&m.dictionary.data_path), 0, 3)?;
+                dsl_to_text(&DslEncoding::detect(&head).0.decode(&bytes), word)
+            }
+            _ => stardict_to_text(&bytes, &m.dictionary.content_type),
+        };
+        if !text.is_empty() && !texts.contains(&text) {
+            texts.push(text);
+        }
+    }
+    Ok(texts)
+}
+
+fn read_range(path: &Path, offset: u64, size: u64) -> Result<Vec<u8>, OfflineError> {
+    let mut file = File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut bytes = Vec::with_capacity(size as usize);
+    file.take(size).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn with_suffix(base: &Path, suffix: &str) -> PathBuf {
+    let mut path = base.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Reads `plain`, or decompresses `gzipped` when only that exists.
+fn read_maybe_gzipped(plain: &Path, gzipped: &Path) -> Result<Vec<u8>, OfflineError> {
+    if plain.exists() {
+        return Ok(std::fs::read(plain)?);
+    }
+    let mut bytes = Vec::new();
+    GzDecoder::new(File::open(gzipped)?).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// `plain` when it exists, otherwise `compressed` decompressed into `data_dir`.
+fn plain_or_decompressed(
+    plain: &Path,
+    compressed: &Path,
+    data_dir: &Path,
+) -> Result<PathBuf, OfflineError> {
+    if plain.exists() {
+        return Ok(plain.to_path_buf());
+    }
+    decompress_to(compressed, data_dir)
+}
+
+/// Decompresses a gzip (or dictzip) file into `data_dir`, under a name derived
+/// from its path so that dictionaries with the same file name don't clash.
+fn decompress_to(path: &Path, data_dir: &Path) -> Result<PathBuf, OfflineError> {
+    std::fs::create_dir_all(data_dir)?;
+    let digest = Sha1::digest(path.to_string_lossy().as_bytes());
+    let id: String = digest[..4].iter().map(|b| format!("{:02x}", b)).collect();
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().trim_end_matches(".dz").to_string())
+        .unwrap_or_default();
+    let target = data_dir.join(format!("{}-{}", id, name));
+
+    let mut decoder = GzDecoder::new(File::open(path)?);
+    let mut out = File::create(&target)?;
+    std::io::copy(&mut decoder, &mut out)?;
+    Ok(target)
+}
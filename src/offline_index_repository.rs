@@ -0,0 +1,173 @@
+use crate::entities::offline_dictionaries::{self, Entity as OfflineDictionaries};
+use crate::entities::offline_headwords::{self, Entity as OfflineHeadwords};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+
+/// Headwords inserted per statement, well under SQLite's variable limit.
+const INSERT_CHUNK: usize = 500;
+
+/// A dictionary to (re)index, with the headwords found in it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewDictionary {
+    pub path: String,
+    pub lang_direction: String,
+    pub format: String,
+    pub name: String,
+    pub data_path: String,
+    pub content_type: String,
+    pub file_size: i64,
+    pub modified_at: i64,
+}
+
+/// A headword match: the dictionary it is in and where its article is stored.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HeadwordMatch {
+    pub dictionary: offline_dictionaries::Model,
+    pub offset: i64,
+    pub size: i64,
+}
+
+#[derive(Clone)]
+pub struct OfflineIndexRepository {
+    db: DatabaseConnection,
+}
+
+impl OfflineIndexRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_dictionary(
+        &self,
+        path: &str,
+    ) -> Result<Option<offline_dictionaries::Model>, sea_orm::DbErr> {
+        OfflineDictionaries::find()
+            .filter(offline_dictionaries::Column::Path.eq(path.to_string()))
+            .one(&self.db)
+            .await
+    }
+
+    /// Replaces the index of a dictionary in one transaction, so lookups never
+    /// see a half-written index. Headwords are `(headword, offset, size)`.
+    pub async fn replace_index(
+        &self,
+        dictionary: &NewDictionary,
+        headwords: &[(String, i64, i64)],
+    ) -> Result<offline_dictionaries::Model, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+
+        if let Some(existing) = OfflineDictionaries::find()
+            .filter(offline_dictionaries::Column::Path.eq(dictionary.path.clone()))
+            .one(&txn)
+            .await?
+        {
+            OfflineHeadwords::delete_many()
+                .filter(offline_headwords::Column::DictionaryId.eq(existing.id))
+                .exec(&txn)
+                .await?;
+            OfflineDictionaries::delete_by_id(existing.id)
+                .exec(&txn)
+                .await?;
+        }
+
+        let model = offline_dictionaries::ActiveModel {
+            path: Set(dictionary.path.clone()),
+            lang_direction: Set(dictionary.lang_direction.clone()),
+            format: Set(dictionary.format.clone()),
+            name: Set(dictionary.name.clone()),
+            data_path: Set(dictionary.data_path.clone()),
+            content_type: Set(dictionary.content_type.clone()),
+            file_size: Set(dictionary.file_size),
+            modified_at: Set(dictionary.modified_at),
+            indexed_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        for chunk in headwords.chunks(INSERT_CHUNK) {
+            let rows =
+                chunk
+                    .iter()
+                    .map(|(headword, offset, size)| offline_headwords::ActiveModel {
+                        dictionary_id: Set(model.id),
+                        headword: Set(headword.clone()),
+                        offset: Set(*offset),
+                        size: Set(*size),
+                        ..Default::default()
+                    });
+            OfflineHeadwords::insert_many(rows).exec(&txn).await?;
+        }
+
+        txn.commit().await?;
+        Ok(model)
+    }
+
+    /// Deletes the index of every dictionary whose path is not in `paths`.
+    /// Returns the number of dictionaries removed.
+    pub async fn remove_except(&self, paths: &[String]) -> Result<u64, sea_orm::DbErr> {
+        let stale: Vec<i32> = OfflineDictionaries::find()
+            .filter(offline_dictionaries::Column::Path.is_not_in(paths.to_vec()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|d| d.id)
+            .collect();
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        OfflineHeadwords::delete_many()
+            .filter(offline_headwords::Column::DictionaryId.is_in(stale.clone()))
+            .exec(&self.db)
+            .await?;
+        Ok(OfflineDictionaries::delete_many()
+            .filter(offline_dictionaries::Column::Id.is_in(stale))
+            .exec(&self.db)
+            .await?
+            .rows_affected)
+    }
+
+    /// Articles for a normalized headword in the dictionaries of a direction,
+    /// in the order the dictionaries were indexed.
+    pub async fn find(
+        &self,
+        lang_direction: &str,
+        headword: &str,
+    ) -> Result<Vec<HeadwordMatch>, sea_orm::DbErr> {
+        let dictionaries = OfflineDictionaries::find()
+            .filter(offline_dictionaries::Column::LangDirection.eq(lang_direction.to_string()))
+            .all(&self.db)
+            .await?;
+        if dictionaries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let headwords = OfflineHeadwords::find()
+            .filter(offline_headwords::Column::Headword.eq(headword.to_string()))
+            .filter(
+                offline_headwords::Column::DictionaryId
+                    .is_in(dictionaries.iter().map(|d| d.id).collect::<Vec<_>>()),
+            )
+            .all(&self.db)
+            .await?;
+
+        Ok(dictionaries
+            .into_iter()
+            .flat_map(|dictionary| {
+                headwords
+                    .iter()
+                    .filter(|h| h.dictionary_id == dictionary.id)
+                    .map(|h| HeadwordMatch {
+                        dictionary: dictionary.clone(),
+                        offset: h.offset,
+                        size: h.size,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+}
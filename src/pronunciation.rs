@@ -0,0 +1,168 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use sha1::{Digest, Sha1};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use crate::query::normalize_query;
+use crate::variants::Variant;
+
+pub type TtsError = Box<dyn std::error::Error + Send + Sync>;
+
+/// How long one TTS or encoder run may take before it is killed.
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The local TTS engine, installed at startup when its commands are found.
+static SYNTHESIZER: OnceLock<Synthesizer> = OnceLock::new();
+
+pub fn set_synthesizer(synthesizer: Synthesizer) {
+    if SYNTHESIZER.set(synthesizer).is_err() {
+        log::warn!("Synthesizer already set");
+    }
+}
+
+pub fn synthesizer() -> Option<&'static Synthesizer> {
+    SYNTHESIZER.get()
+}
+
+/// espeak-ng voice for a language code. Portuguese follows the chat's
+/// preferred variant and defaults to the European voice.
+pub fn voice_for(lang: &str, variant: Option<Variant>) -> Option<&'static str> {
+    match lang {
+        "pt" if variant == Some(Variant::Brazilian) => Some("pt-br"),
+        "pt" => Some("pt"),
+        "en" => Some("en"),
+        "it" => Some("it"),
+        "es" => Some("es"),
+        "fr" => Some("fr"),
+        "de" => Some("de"),
+        _ => None,
+    }
+}
+
+/// Commands used to synthesize speech, configured with `ESPEAK_NG_PATH` and
+/// `FFMPEG_PATH` (looked up on `PATH` by default).
+#[derive(Clone, Debug, PartialEq)]
+pub struct TtsConfig {
+    pub espeak: String,
+    pub ffmpeg: String,
+}
+
+impl TtsConfig {
+    pub fn from_env() -> Self {
+        Self {
+            espeak: std::env::var("ESPEAK_NG_PATH").unwrap_or_else(|_| "espeak-ng".to_string()),
+            ffmpeg: std::env::var("FFMPEG_PATH").unwrap_or_else(|_| "ffmpeg".to_string()),
+        }
+    }
+}
+
+/// Speaks words with espeak-ng and encodes them as OGG/Opus voice notes,
+/// keeping every clip on disk.
+#[derive(Clone, Debug)]
+pub struct Synthesizer {
+    config: TtsConfig,
+    audio_dir: PathBuf,
+}
+
+impl Synthesizer {
+    pub fn new(config: TtsConfig, audio_dir: &Path) -> Self {
+        Self {
+            config,
+            audio_dir: audio_dir.to_path_buf(),
+        }
+    }
+
+    /// Whether both the TTS engine and the encoder can be run.
+    pub async fn is_available(&self) -> bool {
+        let probe = |program: &str, flag: &str| {
+            let mut command = Command::new(program);
+            command
+                .arg(flag)
+                .stdout(Stdio::null())
+                .stderr(Stdio::null());
+            command
+        };
+        let espeak = probe(&self.config.espeak, "--version").status().await;
+        let ffmpeg = probe(&self.config.ffmpeg, "-version").status().await;
+        matches!((espeak, ffmpeg), (Ok(a), Ok(b)) if a.success() && b.success())
+    }
+
+    /// Where the clip of a word in a voice is kept: one directory per voice,
+    /// one file per normalized word.
+    pub fn cache_path(&self, word: &str, voice: &str) -> PathBuf {
+        let digest = Sha1::digest(normalize_query(word).as_bytes());
+        let name: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+        self.audio_dir.join(voice).join(format!("{}.ogg", name))
+    }
+
+    /// Returns the voice note for a word, synthesizing it on first use.
+    pub async fn synthesize(&self, word: &str, voice: &str) -> Result<PathBuf, TtsError> {
+        let path = self.cache_path(word, voice);
+        if tokio::fs::try_exists(&path).await? {
+            return Ok(path);
+        }
+
+        let dir = path.parent().unwrap_or(&self.audio_dir);
+        tokio::fs::create_dir_all(dir).await?;
+
+        // Both files live next to the clip so that the final rename is atomic
+        let wav = tempfile::Builder::new().suffix(".wav").tempfile_in(dir)?;
+        let ogg = tempfile::Builder::new().suffix(".ogg").tempfile_in(dir)?;
+
+        let mut espeak = Command::new(&self.config.espeak);
+        espeak.args(["-v", voice, "--stdin", "-w"]).arg(wav.path());
+        run(espeak, Some(word)).await?;
+
+        let mut ffmpeg = Command::new(&self.config.ffmpeg);
+        ffmpeg
+            .args(["-y", "-loglevel", "error", "-i"])
+            .arg(wav.path())
+            .args(["-c:a", "libopus", "-b:a", "32k", "-f", "ogg"])
+            .arg(ogg.path());
+        run(ffmpeg, None).await?;
+
+        ogg.persist(&path)?;
+        Ok(path)
+    }
+}
+
+/// Runs a command to completion, feeding it `input` on stdin, and fails with
+/// its stderr when it exits unsuccessfully or takes too long.
+async fn run(mut command: Command, input: Option<&str>) -> Result<(), TtsError> {
+    command
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+
+    let mut child = command.spawn()?;
+    // A command that exits without reading its input is reported by its status
+    if let (Some(text), Some(mut stdin)) = (input, child.stdin.take())
+        && let Err(e) = stdin.write_all(text.as_bytes()).await
+        && e.kind() != std::io::ErrorKind::BrokenPipe
+    {
+        return Err(e.into());
+    }
+
+    let output = tokio::time::timeout(COMMAND_TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| format!("{:?} timed out", command.as_std().get_program()))??;
+    if !output.status.success() {
+        return Err(format!(
+            "{:?} failed ({}): {}",
+            command.as_std().get_program(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+    Ok(())
+}
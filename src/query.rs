@@ -0,0 +1,62 @@
+/// Most lines of a multi-line message looked up as one batch.
+pub const MAX_BATCH_LINES: usize = 50;
+
+/// Normalizes a lookup: lowercased, trimmed, with runs of whitespace inside a
+/// phrase collapsed to a single space.
+pub fn normalize_query(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Splits a message into lookups, one per non-empty line.
+///
+/// Lines are normalized with [`normalize_query`], duplicates are dropped
+/// keeping the first occurrence, and at most [`MAX_BATCH_LINES`] are returned,
+/// along with how many lookups were left out past that.
+pub fn split_queries(text: &str) -> (Vec<String>, usize) {
+    let mut queries: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let query = normalize_query(line);
+        if !query.is_empty() && !queries.contains(&query) {
+            queries.push(query);
+        }
+    }
+    let dropped = queries.len().saturating_sub(MAX_BATCH_LINES);
+    queries.truncate(MAX_BATCH_LINES);
+    (queries, dropped)
+}
+
+/// Most words offered from a replied-to message.
+pub const MAX_MESSAGE_WORDS: usize = 30;
+
+/// Splits a message into the words that can be looked up, lowercased and in
+/// order, without repeats and at most [`MAX_MESSAGE_WORDS`] of them.
+///
+/// Mentions, commands, hashtags and links are skipped, as are numbers and
+/// single letters. Apostrophes and hyphens inside a word are kept
+/// (`d'água`, `guarda-chuva`).
+pub fn tokenize_words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for chunk in text.split_whitespace() {
+        if chunk.starts_with(['@', '/', '#']) || chunk.contains("://") {
+            continue;
+        }
+        for token in
+            chunk.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '’' || c == '-'))
+        {
+            let word = token
+                .trim_matches(|c: char| c == '\'' || c == '’' || c == '-')
+                .to_lowercase();
+            if word.chars().count() < 2 || word.chars().all(|c| c.is_numeric()) {
+                continue;
+            }
+            if !words.contains(&word) {
+                words.push(word);
+            }
+        }
+    }
+    words.truncate(MAX_MESSAGE_WORDS);
+    words
+}
@@ -0,0 +1,321 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A token bucket: holds up to `capacity` tokens and refills continuously at
+/// `refill_per_sec`. Each request takes tokens; an empty bucket rejects it.
+#[derive(Clone, Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket of `capacity` tokens refilling at `per_minute` tokens a minute.
+    pub fn new(capacity: u32, per_minute: u32, now: Instant) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec: per_minute as f64 / 60.0,
+            tokens: capacity as f64,
+            updated: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Whether `cost` tokens are available at `now`, without taking them.
+    pub fn has(&mut self, cost: u32, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= cost as f64
+    }
+
+    /// Whole tokens available at `now`.
+    pub fn available(&mut self, now: Instant) -> u32 {
+        self.refill(now);
+        self.tokens as u32
+    }
+
+    /// Takes `cost` tokens if they are available at `now`.
+    pub fn try_take(&mut self, cost: u32, now: Instant) -> bool {
+        if !self.has(cost, now) {
+            return false;
+        }
+        self.tokens -= cost as f64;
+        true
+    }
+
+    /// Whether the bucket has refilled to capacity at `now`, making it no
+    /// different from a new one.
+    pub fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+
+    /// Time until one token is available, zero if one already is.
+    pub fn wait_time(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 1.0 || self.refill_per_sec <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64((1.0 - self.tokens) / self.refill_per_sec)
+    }
+}
+
+/// Limits for lookups that need a WordReference fetch, read from the environment.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimitConfig {
+    /// `RATE_LIMIT_CHAT_BURST`: fetches a chat can make at once.
+    pub chat_burst: u32,
+    /// `RATE_LIMIT_CHAT_PER_MINUTE`: sustained fetches per chat.
+    pub chat_per_minute: u32,
+    /// `RATE_LIMIT_USER_BURST`: fetches a user can make at once, across chats.
+    pub user_burst: u32,
+    /// `RATE_LIMIT_USER_PER_MINUTE`: sustained fetches per user.
+    pub user_per_minute: u32,
+    /// `FETCH_RATE_PER_SECOND`: outbound requests to WordReference, bot-wide.
+    pub fetch_per_second: u32,
+    /// `ADMIN_USER_IDS`: comma-separated Telegram user IDs exempt from limits.
+    pub admin_ids: Vec<u64>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            chat_burst: 60,
+            chat_per_minute: 30,
+            user_burst: 40,
+            user_per_minute: 20,
+            fetch_per_second: 2,
+            admin_ids: Vec::new(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// Reads the limits from the environment, keeping the default for any
+    /// variable that is unset or invalid.
+    pub fn from_env() -> Self {
+        let default = Self::default();
+        let var = |name: &str, default: u32| {
+            std::env::var(name)
+                .ok()
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(default)
+        };
+
+        Self {
+            chat_burst: var("RATE_LIMIT_CHAT_BURST", default.chat_burst),
+            chat_per_minute: var("RATE_LIMIT_CHAT_PER_MINUTE", default.chat_per_minute),
+            user_burst: var("RATE_LIMIT_USER_BURST", default.user_burst),
+            user_per_minute: var("RATE_LIMIT_USER_PER_MINUTE", default.user_per_minute),
+            fetch_per_second: var("FETCH_RATE_PER_SECOND", default.fetch_per_second),
+            admin_ids: parse_admin_ids(&std::env::var("ADMIN_USER_IDS").unwrap_or_default()),
+        }
+    }
+}
+
+/// Parses a comma-separated list of user IDs, skipping invalid entries.
+pub fn parse_admin_ids(value: &str) -> Vec<u64> {
+    value
+        .split(',')
+        .filter_map(|id| id.trim().parse().ok())
+        .collect()
+}
+
+/// Per-chat and per-user token buckets for lookups that reach WordReference.
+/// Cheap to clone; clones share the same buckets.
+#[derive(Clone)]
+pub struct RateLimiter {
+    config: Arc<RateLimitConfig>,
+    chats: Arc<Mutex<HashMap<i64, TokenBucket>>>,
+    users: Arc<Mutex<HashMap<u64, TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            chats: Arc::new(Mutex::new(HashMap::new())),
+            users: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn is_admin(&self, user_id: Option<u64>) -> bool {
+        user_id.is_some_and(|id| self.config.admin_ids.contains(&id))
+    }
+
+    /// Takes `cost` tokens from both the chat's and the user's bucket, or
+    /// neither when either is short. Admins are never limited.
+    pub fn check(&self, chat_id: i64, user_id: Option<u64>, cost: u32) -> bool {
+        self.check_at(chat_id, user_id, cost, Instant::now())
+    }
+
+    pub fn check_at(&self, chat_id: i64, user_id: Option<u64>, cost: u32, now: Instant) -> bool {
+        if cost == 0 || self.is_admin(user_id) {
+            return true;
+        }
+
+        self.with_buckets(chat_id, user_id, now, |chat, user| {
+            if !chat.has(cost, now) {
+                return false;
+            }
+            if let Some(user) = user
+                && !user.try_take(cost, now)
+            {
+                return false;
+            }
+            chat.try_take(cost, now)
+        })
+    }
+
+    /// Takes as many of `cost` tokens as both the chat's and the user's
+    /// bucket hold, and returns how many were taken, so that a request too
+    /// big for the buckets can still be partly served. Admins get them all.
+    pub fn take_up_to(&self, chat_id: i64, user_id: Option<u64>, cost: u32) -> u32 {
+        self.take_up_to_at(chat_id, user_id, cost, Instant::now())
+    }
+
+    pub fn take_up_to_at(
+        &self,
+        chat_id: i64,
+        user_id: Option<u64>,
+        cost: u32,
+        now: Instant,
+    ) -> u32 {
+        if cost == 0 || self.is_admin(user_id) {
+            return cost;
+        }
+
+        self.with_buckets(chat_id, user_id, now, |chat, user| {
+            let mut taken = cost.min(chat.available(now));
+            if let Some(user) = user {
+                taken = taken.min(user.available(now));
+                user.try_take(taken, now);
+            }
+            chat.try_take(taken, now);
+            taken
+        })
+    }
+
+    /// Runs `f` on the chat's bucket and the user's, if any, creating full
+    /// ones for newcomers.
+    fn with_buckets<R>(
+        &self,
+        chat_id: i64,
+        user_id: Option<u64>,
+        now: Instant,
+        f: impl FnOnce(&mut TokenBucket, Option<&mut TokenBucket>) -> R,
+    ) -> R {
+        let mut chats = self.chats.lock().unwrap();
+        let mut users = self.users.lock().unwrap();
+        // Full buckets are dropped as they would be recreated full anyway
+        chats.retain(|_, bucket| !bucket.is_full(now));
+        users.retain(|_, bucket| !bucket.is_full(now));
+
+        let chat = chats.entry(chat_id).or_insert_with(|| {
+            TokenBucket::new(self.config.chat_burst, self.config.chat_per_minute, now)
+        });
+        let user = user_id.map(|user_id| {
+            users.entry(user_id).or_insert_with(|| {
+                TokenBucket::new(self.config.user_burst, self.config.user_per_minute, now)
+            })
+        });
+        f(chat, user)
+    }
+
+    /// Number of chat and user buckets held, full ones included until the
+    /// next check.
+    pub fn len(&self) -> usize {
+        self.chats.lock().unwrap().len() + self.users.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Bot-wide pacing of outbound requests. Unlike [`RateLimiter`] it never
+/// rejects: callers wait until a request may go out.
+pub struct OutboundLimiter {
+    bucket: tokio::sync::Mutex<TokenBucket>,
+}
+
+impl OutboundLimiter {
+    /// Allows `per_second` requests a second, with bursts of the same size.
+    pub fn new(per_second: u32) -> Self {
+        let per_second = per_second.max(1);
+        Self {
+            bucket: tokio::sync::Mutex::new(TokenBucket::new(
+                per_second,
+                per_second * 60,
+                Instant::now(),
+            )),
+        }
+    }
+
+    /// Waits for a free slot and takes it.
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.bucket.lock().await;
+                let now = Instant::now();
+                if bucket.try_take(1, now) {
+                    return;
+                }
+                bucket.wait_time(now)
+            };
+            tokio::time::sleep(wait.max(Duration::from_millis(10))).await;
+        }
+    }
+}
+
+/// How long WordReference is skipped after a request to it fails.
+pub const OUTAGE_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Stops requests to a service for a while after one fails, so that lookups
+/// go straight to other sources instead of each waiting for its own failure.
+pub struct CircuitBreaker {
+    backoff: Duration,
+    open_until: Mutex<Option<Instant>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(backoff: Duration) -> Self {
+        Self {
+            backoff,
+            open_until: Mutex::new(None),
+        }
+    }
+
+    /// Whether requests should be skipped because one failed recently.
+    pub fn is_open(&self) -> bool {
+        self.is_open_at(Instant::now())
+    }
+
+    pub fn is_open_at(&self, now: Instant) -> bool {
+        self.open_until
+            .lock()
+            .unwrap()
+            .is_some_and(|until| now < until)
+    }
+
+    /// Skips requests for the backoff period.
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    pub fn record_failure_at(&self, now: Instant) {
+        *self.open_until.lock().unwrap() = Some(now + self.backoff);
+    }
+
+    /// Lets requests through again.
+    pub fn record_success(&self) {
+        *self.open_until.lock().unwrap() = None;
+    }
+}
@@ -0,0 +1,182 @@
+/// Tags Telegram's HTML parse mode accepts that the bot emits.
+const ALLOWED_TAGS: &[&str] = &["b", "strong", "i", "em", "u", "s", "code", "pre", "a"];
+
+/// Named entities Telegram understands; any other `&` is escaped.
+const ALLOWED_ENTITIES: &[(&str, char)] = &[
+    ("&lt;", '<'),
+    ("&gt;", '>'),
+    ("&amp;", '&'),
+    ("&quot;", '"'),
+];
+
+/// Joins the words of scraped text with single spaces.
+pub(crate) fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Escapes text for interpolation into a Telegram HTML message.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Length of a supported entity (`&amp;`, `&#123;`, `&#x1F600;`...) at the
+/// start of `text`, if there is one.
+fn entity_len(text: &str) -> Option<usize> {
+    if let Some((entity, _)) = ALLOWED_ENTITIES.iter().find(|(e, _)| text.starts_with(e)) {
+        return Some(entity.len());
+    }
+
+    let rest = text.strip_prefix("&#")?;
+    let (digits, radix) = match rest.strip_prefix(['x', 'X']) {
+        Some(hex) => (hex, 16),
+        None => (rest, 10),
+    };
+    let end = digits.find(';')?;
+    let number = &digits[..end];
+    if number.is_empty() || !number.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+    Some(text.len() - digits.len() + end + 1)
+}
+
+/// A tag in `html` starting at `<`, split into its name, whether it closes,
+/// and the `href` attribute for links. Returns the tag's byte length.
+fn parse_tag(html: &str) -> Option<(String, bool, Option<String>, usize)> {
+    let end = html.find('>')?;
+    let inner = &html[1..end];
+    let (closing, inner) = match inner.strip_prefix('/') {
+        Some(rest) => (true, rest),
+        None => (false, inner),
+    };
+    let name: String = inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .to_lowercase();
+    if name.is_empty() {
+        return None;
+    }
+
+    let href = inner.find("href=").and_then(|pos| {
+        let value = &inner[pos + 5..];
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        value.find(quote).map(|close| value[..close].to_string())
+    });
+
+    Some((name, closing, href, end + 1))
+}
+
+/// Makes a string safe to send with `ParseMode::Html`.
+///
+/// Whitelisted tags are kept (links keep only their `href`), any other tag and
+/// any stray `<`, `>` or `&` is escaped, closing tags without a matching opening
+/// tag are dropped and tags left open are closed at the end. Already-sanitized
+/// text passes through unchanged.
+pub fn sanitize_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut open: Vec<String> = Vec::new();
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '<' => {
+                if let Some((name, closing, href, len)) = parse_tag(rest)
+                    && ALLOWED_TAGS.contains(&name.as_str())
+                {
+                    if closing {
+                        if let Some(pos) = open.iter().rposition(|t| *t == name) {
+                            // Close anything opened inside it first
+                            for inner in open.drain(pos..).rev() {
+                                out.push_str(&format!("</{}>", inner));
+                            }
+                        }
+                    } else {
+                        match (name.as_str(), href) {
+                            ("a", Some(href)) => {
+                                out.push_str(&format!("<a href=\"{}\">", escape_html(&href)))
+                            }
+                            _ => out.push_str(&format!("<{}>", name)),
+                        }
+                        open.push(name);
+                    }
+                    rest = &rest[len..];
+                } else {
+                    out.push_str("&lt;");
+                    rest = &rest[1..];
+                }
+            }
+            '>' => {
+                out.push_str("&gt;");
+                rest = &rest[1..];
+            }
+            '&' => match entity_len(rest) {
+                Some(len) => {
+                    out.push_str(&rest[..len]);
+                    rest = &rest[len..];
+                }
+                None => {
+                    out.push_str("&amp;");
+                    rest = &rest[1..];
+                }
+            },
+            c => {
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+
+    for name in open.iter().rev() {
+        out.push_str(&format!("</{}>", name));
+    }
+
+    out
+}
+
+/// Converts Telegram HTML to plain text: tags are removed and entities decoded.
+/// Used to resend a message when Telegram rejects its markup.
+pub fn to_plain_text(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some((_, _, _, len)) = parse_tag(rest)
+        {
+            rest = &rest[len..];
+            continue;
+        }
+
+        if c == '&'
+            && let Some(len) = entity_len(rest)
+        {
+            let entity = &rest[..len];
+            let decoded = ALLOWED_ENTITIES
+                .iter()
+                .find(|(e, _)| *e == entity)
+                .map(|(_, ch)| *ch)
+                .or_else(|| {
+                    let number = entity.trim_start_matches("&#").trim_end_matches(';');
+                    let code = match number.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                        None => number.parse().ok(),
+                    };
+                    code.and_then(char::from_u32)
+                });
+            if let Some(ch) = decoded {
+                out.push(ch);
+                rest = &rest[len..];
+                continue;
+            }
+        }
+
+        out.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    out
+}
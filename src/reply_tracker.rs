@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long the bot remembers which of its messages answered a user's
+/// message. Edits after that are ignored.
+pub const REPLY_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Clone, Copy, Debug)]
+struct TrackedReply {
+    reply_id: i32,
+    recorded: Instant,
+}
+
+/// Remembers the bot's reply to each looked-up message for a while, so that
+/// editing the message can update the reply in place. Cheap to clone; clones
+/// share the same entries.
+#[derive(Clone)]
+pub struct ReplyTracker {
+    ttl: Duration,
+    /// Keyed by chat and message id
+    replies: Arc<Mutex<HashMap<(i64, i32), TrackedReply>>>,
+}
+
+impl ReplyTracker {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            replies: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Records that `reply_id` answers message `message_id` in a chat.
+    pub fn record(&self, chat_id: i64, message_id: i32, reply_id: i32) {
+        self.record_at(chat_id, message_id, reply_id, Instant::now());
+    }
+
+    pub fn record_at(&self, chat_id: i64, message_id: i32, reply_id: i32, now: Instant) {
+        let mut replies = self.replies.lock().unwrap();
+        // Expired entries are dropped as new ones come in
+        replies.retain(|_, reply| now.saturating_duration_since(reply.recorded) < self.ttl);
+        replies.insert(
+            (chat_id, message_id),
+            TrackedReply {
+                reply_id,
+                recorded: now,
+            },
+        );
+    }
+
+    /// The bot's reply to a message, unless it is unknown or too old.
+    pub fn get(&self, chat_id: i64, message_id: i32) -> Option<i32> {
+        self.get_at(chat_id, message_id, Instant::now())
+    }
+
+    pub fn get_at(&self, chat_id: i64, message_id: i32, now: Instant) -> Option<i32> {
+        let replies = self.replies.lock().unwrap();
+        replies
+            .get(&(chat_id, message_id))
+            .filter(|reply| now.saturating_duration_since(reply.recorded) < self.ttl)
+            .map(|reply| reply.reply_id)
+    }
+
+    /// Number of entries held, expired ones included until the next record.
+    pub fn len(&self) -> usize {
+        self.replies.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,76 @@
+use crate::entities::saved_words::{self, ActiveModel, Entity as SavedWords, Model};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QueryOrder, Set,
+};
+
+#[derive(Clone)]
+pub struct SavedWordsRepository {
+    db: DatabaseConnection,
+}
+
+impl SavedWordsRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Saves a word for a member of the chat. Saving the same word and
+    /// direction twice is a no-op.
+    pub async fn save_word(
+        &self,
+        chat_id: &str,
+        user_id: i64,
+        word: &str,
+        dir: &str,
+    ) -> Result<Model, sea_orm::DbErr> {
+        if let Some(existing) = SavedWords::find()
+            .filter(saved_words::Column::ChatId.eq(chat_id.to_string()))
+            .filter(saved_words::Column::UserId.eq(user_id))
+            .filter(saved_words::Column::Word.eq(word.to_string()))
+            .filter(saved_words::Column::LangDirection.eq(dir.to_string()))
+            .one(&self.db)
+            .await?
+        {
+            return Ok(existing);
+        }
+
+        let am = ActiveModel {
+            chat_id: Set(chat_id.to_string()),
+            user_id: Set(Some(user_id)),
+            word: Set(word.to_string()),
+            lang_direction: Set(dir.to_string()),
+            created_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        };
+        am.insert(&self.db).await
+    }
+
+    /// Returns the words a member saved in the chat, along with the words
+    /// the chat shares from before words were kept per member, oldest first.
+    pub async fn get_saved_words(
+        &self,
+        chat_id: &str,
+        user_id: i64,
+    ) -> Result<Vec<Model>, sea_orm::DbErr> {
+        SavedWords::find()
+            .filter(saved_words::Column::ChatId.eq(chat_id.to_string()))
+            .filter(
+                Condition::any()
+                    .add(saved_words::Column::UserId.eq(user_id))
+                    .add(saved_words::Column::UserId.is_null()),
+            )
+            .order_by_asc(saved_words::Column::Id)
+            .all(&self.db)
+            .await
+    }
+
+    /// Deletes every word saved in the chat. Returns the number of rows deleted.
+    pub async fn delete_saved_words(&self, chat_id: &str) -> Result<u64, sea_orm::DbErr> {
+        Ok(SavedWords::delete_many()
+            .filter(saved_words::Column::ChatId.eq(chat_id.to_string()))
+            .exec(&self.db)
+            .await?
+            .rows_affected)
+    }
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use sd_notify::NotifyState;
+use sea_orm::DatabaseConnection;
+use tokio::signal::unix::{SignalKind, signal};
+
+/// How long in-flight updates get to finish after a shutdown signal. Kept
+/// below the unit's `TimeoutStopSec`, after which systemd kills the bot.
+pub const SHUTDOWN_DEADLINE: Duration = Duration::from_secs(20);
+
+/// Waits for SIGTERM or SIGINT and returns the signal's name.
+pub async fn shutdown_signal() -> &'static str {
+    let mut terminate = signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM");
+    let mut interrupt = signal(SignalKind::interrupt()).expect("Failed to listen for SIGINT");
+    tokio::select! {
+        _ = terminate.recv() => "SIGTERM",
+        _ = interrupt.recv() => "SIGINT",
+    }
+}
+
+/// Tells systemd the bot is taking updates. Does nothing when not run as a
+/// `Type=notify` unit.
+pub fn notify_ready() {
+    notify(&[NotifyState::Ready]);
+}
+
+/// Tells systemd the bot is shutting down.
+pub fn notify_stopping() {
+    notify(&[NotifyState::Stopping]);
+}
+
+fn notify(states: &[NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, states) {
+        log::warn!("Failed to notify systemd: {}", e);
+    }
+}
+
+/// How often to ping systemd's watchdog: half the unit's `WatchdogSec`, or
+/// None when the watchdog is off.
+pub fn watchdog_interval() -> Option<Duration> {
+    let mut usec = 0;
+    sd_notify::watchdog_enabled(false, &mut usec).then(|| Duration::from_micros(usec) / 2)
+}
+
+/// Pings systemd's watchdog for as long as the database answers, so that a
+/// hung bot gets restarted.
+pub fn spawn_watchdog(db: DatabaseConnection) {
+    let Some(interval) = watchdog_interval() else {
+        return;
+    };
+    log::info!("Pinging the systemd watchdog every {:?}", interval);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            match db.ping().await {
+                Ok(()) => notify(&[NotifyState::Watchdog]),
+                Err(e) => log::warn!("Skipping watchdog ping, database unavailable: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,58 @@
+use crate::entities::lookup_stats::{Entity as LookupStats, Model};
+use chrono::{NaiveDate, Utc};
+use sea_orm::{ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait, Statement};
+
+#[derive(Clone)]
+pub struct StatsRepository {
+    db: DatabaseConnection,
+}
+
+impl StatsRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Counts a lookup for today, noting whether it was answered from the cache.
+    pub async fn record_lookup(&self, cache_hit: bool) -> Result<(), sea_orm::DbErr> {
+        self.record_lookup_on(Utc::now().date_naive(), cache_hit)
+            .await
+    }
+
+    pub async fn record_lookup_on(
+        &self,
+        day: NaiveDate,
+        cache_hit: bool,
+    ) -> Result<(), sea_orm::DbErr> {
+        // A single upsert, so concurrent lookups don't lose counts
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Sqlite,
+                "INSERT INTO lookup_stats (day, lookups, cache_hits) VALUES (?, 1, ?) \
+                 ON CONFLICT(day) DO UPDATE SET lookups = lookups + 1, \
+                 cache_hits = cache_hits + excluded.cache_hits",
+                [day.to_string().into(), (cache_hit as i64).into()],
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Counters for one day, zero when nothing was looked up.
+    pub async fn get_day(&self, day: NaiveDate) -> Result<Model, sea_orm::DbErr> {
+        Ok(LookupStats::find_by_id(day.to_string())
+            .one(&self.db)
+            .await?
+            .unwrap_or(Model {
+                day: day.to_string(),
+                lookups: 0,
+                cache_hits: 0,
+            }))
+    }
+
+    /// Lookups and cache hits summed over all days.
+    pub async fn get_totals(&self) -> Result<(i64, i64), sea_orm::DbErr> {
+        let days = LookupStats::find().all(&self.db).await?;
+        Ok(days.iter().fold((0, 0), |(lookups, hits), day| {
+            (lookups + day.lookups, hits + day.cache_hits)
+        }))
+    }
+}
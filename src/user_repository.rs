@@ -1,6 +1,12 @@
-use crate::entities::users::{ActiveModel, Entity as Users, Model};
+use crate::constants::DEFAULT_LANG_DIRECTION;
+use crate::entities::chat_member_settings::{self, Entity as ChatMemberSettings};
+use crate::entities::users::{self, ActiveModel, Entity as Users, Model};
+use crate::group_chat::GroupMode;
 use chrono::Utc;
-use sea_orm::{ActiveModelTrait, DatabaseConnection, EntityTrait, Set};
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbBackend, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, Set, Statement,
+};
 
 #[derive(Clone)]
 pub struct UserRepository {
@@ -22,14 +28,42 @@ impl UserRepository {
         translation_direction: &str,
         user_id: Option<i64>,
         username: Option<&str>,
+    ) -> Result<Model, sea_orm::DbErr> {
+        self.insert_chat(chat_id, Some(translation_direction), user_id, username)
+            .await
+    }
+
+    /// Creates a chat's row without a direction of its own, for chats that
+    /// change another setting first. Lookups then fall through to the
+    /// sender's direction.
+    pub async fn create_chat(
+        &self,
+        chat_id: &str,
+        user_id: Option<i64>,
+        username: Option<&str>,
+    ) -> Result<Model, sea_orm::DbErr> {
+        self.insert_chat(chat_id, None, user_id, username).await
+    }
+
+    async fn insert_chat(
+        &self,
+        chat_id: &str,
+        translation_direction: Option<&str>,
+        user_id: Option<i64>,
+        username: Option<&str>,
     ) -> Result<Model, sea_orm::DbErr> {
         let now = Utc::now().naive_utc();
 
         let user = ActiveModel {
             chat_id: Set(chat_id.to_string()),
-            translation_direction: Set(translation_direction.to_string()),
+            translation_direction: Set(translation_direction.map(str::to_string)),
             user_id: Set(user_id),
             username: Set(username.map(|s| s.to_string())),
+            auto_detect: Set(false),
+            preferred_variant: Set(None),
+            hide_other_variant: Set(false),
+            group_mode: Set(GroupMode::default().code().to_string()),
+            locale: Set(None),
             created_at: Set(now),
             updated_at: Set(now),
         };
@@ -48,8 +82,208 @@ impl UserRepository {
             .ok_or_else(|| sea_orm::DbErr::RecordNotFound("User not found".to_string()))?
             .into();
 
-        user.translation_direction = Set(direction.to_string());
+        user.translation_direction = Set(Some(direction.to_string()));
         user.updated_at = Set(Utc::now().naive_utc());
         user.update(&self.db).await
     }
+
+    pub async fn update_auto_detect(
+        &self,
+        chat_id: &str,
+        enabled: bool,
+    ) -> Result<Model, sea_orm::DbErr> {
+        let mut user: ActiveModel = Users::find_by_id(chat_id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("User not found".to_string()))?
+            .into();
+
+        user.auto_detect = Set(enabled);
+        user.updated_at = Set(Utc::now().naive_utc());
+        user.update(&self.db).await
+    }
+
+    /// Sets the chat's preferred Portuguese variant (`pt`, `br`, or None for
+    /// no preference) and whether translations for the other one are hidden.
+    pub async fn update_variant_preference(
+        &self,
+        chat_id: &str,
+        preferred: Option<&str>,
+        hide_others: bool,
+    ) -> Result<Model, sea_orm::DbErr> {
+        let mut user: ActiveModel = Users::find_by_id(chat_id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("User not found".to_string()))?
+            .into();
+
+        user.preferred_variant = Set(preferred.map(|s| s.to_string()));
+        user.hide_other_variant = Set(hide_others);
+        user.updated_at = Set(Utc::now().naive_utc());
+        user.update(&self.db).await
+    }
+
+    pub async fn update_group_mode(
+        &self,
+        chat_id: &str,
+        mode: GroupMode,
+    ) -> Result<Model, sea_orm::DbErr> {
+        let mut user: ActiveModel = Users::find_by_id(chat_id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("User not found".to_string()))?
+            .into();
+
+        user.group_mode = Set(mode.code().to_string());
+        user.updated_at = Set(Utc::now().naive_utc());
+        user.update(&self.db).await
+    }
+
+    /// Sets the language the bot speaks in a chat; None follows each user's
+    /// Telegram language.
+    pub async fn update_locale(
+        &self,
+        chat_id: &str,
+        locale: Option<&str>,
+    ) -> Result<Model, sea_orm::DbErr> {
+        let mut user: ActiveModel = Users::find_by_id(chat_id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| sea_orm::DbErr::RecordNotFound("User not found".to_string()))?
+            .into();
+
+        user.locale = Set(locale.map(str::to_string));
+        user.updated_at = Set(Utc::now().naive_utc());
+        user.update(&self.db).await
+    }
+
+    /// A member's own direction inside a group, if they set one.
+    pub async fn get_member_direction(
+        &self,
+        chat_id: &str,
+        user_id: i64,
+    ) -> Result<Option<String>, sea_orm::DbErr> {
+        Ok(ChatMemberSettings::find()
+            .filter(chat_member_settings::Column::ChatId.eq(chat_id.to_string()))
+            .filter(chat_member_settings::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await?
+            .map(|m| m.translation_direction))
+    }
+
+    /// Sets a member's own direction inside a group, overriding the group's.
+    pub async fn set_member_direction(
+        &self,
+        chat_id: &str,
+        user_id: i64,
+        direction: &str,
+    ) -> Result<(), sea_orm::DbErr> {
+        let now = Utc::now().naive_utc();
+        if let Some(existing) = ChatMemberSettings::find()
+            .filter(chat_member_settings::Column::ChatId.eq(chat_id.to_string()))
+            .filter(chat_member_settings::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await?
+        {
+            let mut am: chat_member_settings::ActiveModel = existing.into();
+            am.translation_direction = Set(direction.to_string());
+            am.updated_at = Set(now);
+            am.update(&self.db).await?;
+        } else {
+            chat_member_settings::ActiveModel {
+                chat_id: Set(chat_id.to_string()),
+                user_id: Set(user_id),
+                translation_direction: Set(direction.to_string()),
+                created_at: Set(now),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&self.db)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// The direction a user's lookups in a chat use, from the most specific
+    /// setting to the least: the user's own direction in that chat, the chat's
+    /// when it has one, the user's global one (the row of their private chat
+    /// with the bot), and finally `DEFAULT_LANG_DIRECTION`.
+    pub async fn resolve_direction(
+        &self,
+        chat_id: &str,
+        user_id: Option<i64>,
+    ) -> Result<String, sea_orm::DbErr> {
+        if let Some(user_id) = user_id
+            && let Some(direction) = self.get_member_direction(chat_id, user_id).await?
+        {
+            return Ok(direction);
+        }
+        if let Some(direction) = self
+            .get_user(chat_id)
+            .await?
+            .and_then(|chat| chat.translation_direction)
+        {
+            return Ok(direction);
+        }
+        if let Some(user_id) = user_id
+            && let Some(direction) = self
+                .get_user(&user_id.to_string())
+                .await?
+                .and_then(|user| user.translation_direction)
+        {
+            return Ok(direction);
+        }
+        Ok(DEFAULT_LANG_DIRECTION.to_string())
+    }
+
+    /// Number of chats with a stored row.
+    pub async fn count_chats(&self) -> Result<u64, sea_orm::DbErr> {
+        Users::find().count(&self.db).await
+    }
+
+    /// Number of distinct Telegram users who have configured a chat.
+    pub async fn count_users(&self) -> Result<i64, sea_orm::DbErr> {
+        let row = self
+            .db
+            .query_one(Statement::from_string(
+                DbBackend::Sqlite,
+                "SELECT COUNT(DISTINCT user_id) AS users FROM users",
+            ))
+            .await?;
+        match row {
+            Some(row) => row.try_get("", "users"),
+            None => Ok(0),
+        }
+    }
+
+    /// IDs of every chat with a stored row, e.g. for broadcasts.
+    pub async fn get_chat_ids(&self) -> Result<Vec<String>, sea_orm::DbErr> {
+        Ok(Users::find()
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|user| user.chat_id)
+            .collect())
+    }
+
+    /// Every chat row, oldest first.
+    pub async fn list_users(&self) -> Result<Vec<Model>, sea_orm::DbErr> {
+        Users::find()
+            .order_by_asc(users::Column::CreatedAt)
+            .all(&self.db)
+            .await
+    }
+
+    /// Deletes a chat's row and its members' settings. Returns false if there
+    /// was no row.
+    pub async fn delete_user(&self, chat_id: &str) -> Result<bool, sea_orm::DbErr> {
+        ChatMemberSettings::delete_many()
+            .filter(chat_member_settings::Column::ChatId.eq(chat_id.to_string()))
+            .exec(&self.db)
+            .await?;
+        let result = Users::delete_by_id(chat_id.to_string())
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected > 0)
+    }
 }
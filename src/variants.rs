@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::users;
+use crate::languages;
+
+/// A regional variety of Portuguese, as WordReference labels it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Variant {
+    #[serde(rename = "PT")]
+    European,
+    #[serde(rename = "BR")]
+    Brazilian,
+}
+
+impl Variant {
+    /// Code stored in the chat's settings and used in callback data.
+    pub fn code(self) -> &'static str {
+        match self {
+            Variant::European => "pt",
+            Variant::Brazilian => "br",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Variant> {
+        match code.to_lowercase().as_str() {
+            "pt" => Some(Variant::European),
+            "br" => Some(Variant::Brazilian),
+            _ => None,
+        }
+    }
+
+    pub fn flag(self) -> &'static str {
+        match self {
+            Variant::European => "🇵🇹",
+            Variant::Brazilian => "🇧🇷",
+        }
+    }
+}
+
+/// Variants named by the parenthesized labels in a piece of text, e.g. `(BR)`
+/// or `(PT, BR)`, in order of appearance and without repeats.
+pub fn parse_labels(text: &str) -> Vec<Variant> {
+    let mut variants = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('(') {
+        let Some(len) = rest[start..].find(')') else {
+            break;
+        };
+        let inner = &rest[start + 1..start + len];
+        for token in inner.split([',', '/', ' ']).filter(|t| !t.is_empty()) {
+            if let Some(variant) = Variant::from_code(token)
+                && !variants.contains(&variant)
+            {
+                variants.push(variant);
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+
+    variants
+}
+
+/// How a chat wants regional variants shown: which one it prefers, and whether
+/// translations labelled only with the other one are hidden or just toned down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VariantPreference {
+    pub preferred: Option<Variant>,
+    pub hide_others: bool,
+}
+
+impl VariantPreference {
+    pub fn from_user(user: &users::Model) -> Self {
+        Self {
+            preferred: user
+                .preferred_variant
+                .as_deref()
+                .and_then(Variant::from_code),
+            hide_others: user.hide_other_variant,
+        }
+    }
+}
+
+/// Applies a chat's variant preference to formatted translations, one line per
+/// row. Rows labelled with the preferred variant get its flag; rows labelled
+/// only with the other one are set in italics or dropped. Unlabelled rows and
+/// directions without Portuguese are left alone, and so is a reply that would
+/// otherwise end up empty.
+pub fn apply_preference(
+    formatted: &str,
+    lang_direction: &str,
+    preference: VariantPreference,
+) -> String {
+    let Some(preferred) = preference.preferred else {
+        return formatted.to_string();
+    };
+    let involves_portuguese =
+        languages::direction(lang_direction).is_some_and(|d| d.source == "pt" || d.target == "pt");
+    if !involves_portuguese {
+        return formatted.to_string();
+    }
+
+    let mut result = String::with_capacity(formatted.len());
+    let mut kept_rows = false;
+
+    for line in formatted.split_inclusive('\n') {
+        let labels = parse_labels(line);
+        let (content, newline) = match line.strip_suffix('\n') {
+            Some(content) => (content, "\n"),
+            None => (line, ""),
+        };
+
+        if labels.is_empty() || content.trim().is_empty() {
+            kept_rows |= !content.trim().is_empty();
+            result.push_str(line);
+        } else if labels.contains(&preferred) {
+            kept_rows = true;
+            result.push_str(&format!("{} {}{}", preferred.flag(), content, newline));
+        } else if !preference.hide_others {
+            kept_rows = true;
+            result.push_str(&format!("<i>{}</i>{}", content, newline));
+        }
+    }
+
+    if kept_rows {
+        result
+    } else {
+        formatted.to_string()
+    }
+}
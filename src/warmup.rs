@@ -0,0 +1,104 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::cache_repository::CacheRepository;
+use crate::lookup;
+use crate::query::normalize_query;
+
+/// Words of a frequency list, in list order, without duplicates.
+///
+/// Each line holds a word or phrase, optionally with a numeric rank before it
+/// or a count after it (`casa 1234`, `12\tcasa`). Blank lines and lines
+/// starting with `#` are skipped.
+pub fn parse_word_list(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let is_number = |token: &str| token.chars().all(|c| c.is_ascii_digit() || c == '.');
+        let mut tokens: Vec<&str> = line.split_whitespace().collect();
+        while tokens.last().is_some_and(|t| is_number(t)) {
+            tokens.pop();
+        }
+        let start = tokens.iter().take_while(|t| is_number(t)).count();
+
+        let word = normalize_query(&tokens[start..].join(" "));
+        if !word.is_empty() && !words.contains(&word) {
+            words.push(word);
+        }
+    }
+    words
+}
+
+/// What warming one word did.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WarmupStatus {
+    /// Already in the cache; WordReference was not contacted
+    Cached,
+    Fetched,
+    NotFound,
+    Failed(String),
+}
+
+impl fmt::Display for WarmupStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WarmupStatus::Cached => write!(f, "cached"),
+            WarmupStatus::Fetched => write!(f, "fetched"),
+            WarmupStatus::NotFound => write!(f, "not found"),
+            WarmupStatus::Failed(error) => write!(f, "failed ({})", error),
+        }
+    }
+}
+
+/// Caches a word's article unless it is cached already.
+pub async fn warm_word(
+    cache_repo: &CacheRepository,
+    word: &str,
+    lang_direction: &str,
+) -> WarmupStatus {
+    match cache_repo.get_cached_html(word, lang_direction).await {
+        Ok(Some(_)) => return WarmupStatus::Cached,
+        Ok(None) => {}
+        Err(e) => return WarmupStatus::Failed(e.to_string()),
+    }
+
+    match lookup::fetch_and_cache(cache_repo, word, lang_direction).await {
+        Ok(Some(_)) => WarmupStatus::Fetched,
+        Ok(None) => WarmupStatus::NotFound,
+        Err(e) => WarmupStatus::Failed(e.to_string()),
+    }
+}
+
+/// Counts of each status over a warm-up run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WarmupSummary {
+    pub cached: usize,
+    pub fetched: usize,
+    pub not_found: usize,
+    pub failed: usize,
+}
+
+impl WarmupSummary {
+    pub fn record(&mut self, status: &WarmupStatus) {
+        match status {
+            WarmupStatus::Cached => self.cached += 1,
+            WarmupStatus::Fetched => self.fetched += 1,
+            WarmupStatus::NotFound => self.not_found += 1,
+            WarmupStatus::Failed(_) => self.failed += 1,
+        }
+    }
+}
+
+/// Number of list entries a previous run got through, 0 without a checkpoint.
+pub fn read_checkpoint(path: &Path) -> usize {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+pub fn write_checkpoint(path: &Path, done: usize) -> std::io::Result<()> {
+    std::fs::write(path, format!("{}\n", done))
+}
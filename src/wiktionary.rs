@@ -0,0 +1,487 @@
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::fetch_translations::TranslationEntry;
+use crate::languages;
+use crate::query::normalize_query;
+use crate::render::escape_html;
+use crate::wiktionary_repository::{EntryUpdate, WiktionaryRepository};
+
+pub type WiktionaryError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Entries written per transaction during an import.
+const IMPORT_BATCH: usize = 500;
+
+/// Inflected forms shown in a reply; the rest stay in the database.
+const MAX_FORMS_SHOWN: usize = 6;
+
+/// Kaikki form tags that mark table metadata rather than an inflected form.
+const META_FORM_TAGS: &[&str] = &["table-tags", "inflection-template", "class"];
+
+/// An inflected form and its grammatical tags, e.g. `casas` (`plural`).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Form {
+    pub form: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// A usage example and its English translation.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Example {
+    pub text: String,
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sense {
+    pub glosses: Vec<String>,
+    pub tags: Vec<String>,
+    pub examples: Vec<Example>,
+}
+
+/// One word, part of speech and etymology of an English Wiktionary extract.
+/// Glosses are in English.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WiktionaryEntry {
+    pub lang_code: String,
+    pub word: String,
+    pub pos: String,
+    pub etymology_number: i32,
+    pub etymology: Option<String>,
+    pub ipa: Vec<String>,
+    pub forms: Vec<Form>,
+    pub senses: Vec<Sense>,
+}
+
+/// What identifies an entry across dumps.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct EntryKey {
+    pub lang_code: String,
+    pub word: String,
+    pub pos: String,
+    pub etymology_number: i32,
+}
+
+impl WiktionaryEntry {
+    pub fn key(&self) -> EntryKey {
+        EntryKey {
+            lang_code: self.lang_code.clone(),
+            word: self.word.clone(),
+            pos: self.pos.clone(),
+            etymology_number: self.etymology_number,
+        }
+    }
+}
+
+// The subset of a Kaikki (wiktextract) JSONL line that is imported
+#[derive(Deserialize)]
+struct KaikkiLine {
+    #[serde(default)]
+    word: String,
+    #[serde(default)]
+    lang_code: String,
+    #[serde(default)]
+    pos: String,
+    #[serde(default)]
+    etymology_number: Option<i32>,
+    #[serde(default)]
+    etymology_text: Option<String>,
+    #[serde(default)]
+    sounds: Vec<KaikkiSound>,
+    #[serde(default)]
+    forms: Vec<Form>,
+    #[serde(default)]
+    senses: Vec<KaikkiSense>,
+}
+
+#[derive(Deserialize)]
+struct KaikkiSound {
+    #[serde(default)]
+    ipa: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KaikkiSense {
+    #[serde(default)]
+    glosses: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    examples: Vec<KaikkiExample>,
+}
+
+#[derive(Deserialize)]
+struct KaikkiExample {
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    english: Option<String>,
+    #[serde(default)]
+    translation: Option<String>,
+}
+
+/// Parses one line of a Kaikki dump. Returns None for entries without a
+/// word or without any glossed sense, such as redirects.
+pub fn parse_kaikki_line(line: &str) -> Result<Option<WiktionaryEntry>, serde_json::Error> {
+    let raw: KaikkiLine = serde_json::from_str(line)?;
+    if raw.word.is_empty() || raw.lang_code.is_empty() {
+        return Ok(None);
+    }
+
+    let senses: Vec<Sense> = raw
+        .senses
+        .into_iter()
+        .filter(|s| !s.glosses.is_empty())
+        .map(|s| Sense {
+            glosses: s.glosses,
+            tags: s.tags,
+            examples: s
+                .examples
+                .into_iter()
+                .filter_map(|e| {
+                    Some(Example {
+                        text: e.text?,
+                        translation: e.english.or(e.translation),
+                    })
+                })
+                .collect(),
+        })
+        .collect();
+    if senses.is_empty() {
+        return Ok(None);
+    }
+
+    let mut ipa: Vec<String> = Vec::new();
+    for sound in raw.sounds {
+        if let Some(value) = sound.ipa
+            && !ipa.contains(&value)
+        {
+            ipa.push(value);
+        }
+    }
+
+    let forms = raw
+        .forms
+        .into_iter()
+        .filter(|f| {
+            !f.form.is_empty()
+                && f.form != raw.word
+                && !f.tags.iter().any(|t| META_FORM_TAGS.contains(&t.as_str()))
+        })
+        .collect();
+
+    Ok(Some(WiktionaryEntry {
+        lang_code: raw.lang_code,
+        word: raw.word,
+        pos: raw.pos,
+        etymology_number: raw.etymology_number.unwrap_or(0),
+        etymology: raw.etymology_text.filter(|e| !e.trim().is_empty()),
+        ipa,
+        forms,
+        senses,
+    }))
+}
+
+/// Counts of an import.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub added: u64,
+    pub updated: u64,
+    pub unchanged: u64,
+    /// Entries of the imported languages that are no longer in the dump
+    pub removed: u64,
+    /// Lines in other languages, without glosses, duplicated or not valid JSON
+    pub skipped: u64,
+}
+
+/// Imports a Kaikki JSONL stream, keeping only `lang_codes`.
+///
+/// Lines whose checksum matches the stored entry are skipped, so re-importing
+/// a newer dump only writes what changed. Entries of a language present in the
+/// dump that the dump no longer contains are deleted.
+pub async fn import_kaikki<R: BufRead>(
+    repo: &WiktionaryRepository,
+    reader: R,
+    lang_codes: &[String],
+) -> Result<ImportSummary, WiktionaryError> {
+    let mut existing = repo.entry_checksums(lang_codes).await?;
+    let mut summary = ImportSummary::default();
+    let mut seen_langs: HashSet<String> = HashSet::new();
+    let mut seen_keys: HashSet<EntryKey> = HashSet::new();
+    let mut batch: Vec<EntryUpdate> = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = match parse_kaikki_line(&line) {
+            Ok(Some(entry)) if lang_codes.contains(&entry.lang_code) => entry,
+            Ok(_) => {
+                summary.skipped += 1;
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Skipping invalid Wiktionary line: {}", e);
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        if !seen_keys.insert(entry.key()) {
+            // Later duplicates of an entry in the same dump are ignored
+            summary.skipped += 1;
+            continue;
+        }
+        seen_langs.insert(entry.lang_code.clone());
+
+        let checksum = sha1_hex(line.as_bytes());
+        let replaces = match existing.remove(&entry.key()) {
+            Some((_, stored)) if stored == checksum => {
+                summary.unchanged += 1;
+                continue;
+            }
+            Some((id, _)) => {
+                summary.updated += 1;
+                Some(id)
+            }
+            None => {
+                summary.added += 1;
+                None
+            }
+        };
+        batch.push(EntryUpdate {
+            entry,
+            checksum,
+            replaces,
+        });
+
+        if batch.len() >= IMPORT_BATCH {
+            repo.store_entries(&batch).await?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty() {
+        repo.store_entries(&batch).await?;
+    }
+
+    // Whatever is left of the stored entries was not in the dump
+    let stale: Vec<i32> = existing
+        .into_iter()
+        .filter(|(key, _)| seen_langs.contains(&key.lang_code))
+        .map(|(_, (id, _))| id)
+        .collect();
+    summary.removed = repo.delete_entries(&stale).await?;
+
+    Ok(summary)
+}
+
+/// Imports a Kaikki dump file (`.jsonl` or `.jsonl.gz`). Returns None without
+/// reading it further when the same file was already imported for the same
+/// languages, unless `force` is set.
+pub async fn import_file(
+    repo: &WiktionaryRepository,
+    path: &Path,
+    lang_codes: &[String],
+    force: bool,
+) -> Result<Option<ImportSummary>, WiktionaryError> {
+    let mut hasher = Sha1::new();
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; 1 << 16];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    let mut langs = lang_codes.to_vec();
+    langs.sort();
+    hasher.update(langs.join(",").as_bytes());
+    let checksum = hex(&hasher.finalize());
+
+    if !force && repo.is_imported(&checksum).await? {
+        return Ok(None);
+    }
+
+    let file = File::open(path)?;
+    let summary = if path.extension().is_some_and(|e| e == "gz") {
+        import_kaikki(repo, BufReader::new(GzDecoder::new(file)), lang_codes).await?
+    } else {
+        import_kaikki(repo, BufReader::new(file), lang_codes).await?
+    };
+
+    let entries = summary.added + summary.updated + summary.unchanged;
+    if !repo.is_imported(&checksum).await? {
+        repo.record_import(&path.to_string_lossy(), &checksum, entries as i64)
+            .await?;
+    }
+    Ok(Some(summary))
+}
+
+fn sha1_hex(bytes: &[u8]) -> String {
+    hex(&Sha1::digest(bytes))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Formats the entries of a word as a Telegram HTML reply: IPA, part of
+/// speech, a few inflected forms, numbered glosses with examples, etymology.
+pub fn format_entries(entries: &[WiktionaryEntry]) -> String {
+    let mut out = String::from("📗 <i>Wiktionary</i>\n");
+
+    for entry in entries {
+        out.push_str(&format!("\n<b>{}</b>", escape_html(&entry.word)));
+        if let Some(ipa) = entry.ipa.first() {
+            out.push_str(&format!(" {}", escape_html(ipa)));
+        }
+        out.push('\n');
+
+        out.push_str(&format!("<i>{}</i>", escape_html(&entry.pos)));
+        let forms: Vec<String> = entry
+            .forms
+            .iter()
+            .take(MAX_FORMS_SHOWN)
+            .map(|f| {
+                if f.tags.is_empty() {
+                    escape_html(&f.form)
+                } else {
+                    format!(
+                        "{} ({})",
+                        escape_html(&f.form),
+                        escape_html(&f.tags.join(" "))
+                    )
+                }
+            })
+            .collect();
+        if !forms.is_empty() {
+            out.push_str(&format!(" · {}", forms.join(", ")));
+        }
+        out.push('\n');
+
+        for (i, sense) in entry.senses.iter().enumerate() {
+            out.push_str(&format!("{}. ", i + 1));
+            if !sense.tags.is_empty() {
+                out.push_str(&format!(
+                    "<i>({})</i> ",
+                    escape_html(&sense.tags.join(", "))
+                ));
+            }
+            out.push_str(&escape_html(&sense.glosses.join("; ")));
+            out.push('\n');
+            for example in &sense.examples {
+                out.push_str(&format!("    <i>{}</i>", escape_html(&example.text)));
+                if let Some(translation) = &example.translation {
+                    out.push_str(&format!(" — {}", escape_html(translation)));
+                }
+                out.push('\n');
+            }
+        }
+
+        if let Some(etymology) = &entry.etymology {
+            out.push_str(&format!("<i>Etymology:</i> {}\n", escape_html(etymology)));
+        }
+    }
+
+    out
+}
+
+/// One translation row per sense, for glossaries.
+pub fn translation_entries(entries: &[WiktionaryEntry]) -> Vec<TranslationEntry> {
+    entries
+        .iter()
+        .flat_map(|entry| {
+            entry.senses.iter().map(|sense| TranslationEntry {
+                word: entry.word.clone(),
+                part_of_speech: entry.pos.clone(),
+                sense: sense.tags.join(", "),
+                translation: sense.glosses.join("; "),
+                examples: sense.examples.iter().map(|e| e.text.clone()).collect(),
+                variants: Vec::new(),
+            })
+        })
+        .collect()
+}
+
+/// How Wiktionary entries are used in lookups.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WiktionaryMode {
+    Off,
+    /// Only for words no other source has
+    Fallback,
+    /// Also appended to WordReference articles
+    Merge,
+}
+
+impl WiktionaryMode {
+    /// `WIKTIONARY_MODE`: `off`, `fallback` (default) or `merge`.
+    pub fn from_env() -> Self {
+        match std::env::var("WIKTIONARY_MODE")
+            .unwrap_or_default()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "off" => Self::Off,
+            "merge" => Self::Merge,
+            _ => Self::Fallback,
+        }
+    }
+}
+
+/// Looks words up in the imported Wiktionary entries.
+#[derive(Clone)]
+pub struct WiktionaryProvider {
+    repo: WiktionaryRepository,
+    mode: WiktionaryMode,
+}
+
+impl WiktionaryProvider {
+    pub fn new(repo: WiktionaryRepository, mode: WiktionaryMode) -> Self {
+        Self { repo, mode }
+    }
+
+    pub fn mode(&self) -> WiktionaryMode {
+        self.mode
+    }
+
+    /// Entries for a word in the direction's source language. Only directions
+    /// into English are served, since the extracts have English glosses.
+    pub async fn lookup(
+        &self,
+        word: &str,
+        lang_direction: &str,
+    ) -> Result<Vec<WiktionaryEntry>, WiktionaryError> {
+        let Some(direction) = languages::direction(lang_direction) else {
+            return Ok(Vec::new());
+        };
+        if direction.target != "en" {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .repo
+            .find(direction.source, &normalize_query(word))
+            .await?)
+    }
+}
+
+/// Default languages to import: every source language the bot translates from.
+pub fn supported_lang_codes() -> Vec<String> {
+    let mut codes: Vec<String> = languages::DIRECTIONS
+        .iter()
+        .filter(|d| d.target == "en")
+        .map(|d| d.source.to_string())
+        .collect();
+    codes.sort();
+    codes.dedup();
+    codes
+}
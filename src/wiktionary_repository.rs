@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+
+use crate::entities::wiktionary_entries::{self, Entity as WiktionaryEntries};
+use crate::entities::wiktionary_imports::{self, Entity as WiktionaryImports};
+use crate::entities::wiktionary_senses::{self, Entity as WiktionarySenses};
+use crate::query::normalize_query;
+use crate::wiktionary::{EntryKey, Sense, WiktionaryEntry};
+use chrono::Utc;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+
+/// Ids deleted per statement, well under SQLite's variable limit.
+const DELETE_CHUNK: usize = 500;
+
+/// An entry to write, with the checksum of its dump line and the id of the
+/// row it replaces, if any.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryUpdate {
+    pub entry: WiktionaryEntry,
+    pub checksum: String,
+    pub replaces: Option<i32>,
+}
+
+#[derive(Clone)]
+pub struct WiktionaryRepository {
+    db: DatabaseConnection,
+}
+
+impl WiktionaryRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Whether a dump with this checksum was imported before.
+    pub async fn is_imported(&self, checksum: &str) -> Result<bool, sea_orm::DbErr> {
+        Ok(WiktionaryImports::find()
+            .filter(wiktionary_imports::Column::Checksum.eq(checksum.to_string()))
+            .one(&self.db)
+            .await?
+            .is_some())
+    }
+
+    pub async fn record_import(
+        &self,
+        path: &str,
+        checksum: &str,
+        entries: i64,
+    ) -> Result<(), sea_orm::DbErr> {
+        wiktionary_imports::ActiveModel {
+            path: Set(path.to_string()),
+            checksum: Set(checksum.to_string()),
+            entries: Set(entries),
+            imported_at: Set(Utc::now().naive_utc()),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Id and line checksum of every stored entry in the given languages.
+    pub async fn entry_checksums(
+        &self,
+        lang_codes: &[String],
+    ) -> Result<HashMap<EntryKey, (i32, String)>, sea_orm::DbErr> {
+        Ok(WiktionaryEntries::find()
+            .filter(wiktionary_entries::Column::LangCode.is_in(lang_codes.to_vec()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|m| {
+                let key = EntryKey {
+                    lang_code: m.lang_code,
+                    word: m.word,
+                    pos: m.pos,
+                    etymology_number: m.etymology_number,
+                };
+                (key, (m.id, m.checksum))
+            })
+            .collect())
+    }
+
+    /// Writes new and changed entries with their senses in one transaction,
+    /// replacing the rows they supersede. Returns the ids of the new rows.
+    pub async fn store_entries(&self, updates: &[EntryUpdate]) -> Result<Vec<i32>, sea_orm::DbErr> {
+        let txn = self.db.begin().await?;
+        let now = Utc::now().naive_utc();
+        let mut ids = Vec::with_capacity(updates.len());
+
+        for update in updates {
+            if let Some(id) = update.replaces {
+                WiktionarySenses::delete_many()
+                    .filter(wiktionary_senses::Column::EntryId.eq(id))
+                    .exec(&txn)
+                    .await?;
+                WiktionaryEntries::delete_by_id(id).exec(&txn).await?;
+            }
+
+            let entry = &update.entry;
+            let model = wiktionary_entries::ActiveModel {
+                lang_code: Set(entry.lang_code.clone()),
+                word: Set(entry.word.clone()),
+                headword: Set(normalize_query(&entry.word)),
+                pos: Set(entry.pos.clone()),
+                etymology_number: Set(entry.etymology_number),
+                etymology: Set(entry.etymology.clone()),
+                ipa: Set(json(&entry.ipa)?),
+                inflections: Set(json(&entry.forms)?),
+                checksum: Set(update.checksum.clone()),
+                updated_at: Set(now),
+                ..Default::default()
+            }
+            .insert(&txn)
+            .await?;
+
+            if !entry.senses.is_empty() {
+                let mut senses = Vec::with_capacity(entry.senses.len());
+                for (position, sense) in entry.senses.iter().enumerate() {
+                    senses.push(wiktionary_senses::ActiveModel {
+                        entry_id: Set(model.id),
+                        position: Set(position as i32),
+                        glosses: Set(json(&sense.glosses)?),
+                        tags: Set(json(&sense.tags)?),
+                        examples: Set(json(&sense.examples)?),
+                        ..Default::default()
+                    });
+                }
+                WiktionarySenses::insert_many(senses).exec(&txn).await?;
+            }
+            ids.push(model.id);
+        }
+
+        txn.commit().await?;
+        Ok(ids)
+    }
+
+    /// Deletes entries and their senses. Returns the number of entries deleted.
+    pub async fn delete_entries(&self, ids: &[i32]) -> Result<u64, sea_orm::DbErr> {
+        let mut deleted = 0;
+        for chunk in ids.chunks(DELETE_CHUNK) {
+            WiktionarySenses::delete_many()
+                .filter(wiktionary_senses::Column::EntryId.is_in(chunk.to_vec()))
+                .exec(&self.db)
+                .await?;
+            deleted += WiktionaryEntries::delete_many()
+                .filter(wiktionary_entries::Column::Id.is_in(chunk.to_vec()))
+                .exec(&self.db)
+                .await?
+                .rows_affected;
+        }
+        Ok(deleted)
+    }
+
+    /// Entries of a normalized headword in a language, with their senses.
+    pub async fn find(
+        &self,
+        lang_code: &str,
+        headword: &str,
+    ) -> Result<Vec<WiktionaryEntry>, sea_orm::DbErr> {
+        let models = WiktionaryEntries::find()
+            .filter(wiktionary_entries::Column::LangCode.eq(lang_code.to_string()))
+            .filter(wiktionary_entries::Column::Headword.eq(headword.to_string()))
+            .order_by_asc(wiktionary_entries::Column::EtymologyNumber)
+            .order_by_asc(wiktionary_entries::Column::Id)
+            .all(&self.db)
+            .await?;
+
+        let mut entries = Vec::with_capacity(models.len());
+        for model in models {
+            let senses = WiktionarySenses::find()
+                .filter(wiktionary_senses::Column::EntryId.eq(model.id))
+                .order_by_asc(wiktionary_senses::Column::Position)
+                .all(&self.db)
+                .await?;
+
+            // Rows that no longer deserialize lose the field rather than the entry
+            entries.push(WiktionaryEntry {
+                lang_code: model.lang_code,
+                word: model.word,
+                pos: model.pos,
+                etymology_number: model.etymology_number,
+                etymology: model.etymology,
+                ipa: serde_json::from_str(&model.ipa).unwrap_or_default(),
+                forms: serde_json::from_str(&model.inflections).unwrap_or_default(),
+                senses: senses
+                    .into_iter()
+                    .map(|s| Sense {
+                        glosses: serde_json::from_str(&s.glosses).unwrap_or_default(),
+                        tags: serde_json::from_str(&s.tags).unwrap_or_default(),
+                        examples: serde_json::from_str(&s.examples).unwrap_or_default(),
+                    })
+                    .collect(),
+            });
+        }
+        Ok(entries)
+    }
+
+    pub async fn count_entries(&self) -> Result<u64, sea_orm::DbErr> {
+        WiktionaryEntries::find().count(&self.db).await
+    }
+}
+
+fn json<T: serde::Serialize>(value: &T) -> Result<String, sea_orm::DbErr> {
+    serde_json::to_string(value)
+        .map_err(|e| sea_orm::DbErr::Custom(format!("Failed to serialize entry: {}", e)))
+}
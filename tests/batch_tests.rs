@@ -0,0 +1,108 @@
+use pt_dict_bot::batch::{
+    BatchItem, BatchOutcome, build_glossary_csv, format_glossary, top_translations,
+};
+use pt_dict_bot::fetch_translations::TranslationEntry;
+use pt_dict_bot::i18n::Locale;
+
+fn entry(translation: &str) -> TranslationEntry {
+    TranslationEntry {
+        word: "casa".to_string(),
+        translation: translation.to_string(),
+        ..Default::default()
+    }
+}
+
+fn items() -> Vec<BatchItem> {
+    vec![
+        BatchItem {
+            word: "casa".to_string(),
+            lang_direction: "pten".to_string(),
+            outcome: BatchOutcome::Found {
+                translations: vec!["house".to_string(), "home".to_string()],
+                lemma: None,
+            },
+        },
+        BatchItem {
+            word: "fiz".to_string(),
+            lang_direction: "pten".to_string(),
+            outcome: BatchOutcome::Found {
+                translations: vec!["do, make".to_string()],
+                lemma: Some("fazer".to_string()),
+            },
+        },
+        BatchItem {
+            word: "xyzzy".to_string(),
+            lang_direction: "pten".to_string(),
+            outcome: BatchOutcome::NotFound,
+        },
+        BatchItem {
+            word: "rua".to_string(),
+            lang_direction: "pten".to_string(),
+            outcome: BatchOutcome::Failed("timed out".to_string()),
+        },
+    ]
+}
+
+#[test]
+fn test_top_translations_dedupes_and_limits() {
+    let entries = vec![
+        entry("house"),
+        entry("house"),
+        entry(""),
+        entry("home"),
+        entry("household"),
+        entry("building"),
+    ];
+    assert_eq!(
+        top_translations(&entries, 3),
+        vec!["house", "home", "household"]
+    );
+}
+
+#[test]
+fn test_format_glossary() {
+    let glossary = format_glossary(&items(), 0, Locale::English);
+    assert!(glossary.starts_with("📚 <b>Glossary</b> (4 words)\n\n"));
+    assert!(glossary.contains("<b>casa</b> — house; home\n"));
+    assert!(glossary.contains("<b>fiz</b> (→ fazer) — do, make\n"));
+    assert!(glossary.contains("🤷 Not found: xyzzy\n"));
+    assert!(glossary.contains("⚠️ Failed: rua (timed out)\n"));
+    assert!(!glossary.contains("✂️"));
+
+    let glossary = format_glossary(&items(), 0, Locale::Italian);
+    assert!(glossary.starts_with("📚 <b>Glossario</b> (4 parole)\n\n"));
+    assert!(glossary.contains("🤷 Non trovate: xyzzy\n"));
+}
+
+#[test]
+fn test_format_glossary_lists_rate_limited_words() {
+    let mut items = items();
+    for word in ["mesa", "porta"] {
+        items.push(BatchItem {
+            word: word.to_string(),
+            lang_direction: "pten".to_string(),
+            outcome: BatchOutcome::RateLimited,
+        });
+    }
+    let glossary = format_glossary(&items, 0, Locale::English);
+    assert!(glossary.contains("Try these again in a minute: mesa, porta\n"));
+    assert!(!build_glossary_csv(&items).contains("mesa"));
+}
+
+#[test]
+fn test_format_glossary_mentions_dropped_lines() {
+    let glossary = format_glossary(&items(), 3, Locale::English);
+    assert!(
+        glossary.ends_with("\n✂️ Only the first 50 lines were looked up; 3 more were left out.\n")
+    );
+}
+
+#[test]
+fn test_build_glossary_csv_skips_missing_words() {
+    assert_eq!(
+        build_glossary_csv(&items()),
+        "word,lang_direction,translations\n\
+         casa,pten,house; home\n\
+         fiz,pten,\"do, make\"\n"
+    );
+}
@@ -0,0 +1,293 @@
+use chrono::{Duration, Utc};
+use pt_dict_bot::cache_repository::{ArticleFilter, ArticleRecord, CacheRepository};
+use pt_dict_bot::constants::{LANG_EN_PT, LANG_PT_EN};
+use pt_dict_bot::migration::Migrator;
+use sea_orm::{Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+
+async fn setup_test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+
+    db
+}
+
+#[tokio::test]
+async fn test_insert_and_get_cached() {
+    let repo = CacheRepository::new(setup_test_db().await);
+
+    repo.insert_html("casa", LANG_PT_EN, "<table></table>")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_cached_html("casa", LANG_PT_EN).await.unwrap(),
+        Some("<table></table>".to_string())
+    );
+    assert_eq!(
+        repo.get_cached_formatted("casa", LANG_PT_EN).await.unwrap(),
+        None
+    );
+
+    repo.update_formatted("casa", LANG_PT_EN, "<b>casa</b> ⮕ house\n")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_cached_formatted("casa", LANG_PT_EN).await.unwrap(),
+        Some("<b>casa</b> ⮕ house\n".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_purge() {
+    let repo = CacheRepository::new(setup_test_db().await);
+    repo.insert_html("casa", LANG_PT_EN, "a").await.unwrap();
+    repo.insert_html("casa", LANG_EN_PT, "b").await.unwrap();
+    repo.insert_html("livro", LANG_PT_EN, "c").await.unwrap();
+
+    assert_eq!(repo.purge("casa", Some(LANG_PT_EN)).await.unwrap(), 1);
+    assert!(
+        repo.get_cached_html("casa", LANG_PT_EN)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        repo.get_cached_html("casa", LANG_EN_PT)
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    // Without a direction every direction goes
+    repo.insert_html("casa", LANG_PT_EN, "a").await.unwrap();
+    assert_eq!(repo.purge("casa", None).await.unwrap(), 2);
+    assert_eq!(repo.purge("casa", None).await.unwrap(), 0);
+    assert!(
+        repo.get_cached_html("livro", LANG_PT_EN)
+            .await
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[tokio::test]
+async fn test_get_stats() {
+    let repo = CacheRepository::new(setup_test_db().await);
+    assert_eq!(repo.get_stats().await.unwrap().articles, 0);
+
+    repo.insert_html("casa", LANG_PT_EN, "12345").await.unwrap();
+    repo.update_formatted("casa", LANG_PT_EN, "123")
+        .await
+        .unwrap();
+    repo.insert_html("livro", LANG_PT_EN, "12").await.unwrap();
+
+    let stats = repo.get_stats().await.unwrap();
+    assert_eq!(stats.articles, 2);
+    assert_eq!(stats.bytes, 10);
+}
+
+#[tokio::test]
+async fn test_purge_older_than() {
+    let repo = CacheRepository::new(setup_test_db().await);
+    repo.insert_html("casa", LANG_PT_EN, "a").await.unwrap();
+
+    let past = Utc::now().naive_utc() - Duration::days(1);
+    assert_eq!(repo.purge_older_than(past).await.unwrap(), 0);
+
+    let future = Utc::now().naive_utc() + Duration::days(1);
+    assert_eq!(repo.purge_older_than(future).await.unwrap(), 1);
+    assert!(
+        repo.get_cached_html("casa", LANG_PT_EN)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn test_list_articles_pages_by_id() {
+    let repo = CacheRepository::new(setup_test_db().await);
+    for word in ["a", "b", "c"] {
+        repo.insert_html(word, LANG_PT_EN, "x").await.unwrap();
+    }
+
+    let first = repo.list_articles(0, 2).await.unwrap();
+    assert_eq!(
+        first.iter().map(|a| a.word.as_str()).collect::<Vec<_>>(),
+        vec!["a", "b"]
+    );
+    let rest = repo.list_articles(first[1].id, 2).await.unwrap();
+    assert_eq!(
+        rest.iter().map(|a| a.word.as_str()).collect::<Vec<_>>(),
+        vec!["c"]
+    );
+    assert!(repo.list_articles(rest[0].id, 2).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_list_articles_matching_filter() {
+    let repo = CacheRepository::new(setup_test_db().await);
+    repo.insert_html("casa", LANG_PT_EN, "x").await.unwrap();
+    repo.insert_html("house", LANG_EN_PT, "x").await.unwrap();
+
+    let words = |articles: Vec<pt_dict_bot::entities::cached_articles::Model>| {
+        articles.into_iter().map(|a| a.word).collect::<Vec<_>>()
+    };
+
+    let by_direction = ArticleFilter {
+        lang_direction: Some(LANG_EN_PT.to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        words(
+            repo.list_articles_matching(0, 10, &by_direction)
+                .await
+                .unwrap()
+        ),
+        vec!["house"]
+    );
+
+    let now = Utc::now().naive_utc();
+    let recent = ArticleFilter {
+        updated_since: Some(now - Duration::days(1)),
+        updated_before: Some(now + Duration::days(1)),
+        ..Default::default()
+    };
+    assert_eq!(
+        words(repo.list_articles_matching(0, 10, &recent).await.unwrap()),
+        vec!["casa", "house"]
+    );
+
+    let future = ArticleFilter {
+        updated_since: Some(now + Duration::days(1)),
+        ..Default::default()
+    };
+    assert!(
+        repo.list_articles_matching(0, 10, &future)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_export_import_round_trip() {
+    let source = CacheRepository::new(setup_test_db().await);
+    source
+        .insert_html("casa", LANG_PT_EN, "<table></table>")
+        .await
+        .unwrap();
+    source
+        .update_formatted("casa", LANG_PT_EN, "<b>casa</b> ⮕ house\n")
+        .await
+        .unwrap();
+
+    let lines: Vec<String> = source
+        .list_articles(0, 10)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|article| serde_json::to_string(&ArticleRecord::from(article)).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1);
+
+    let target = CacheRepository::new(setup_test_db().await);
+    let record: ArticleRecord = serde_json::from_str(&lines[0]).unwrap();
+    target.import_article(&record).await.unwrap();
+
+    assert_eq!(
+        target.get_cached_html("casa", LANG_PT_EN).await.unwrap(),
+        Some("<table></table>".to_string())
+    );
+    assert_eq!(
+        target
+            .get_cached_formatted("casa", LANG_PT_EN)
+            .await
+            .unwrap(),
+        Some("<b>casa</b> ⮕ house\n".to_string())
+    );
+}
+
+#[tokio::test]
+async fn test_update_pronunciation() {
+    let repo = CacheRepository::new(setup_test_db().await);
+
+    // Nothing is stored for words that are not cached
+    repo.update_pronunciation("casa", LANG_PT_EN, "/ˈkazɐ/")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_cached_pronunciation("casa", LANG_PT_EN)
+            .await
+            .unwrap(),
+        None
+    );
+
+    repo.insert_html("casa", LANG_PT_EN, "<table></table>")
+        .await
+        .unwrap();
+    repo.update_pronunciation("casa", LANG_PT_EN, "/ˈkazɐ/")
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_cached_pronunciation("casa", LANG_PT_EN)
+            .await
+            .unwrap(),
+        Some("/ˈkazɐ/".to_string())
+    );
+    assert_eq!(
+        repo.get_cached_pronunciation("casa", LANG_EN_PT)
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_store_article() {
+    let repo = CacheRepository::new(setup_test_db().await);
+
+    repo.store_article(
+        "casa",
+        LANG_PT_EN,
+        "<table></table>",
+        Some("<b>casa</b> ⮕ house\n"),
+        Some("/ˈkazɐ/"),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        repo.get_cached_formatted("casa", LANG_PT_EN).await.unwrap(),
+        Some("<b>casa</b> ⮕ house\n".to_string())
+    );
+    assert_eq!(
+        repo.get_cached_pronunciation("casa", LANG_PT_EN)
+            .await
+            .unwrap(),
+        Some("/ˈkazɐ/".to_string())
+    );
+
+    // Storing again replaces the text but keeps a known pronunciation
+    repo.store_article("casa", LANG_PT_EN, "<table>2</table>", None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.get_cached_html("casa", LANG_PT_EN).await.unwrap(),
+        Some("<table>2</table>".to_string())
+    );
+    assert_eq!(
+        repo.get_cached_formatted("casa", LANG_PT_EN).await.unwrap(),
+        None
+    );
+    assert_eq!(
+        repo.get_cached_pronunciation("casa", LANG_PT_EN)
+            .await
+            .unwrap(),
+        Some("/ˈkazɐ/".to_string())
+    );
+}
@@ -0,0 +1,116 @@
+use pt_dict_bot::conjugation_repository::ConjugationRepository;
+use pt_dict_bot::conjugations::{
+    conjugation_language, format_tense, get_conjugation_url, parse_conjugation,
+};
+use pt_dict_bot::constants::{LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
+use pt_dict_bot::migration::Migrator;
+use sea_orm::Database;
+use sea_orm_migration::MigratorTrait;
+
+const CONJ_PAGE: &str = r#"
+    <html>
+        <body>
+            <div class="aa">
+                <h4>indicativo</h4>
+                <table class="neoConj">
+                    <tr><th colspan="2">presente</th></tr>
+                    <tr><th scope="row">eu</th><td><b>faço</b></td></tr>
+                    <tr><th scope="row">tu</th><td>fazes</td></tr>
+                </table>
+                <table class="neoConj">
+                    <tr><th colspan="2">pretérito perfeito</th></tr>
+                    <tr><th scope="row">eu</th><td>fiz</td></tr>
+                </table>
+            </div>
+            <div class="aa">
+                <h4>conjuntivo</h4>
+                <table class="neoConj">
+                    <tr><th colspan="2">presente</th></tr>
+                    <tr><th scope="row">que eu</th><td>faça</td></tr>
+                </table>
+            </div>
+        </body>
+    </html>
+"#;
+
+#[test]
+fn test_conjugation_language() {
+    assert_eq!(conjugation_language(LANG_PT_EN), Some("pt"));
+    assert_eq!(conjugation_language(LANG_EN_PT), Some("pt"));
+    assert_eq!(conjugation_language(LANG_IT_EN), Some("it"));
+    assert_eq!(conjugation_language(LANG_EN_IT), Some("it"));
+    assert_eq!(conjugation_language("enfr"), None);
+    assert_eq!(conjugation_language("invalid"), None);
+}
+
+#[test]
+fn test_get_conjugation_url() {
+    assert_eq!(
+        get_conjugation_url("fazer", "pt"),
+        "https://www.wordreference.com/conj/ptverbs.aspx?v=fazer"
+    );
+    assert_eq!(
+        get_conjugation_url("pôr", "pt"),
+        "https://www.wordreference.com/conj/ptverbs.aspx?v=p%C3%B4r"
+    );
+    assert_eq!(
+        get_conjugation_url("dar-se conta&x", "pt"),
+        "https://www.wordreference.com/conj/ptverbs.aspx?v=dar-se%20conta%26x"
+    );
+}
+
+#[test]
+fn test_parse_conjugation_moods_and_tenses() {
+    let table = parse_conjugation("fazer", CONJ_PAGE).expect("Should parse");
+
+    assert_eq!(table.verb, "fazer");
+    assert_eq!(table.tenses.len(), 3);
+    assert_eq!(table.tenses[0].mood, "indicativo");
+    assert_eq!(table.tenses[0].tense, "presente");
+    assert_eq!(table.tenses[0].forms[0].person, "eu");
+    assert_eq!(table.tenses[0].forms[0].form, "faço");
+    assert_eq!(table.tenses[1].tense, "pretérito perfeito");
+    assert_eq!(table.tenses[2].mood, "conjuntivo");
+    assert_eq!(table.tenses[2].forms[0].person, "que eu");
+    assert_eq!(table.tenses[2].label(), "con. presente");
+}
+
+#[test]
+fn test_parse_conjugation_without_tables() {
+    assert_eq!(parse_conjugation("xyz", "<html><body></body></html>"), None);
+}
+
+#[test]
+fn test_format_tense() {
+    let table = parse_conjugation("fazer", CONJ_PAGE).unwrap();
+
+    assert_eq!(
+        format_tense(&table, 0).unwrap(),
+        "<b>fazer</b> — indicativo · presente\n<i>eu</i> faço\n<i>tu</i> fazes\n"
+    );
+    assert_eq!(format_tense(&table, 10), None);
+}
+
+#[tokio::test]
+async fn test_conjugation_cache_roundtrip() {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    let repo = ConjugationRepository::new(db);
+
+    assert!(repo.get_conjugation("fazer", "pt").await.unwrap().is_none());
+
+    let table = parse_conjugation("fazer", CONJ_PAGE).unwrap();
+    repo.store_conjugation("pt", &table).await.unwrap();
+    // Storing again replaces the row instead of failing on the unique index
+    repo.store_conjugation("pt", &table).await.unwrap();
+
+    assert_eq!(
+        repo.get_conjugation("fazer", "pt").await.unwrap(),
+        Some(table)
+    );
+    assert!(repo.get_conjugation("fazer", "it").await.unwrap().is_none());
+}
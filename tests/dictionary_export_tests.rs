@@ -0,0 +1,124 @@
+use std::cmp::Ordering;
+
+use chrono::NaiveDate;
+use pt_dict_bot::constants::LANG_PT_EN;
+use pt_dict_bot::dictionary_export::{DictionaryEntry, build_stardict, stardict_cmp};
+use pt_dict_bot::entities::cached_articles::Model;
+use pt_dict_bot::fetch_translations::TranslationEntry;
+
+fn dictionary_entry(headword: &str, translation: &str) -> DictionaryEntry {
+    DictionaryEntry {
+        headword: headword.to_string(),
+        lang_direction: LANG_PT_EN.to_string(),
+        entries: vec![TranslationEntry {
+            word: headword.to_string(),
+            part_of_speech: "sf".to_string(),
+            sense: String::new(),
+            translation: translation.to_string(),
+            examples: Vec::new(),
+            variants: Vec::new(),
+        }],
+        updated_at: NaiveDate::from_ymd_opt(2025, 10, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap(),
+    }
+}
+
+/// Splits a StarDict index into (word, offset, size) triples.
+fn parse_idx(idx: &[u8]) -> Vec<(String, u32, u32)> {
+    let mut words = Vec::new();
+    let mut rest = idx;
+    while !rest.is_empty() {
+        let end = rest.iter().position(|&b| b == 0).unwrap();
+        let word = String::from_utf8(rest[..end].to_vec()).unwrap();
+        let offset = u32::from_be_bytes(rest[end + 1..end + 5].try_into().unwrap());
+        let size = u32::from_be_bytes(rest[end + 5..end + 9].try_into().unwrap());
+        words.push((word, offset, size));
+        rest = &rest[end + 9..];
+    }
+    words
+}
+
+#[test]
+fn test_from_article_parses_and_skips_empty() {
+    let now = NaiveDate::from_ymd_opt(2025, 10, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    let mut article = Model {
+        id: 1,
+        word: "casa".to_string(),
+        lang_direction: LANG_PT_EN.to_string(),
+        html: r#"<table class="WRD">
+            <tr class="even"><td class="FrWrd"><strong>casa</strong> sf</td><td></td><td class="ToWrd">house</td></tr>
+        </table>"#
+            .to_string(),
+        formatted: None,
+        pronunciation: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let entry = DictionaryEntry::from_article(&article).unwrap();
+    assert_eq!(entry.headword, "casa");
+    assert_eq!(entry.entries.len(), 1);
+    assert_eq!(entry.entries[0].translation, "house");
+
+    article.html = String::new();
+    assert!(DictionaryEntry::from_article(&article).is_none());
+}
+
+#[test]
+fn test_to_html_escapes_text() {
+    let entry = dictionary_entry("P&D", "R&D <research>");
+    assert_eq!(
+        entry.to_html(),
+        "<b>P&amp;D</b> <i>sf</i> — R&amp;D &lt;research&gt;"
+    );
+}
+
+#[test]
+fn test_stardict_cmp() {
+    assert_eq!(stardict_cmp("Casa", "casa"), Ordering::Less);
+    assert_eq!(stardict_cmp("casa", "Dado"), Ordering::Less);
+    assert_eq!(stardict_cmp("b", "B"), Ordering::Greater);
+}
+
+#[test]
+fn test_build_stardict() {
+    let entries = vec![
+        dictionary_entry("mesa", "table"),
+        dictionary_entry("casa", "house"),
+        dictionary_entry("casa", "home"),
+    ];
+    let stardict = build_stardict("pt_dict_bot pten", "Test", &entries);
+
+    let index = parse_idx(&stardict.idx);
+    let words: Vec<&str> = index.iter().map(|(w, _, _)| w.as_str()).collect();
+    assert_eq!(words, vec!["casa", "mesa"]);
+
+    // Each index entry points at its article in the .dict file
+    let article = |i: usize| {
+        let (_, offset, size) = index[i];
+        std::str::from_utf8(&stardict.dict[offset as usize..(offset + size) as usize]).unwrap()
+    };
+    assert_eq!(
+        article(0),
+        "<b>casa</b> <i>sf</i> — house<br><b>casa</b> <i>sf</i> — home"
+    );
+    assert_eq!(article(1), "<b>mesa</b> <i>sf</i> — table");
+
+    assert!(
+        stardict
+            .ifo
+            .starts_with("StarDict's dict ifo file\nversion=2.4.2\n")
+    );
+    assert!(stardict.ifo.contains("\nwordcount=2\n"));
+    assert!(
+        stardict
+            .ifo
+            .contains(&format!("\nidxfilesize={}\n", stardict.idx.len()))
+    );
+    assert!(stardict.ifo.contains("\nsametypesequence=h\n"));
+}
@@ -0,0 +1,109 @@
+use std::io::{Cursor, Read};
+
+use pt_dict_bot::constants::LANG_PT_EN;
+use pt_dict_bot::export::{VocabularyCard, build_apkg, build_csv};
+use pt_dict_bot::fetch_translations::TranslationEntry;
+use sea_orm::{ConnectionTrait, Database, DbBackend, Statement};
+
+fn entry(translation: &str, part_of_speech: &str, examples: &[&str]) -> TranslationEntry {
+    TranslationEntry {
+        word: "casa".to_string(),
+        part_of_speech: part_of_speech.to_string(),
+        sense: String::new(),
+        translation: translation.to_string(),
+        examples: examples.iter().map(|e| e.to_string()).collect(),
+        variants: Vec::new(),
+    }
+}
+
+#[test]
+fn test_card_from_entries_deduplicates() {
+    let entries = vec![
+        entry("home", "sf", &["Vou para casa."]),
+        entry("house", "sf", &[]),
+        entry("home", "sf", &["Vou para casa."]),
+    ];
+    let card = VocabularyCard::from_entries("casa", LANG_PT_EN, &entries);
+
+    assert_eq!(card.front, "casa");
+    assert_eq!(card.back, "home; house");
+    assert_eq!(card.part_of_speech, "sf");
+    assert_eq!(card.examples, vec!["Vou para casa."]);
+}
+
+#[test]
+fn test_build_csv_quotes_fields() {
+    let card = VocabularyCard {
+        front: "casa".to_string(),
+        back: "home, house".to_string(),
+        part_of_speech: "sf".to_string(),
+        examples: vec![
+            "Ele disse \"olá\".".to_string(),
+            "Vou para casa.".to_string(),
+        ],
+        lang_direction: LANG_PT_EN.to_string(),
+    };
+    let csv = build_csv(&[card]);
+
+    assert_eq!(
+        csv,
+        "front,back,part_of_speech,examples\n\
+         casa,\"home, house\",sf,\"Ele disse \"\"olá\"\". | Vou para casa.\"\n"
+    );
+}
+
+#[tokio::test]
+async fn test_build_apkg_contains_collection() {
+    let cards = vec![VocabularyCard::from_entries(
+        "casa",
+        LANG_PT_EN,
+        &[entry("home", "sf", &["Vou para casa."])],
+    )];
+    let apkg = build_apkg(&cards, "Test deck")
+        .await
+        .expect("Failed to build apkg");
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(apkg)).expect("Not a zip archive");
+    let mut media = String::new();
+    archive
+        .by_name("media")
+        .unwrap()
+        .read_to_string(&mut media)
+        .unwrap();
+    assert_eq!(media, "{}");
+
+    let mut collection = Vec::new();
+    archive
+        .by_name("collection.anki2")
+        .unwrap()
+        .read_to_end(&mut collection)
+        .unwrap();
+
+    // Reopen the collection and check the note made it in
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("collection.anki2");
+    std::fs::write(&path, collection).unwrap();
+    let db = Database::connect(format!("sqlite://{}", path.display()))
+        .await
+        .unwrap();
+    let row = db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT flds, sfld FROM notes",
+        ))
+        .await
+        .unwrap()
+        .expect("Note should exist");
+    let fields: String = row.try_get("", "flds").unwrap();
+    assert_eq!(fields, "casa\x1fhome\x1fsf\x1fVou para casa.");
+
+    let card_count = db
+        .query_one(Statement::from_string(
+            DbBackend::Sqlite,
+            "SELECT COUNT(*) AS n FROM cards",
+        ))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(card_count.try_get::<i64>("", "n").unwrap(), 1);
+}
@@ -1,19 +1,49 @@
 use pt_dict_bot::constants::{LANG_IT_EN, LANG_PT_EN};
 use pt_dict_bot::fetch_translations::{
-    get_raw_translations, get_translation_table_header, get_translations,
+    get_pronunciations, get_raw_translations, get_translation_entries,
+    get_translation_table_header, get_translation_url, get_translations,
 };
+use pt_dict_bot::variants::Variant;
 
 #[test]
 fn test_get_translation_table_header() {
-    assert_eq!(get_translation_table_header(LANG_PT_EN), "Traduções principais");
+    assert_eq!(
+        get_translation_table_header(LANG_PT_EN),
+        "Traduções principais"
+    );
     assert_eq!(
         get_translation_table_header(LANG_IT_EN),
         "Principal Translations/Traduzioni principali"
     );
+    assert_eq!(
+        get_translation_table_header("esen"),
+        "Traducciones principales"
+    );
+    assert_eq!(get_translation_table_header("ende"), "Hauptübersetzungen");
     // Default case
     assert_eq!(get_translation_table_header("xyz"), "Traduções principais");
 }
 
+#[test]
+fn test_get_translation_url() {
+    assert_eq!(
+        get_translation_url("casa", LANG_PT_EN),
+        "https://www.wordreference.com/pten/casa"
+    );
+    assert_eq!(
+        get_translation_url("de repente", LANG_PT_EN),
+        "https://www.wordreference.com/pten/de%20repente"
+    );
+    assert_eq!(
+        get_translation_url("coração", LANG_PT_EN),
+        "https://www.wordreference.com/pten/cora%C3%A7%C3%A3o"
+    );
+    assert_eq!(
+        get_translation_url("a/b?c#d", LANG_PT_EN),
+        "https://www.wordreference.com/pten/a%2Fb%3Fc%23d"
+    );
+}
+
 #[test]
 fn test_get_raw_translations_found() {
     // Simulate minimal WordReference-like HTML containing table with "Traduções principais"
@@ -47,6 +77,23 @@ fn test_get_raw_translations_not_found() {
     assert_eq!(result, "");
 }
 
+#[test]
+fn test_get_pronunciations() {
+    let body = r#"
+        <div id="articleHead">
+            <h1 class="hWord">casa</h1>
+            <div id="pronunciation_widget">
+                <span id="pronWR" class="pronWR">/ˈkazɐ/</span>
+                <span class="pronRH">/ˈkaza/</span>
+                <span class="pronRH"> /ˈkaza/ </span>
+            </div>
+        </div>
+    "#;
+
+    assert_eq!(get_pronunciations(body), vec!["/ˈkazɐ/", "/ˈkaza/"]);
+    assert!(get_pronunciations("<html><body></body></html>").is_empty());
+}
+
 #[test]
 fn test_get_translations_basic() {
     let table_html = r#"
@@ -71,6 +118,25 @@ fn test_get_translations_basic() {
     );
 }
 
+#[test]
+fn test_get_translations_escapes_text() {
+    let table_html = r#"
+        <table class="WRD">
+            <tr class="odd">
+                <td><strong>P&amp;D</strong></td>
+                <td>nf</td>
+                <td>R&amp;D &lt;research&gt;</td>
+            </tr>
+        </table>
+    "#;
+    let result = get_translations(table_html);
+    assert!(
+        result.contains("<b>P&amp;D</b> nf ⮕ R&amp;D &lt;research&gt;"),
+        "Output: {}",
+        result
+    );
+}
+
 #[test]
 fn test_get_translations_skips_bad_rows() {
     let table_html = r#"
@@ -88,3 +154,71 @@ fn test_get_translations_skips_bad_rows() {
     assert!(result.contains("⮕ three"));
     assert!(!result.contains("only one td"));
 }
+
+#[test]
+fn test_get_translation_entries_structured() {
+    let table_html = r#"
+        <table class="WRD">
+            <tr class="wrtopsection"><td colspan="3">Traduções principais</td></tr>
+            <tr class="even">
+                <td class="FrWrd"><strong>casa</strong> <em class="tooltip POS2">sf<span>substantivo feminino</span></em></td>
+                <td> (lar, habitação)</td>
+                <td class="ToWrd">home <em class="tooltip POS2">n<span>noun</span></em></td>
+            </tr>
+            <tr class="even"><td>&nbsp;</td><td colspan="2" class="FrEx">Vou para casa.</td></tr>
+            <tr class="even"><td>&nbsp;</td><td colspan="2" class="ToEx">I'm going home.</td></tr>
+            <tr class="even">
+                <td>&nbsp;</td>
+                <td>&nbsp;</td>
+                <td class="ToWrd">house <em class="tooltip POS2">n<span>noun</span></em></td>
+            </tr>
+        </table>
+    "#;
+    let entries = get_translation_entries(table_html);
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].word, "casa");
+    assert_eq!(entries[0].part_of_speech, "sf");
+    assert_eq!(entries[0].sense, "(lar, habitação)");
+    assert_eq!(entries[0].translation, "home");
+    assert_eq!(
+        entries[0].examples,
+        vec!["Vou para casa.", "I'm going home."]
+    );
+
+    // Continuation row inherits the headword and part of speech
+    assert_eq!(entries[1].word, "casa");
+    assert_eq!(entries[1].part_of_speech, "sf");
+    assert_eq!(entries[1].translation, "house");
+    assert!(entries[1].examples.is_empty());
+}
+
+#[test]
+fn test_get_translation_entries_captures_variants() {
+    let table_html = r#"
+        <table class="WRD">
+            <tr class="wrtopsection"><td colspan="3">Traduções principais</td></tr>
+            <tr class="even">
+                <td class="FrWrd"><strong>bus</strong> <em class="tooltip POS2">n<span>noun</span></em></td>
+                <td> (vehicle)</td>
+                <td class="ToWrd">ônibus (BR) <em class="tooltip POS2">sm<span>substantivo masculino</span></em></td>
+            </tr>
+            <tr class="even">
+                <td>&nbsp;</td>
+                <td>&nbsp;</td>
+                <td class="ToWrd">autocarro (PT) <em class="tooltip POS2">sm<span>substantivo masculino</span></em></td>
+            </tr>
+            <tr class="odd">
+                <td class="FrWrd"><strong>coach</strong> <em class="tooltip POS2">n<span>noun</span></em></td>
+                <td> (long-distance bus)</td>
+                <td class="ToWrd">ônibus <em class="tooltip POS2">sm<span>substantivo masculino</span></em></td>
+            </tr>
+        </table>
+    "#;
+    let entries = get_translation_entries(table_html);
+
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].variants, vec![Variant::Brazilian]);
+    assert_eq!(entries[1].variants, vec![Variant::European]);
+    assert!(entries[2].variants.is_empty());
+}
@@ -41,6 +41,21 @@ fn test_flip_all_supported_directions() {
     }
 }
 
+#[test]
+fn test_flip_registry_directions() {
+    let test_cases = [
+        ("esen", "enes"),
+        ("fren", "enfr"),
+        ("deen", "ende"),
+        ("ptes", "espt"),
+    ];
+
+    for (input, expected) in test_cases {
+        assert_eq!(flip_direction(input), Some(expected.to_string()));
+        assert_eq!(flip_direction(expected), Some(input.to_string()));
+    }
+}
+
 #[test]
 fn test_flip_invalid_length_too_short() {
     let result = flip_direction("pt");
@@ -77,7 +92,7 @@ fn test_flip_double_flip_returns_original() {
 #[test]
 fn test_flip_rejects_unsupported_direction() {
     // Test various unsupported directions
-    assert_eq!(flip_direction("deen"), None);
+    assert_eq!(flip_direction("ruen"), None);
     assert_eq!(flip_direction("esfr"), None);
     assert_eq!(flip_direction("jaen"), None);
 }
@@ -98,7 +113,7 @@ async fn test_flip_command_for_existing_user() {
 
     // Verify initial direction
     let user = repo.get_user("test_chat").await.unwrap().unwrap();
-    assert_eq!(user.translation_direction, "pten");
+    assert_eq!(user.translation_direction.as_deref(), Some("pten"));
 
     // Simulate flip command by updating direction
     repo.update_translation_direction("test_chat", "enpt")
@@ -107,7 +122,7 @@ async fn test_flip_command_for_existing_user() {
 
     // Verify direction changed
     let user_after = repo.get_user("test_chat").await.unwrap().unwrap();
-    assert_eq!(user_after.translation_direction, "enpt");
+    assert_eq!(user_after.translation_direction.as_deref(), Some("enpt"));
     assert_eq!(user_after.user_id, Some(123)); // Preserved
     assert_eq!(user_after.username, Some("testuser".to_string())); // Preserved
 }
@@ -129,7 +144,7 @@ async fn test_flip_command_for_new_user() {
 
     // Verify user was created with flipped default
     let user = repo.get_user("new_chat").await.unwrap().unwrap();
-    assert_eq!(user.translation_direction, "enpt");
+    assert_eq!(user.translation_direction.as_deref(), Some("enpt"));
     assert_eq!(user.user_id, Some(456));
 }
 
@@ -149,7 +164,7 @@ async fn test_flip_command_double_flip_returns_original() {
         .expect("Failed to first flip");
 
     let user = repo.get_user("flip_twice_chat").await.unwrap().unwrap();
-    assert_eq!(user.translation_direction, "enpt");
+    assert_eq!(user.translation_direction.as_deref(), Some("enpt"));
 
     // Second flip: enpt -> pten (back to original)
     repo.update_translation_direction("flip_twice_chat", "pten")
@@ -157,7 +172,7 @@ async fn test_flip_command_double_flip_returns_original() {
         .expect("Failed to second flip");
 
     let user = repo.get_user("flip_twice_chat").await.unwrap().unwrap();
-    assert_eq!(user.translation_direction, "pten");
+    assert_eq!(user.translation_direction.as_deref(), Some("pten"));
 }
 
 #[tokio::test]
@@ -180,7 +195,7 @@ async fn test_flip_command_preserves_user_data() {
     let after = repo.get_user("preserve_chat").await.unwrap().unwrap();
 
     // Verify only direction changed
-    assert_eq!(after.translation_direction, "enpt");
+    assert_eq!(after.translation_direction.as_deref(), Some("enpt"));
     assert_eq!(after.user_id, before.user_id);
     assert_eq!(after.username, before.username);
     assert_eq!(after.created_at, before.created_at);
@@ -0,0 +1,137 @@
+use pt_dict_bot::group_chat::{GroupMode, replied_message, should_respond, strip_bot_mentions};
+use serde_json::json;
+use teloxide::types::{Message, MessageEntity, MessageEntityKind, User, UserId};
+
+const BOT_ID: UserId = UserId(42);
+
+fn mention(offset: usize, length: usize) -> MessageEntity {
+    MessageEntity::new(MessageEntityKind::Mention, offset, length)
+}
+
+fn user(id: u64) -> User {
+    User {
+        id: UserId(id),
+        is_bot: id == BOT_ID.0,
+        first_name: "Dict".to_string(),
+        last_name: None,
+        username: None,
+        language_code: None,
+        is_premium: false,
+        added_to_attachment_menu: false,
+    }
+}
+
+#[test]
+fn test_strip_mention_at_start_or_in_the_middle() {
+    assert_eq!(
+        strip_bot_mentions("@PtDictBot casa", &[mention(0, 10)], "ptdictbot", BOT_ID),
+        Some("casa".to_string())
+    );
+    assert_eq!(
+        strip_bot_mentions(
+            "o que é saudade @ptdictbot?",
+            &[mention(16, 10)],
+            "ptdictbot",
+            BOT_ID
+        ),
+        Some("o que é saudade ?".to_string())
+    );
+}
+
+#[test]
+fn test_strip_mention_uses_utf16_offsets() {
+    // The emoji is two UTF-16 code units but four UTF-8 bytes
+    let text = "🇧🇷 @ptdictbot ônibus";
+    assert_eq!(
+        strip_bot_mentions(text, &[mention(5, 10)], "ptdictbot", BOT_ID),
+        Some("🇧🇷  ônibus".to_string())
+    );
+}
+
+#[test]
+fn test_strip_text_mention_of_the_bot() {
+    let entities = [MessageEntity::text_mention(user(BOT_ID.0), 0, 4)];
+    assert_eq!(
+        strip_bot_mentions("Dict fazer", &entities, "ptdictbot", BOT_ID),
+        Some("fazer".to_string())
+    );
+
+    let entities = [MessageEntity::text_mention(user(7), 0, 4)];
+    assert_eq!(
+        strip_bot_mentions("Dict fazer", &entities, "ptdictbot", BOT_ID),
+        None
+    );
+}
+
+#[test]
+fn test_other_mentions_are_not_the_bot() {
+    assert_eq!(
+        strip_bot_mentions("@someone casa", &[mention(0, 8)], "ptdictbot", BOT_ID),
+        None
+    );
+    // Text that looks like a mention without an entity is not one
+    assert_eq!(
+        strip_bot_mentions("@ptdictbot casa", &[], "ptdictbot", BOT_ID),
+        None
+    );
+}
+
+#[test]
+fn test_group_mode_codes() {
+    for mode in GroupMode::ALL {
+        assert_eq!(GroupMode::from_code(mode.code()), Some(mode));
+    }
+    assert_eq!(GroupMode::from_code(" ALL "), Some(GroupMode::All));
+    assert_eq!(GroupMode::from_code("never"), None);
+    assert_eq!(GroupMode::default(), GroupMode::Mention);
+}
+
+#[test]
+fn test_should_respond() {
+    // Commands are answered in every mode
+    for mode in GroupMode::ALL {
+        assert!(should_respond(mode, false, false, true));
+    }
+
+    assert!(should_respond(GroupMode::Mention, true, false, false));
+    assert!(!should_respond(GroupMode::Mention, false, true, false));
+
+    assert!(should_respond(GroupMode::Reply, false, true, false));
+    assert!(!should_respond(GroupMode::Reply, true, false, false));
+
+    assert!(should_respond(GroupMode::All, false, false, false));
+}
+
+/// A forum message replying to message `replied_id`, posted in the topic
+/// started by message `thread_id`.
+fn topic_message(thread_id: i32, replied_id: i32) -> Message {
+    let chat = json!({ "id": -100, "type": "supergroup", "title": "Forum", "is_forum": true });
+    let from = json!({ "id": 7, "is_bot": false, "first_name": "Ana" });
+    serde_json::from_value(json!({
+        "message_id": 20,
+        "message_thread_id": thread_id,
+        "is_topic_message": true,
+        "date": 0,
+        "chat": chat,
+        "from": from,
+        "text": "casa",
+        "reply_to_message": {
+            "message_id": replied_id,
+            "message_thread_id": thread_id,
+            "date": 0,
+            "chat": chat,
+            "from": from,
+            "text": "uma casa grande",
+        },
+    }))
+    .expect("Valid message")
+}
+
+#[test]
+fn test_replied_message_skips_topic_roots() {
+    // Telegram points every message of a topic at the topic's first message
+    assert!(replied_message(&topic_message(10, 10)).is_none());
+    // A reply to another message in the topic is a real reply
+    let msg = topic_message(10, 15);
+    assert_eq!(replied_message(&msg).map(|m| m.id.0), Some(15));
+}
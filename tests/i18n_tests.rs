@@ -0,0 +1,71 @@
+use pt_dict_bot::i18n::{self, Locale};
+use pt_dict_bot::languages::direction;
+
+#[test]
+fn test_catalogs_translate_every_message() {
+    for locale in Locale::ALL {
+        assert_eq!(
+            i18n::missing_messages(locale),
+            Vec::<String>::new(),
+            "{} catalog is incomplete",
+            locale.code()
+        );
+    }
+}
+
+#[test]
+fn test_locale_from_code() {
+    assert_eq!(Locale::from_code("pt"), Some(Locale::Portuguese));
+    assert_eq!(Locale::from_code("pt-br"), Some(Locale::Portuguese));
+    assert_eq!(Locale::from_code("it_IT"), Some(Locale::Italian));
+    assert_eq!(Locale::from_code("EN"), Some(Locale::English));
+    assert_eq!(Locale::from_code("de"), None);
+    assert_eq!(Locale::from_code(""), None);
+
+    // Telegram users without a translated language get English
+    assert_eq!(Locale::from_language_code(Some("de")), Locale::English);
+    assert_eq!(Locale::from_language_code(None), Locale::English);
+    assert_eq!(Locale::from_language_code(Some("it")), Locale::Italian);
+}
+
+#[test]
+fn test_translate_with_arguments() {
+    assert_eq!(
+        i18n::t_args(Locale::English, "word-saved", &[("word", "casa")]),
+        "⭐ Saved \"casa\" to your vocabulary."
+    );
+    // No bidi isolation marks around the argument
+    assert_eq!(
+        i18n::t_args(Locale::Portuguese, "word-saved", &[("word", "casa")]),
+        "⭐ \"casa\" guardada no teu vocabulário."
+    );
+    assert_eq!(
+        i18n::t(Locale::Italian, "no-translations"),
+        "Nessuna traduzione trovata."
+    );
+}
+
+#[test]
+fn test_unknown_message_falls_back_to_id() {
+    assert_eq!(
+        i18n::t(Locale::Portuguese, "no-such-message"),
+        "no-such-message"
+    );
+}
+
+#[test]
+fn test_direction_name() {
+    let pten = direction("pten").unwrap();
+    assert_eq!(
+        i18n::direction_name(Locale::English, pten),
+        "🇵🇹 Portuguese → 🇬🇧 English"
+    );
+    assert_eq!(
+        i18n::direction_name(Locale::Portuguese, pten),
+        "🇵🇹 Português → 🇬🇧 Inglês"
+    );
+    assert_eq!(
+        i18n::direction_name(Locale::Italian, pten),
+        "🇵🇹 Portoghese → 🇬🇧 Inglese"
+    );
+}
@@ -19,7 +19,9 @@ async fn test_user_config_integration() {
 
     // Test default fallback
     let default_direction = match repo.get_user("unknown_chat").await {
-        Ok(Some(user)) => user.translation_direction,
+        Ok(Some(user)) => user
+            .translation_direction
+            .unwrap_or_else(|| DEFAULT_LANG_DIRECTION.to_string()),
         _ => DEFAULT_LANG_DIRECTION.to_string(),
     };
     assert_eq!(default_direction, DEFAULT_LANG_DIRECTION);
@@ -36,7 +38,7 @@ async fn test_user_config_integration() {
         .expect("User should exist")
         .translation_direction;
 
-    assert_eq!(stored_direction, LANG_IT_EN);
+    assert_eq!(stored_direction.as_deref(), Some(LANG_IT_EN));
 }
 
 #[tokio::test]
@@ -65,7 +67,7 @@ async fn test_chat_context_behavior() {
         .expect("Group config should exist")
         .translation_direction;
 
-    assert_eq!(group_direction, LANG_IT_EN);
+    assert_eq!(group_direction.as_deref(), Some(LANG_IT_EN));
 
     // Individual users in the group don't have separate settings
     let individual_user_result = repo
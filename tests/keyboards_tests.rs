@@ -0,0 +1,123 @@
+use pt_dict_bot::conjugations::{ConjugatedTense, ConjugationTable};
+use pt_dict_bot::i18n::Locale;
+use pt_dict_bot::keyboards::{
+    CALLBACK_DATA_LIMIT, fits, reply_page, settings_picker, tense_picker, variant_picker,
+    word_picker,
+};
+use pt_dict_bot::variants::{Variant, VariantPreference};
+use teloxide::types::{InlineKeyboardButtonKind, InlineKeyboardMarkup};
+
+fn table(verb: &str) -> ConjugationTable {
+    ConjugationTable {
+        verb: verb.to_string(),
+        tenses: ["presente", "pretérito perfeito", "futuro", "imperfeito"]
+            .into_iter()
+            .map(|tense| ConjugatedTense {
+                mood: "indicativo".to_string(),
+                tense: tense.to_string(),
+                forms: Vec::new(),
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_fits() {
+    assert!(fits(&"x".repeat(CALLBACK_DATA_LIMIT)));
+    assert!(!fits(&"x".repeat(CALLBACK_DATA_LIMIT + 1)));
+    // Bytes are counted, not characters
+    assert!(!fits(&"ã".repeat(CALLBACK_DATA_LIMIT / 2 + 1)));
+}
+
+#[test]
+fn test_tense_picker() {
+    let keyboard = tense_picker(&table("fazer"), "pt", 1).expect("Should fit");
+    let buttons: Vec<_> = keyboard.inline_keyboard.iter().flatten().collect();
+
+    assert_eq!(keyboard.inline_keyboard.len(), 2);
+    assert_eq!(buttons.len(), 4);
+    assert!(buttons[1].text.starts_with("• "));
+    assert_eq!(
+        buttons[3].kind,
+        InlineKeyboardButtonKind::CallbackData("conj:pt:3:fazer".to_string())
+    );
+}
+
+#[test]
+fn test_tense_picker_drops_long_verbs() {
+    let verb = "a".repeat(CALLBACK_DATA_LIMIT);
+    assert_eq!(tense_picker(&table(&verb), "pt", 0), None);
+}
+
+fn callback_data(keyboard: &InlineKeyboardMarkup) -> Vec<String> {
+    keyboard
+        .inline_keyboard
+        .iter()
+        .flatten()
+        .filter_map(|button| match &button.kind {
+            InlineKeyboardButtonKind::CallbackData(data) => Some(data.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_reply_page_navigation() {
+    let full = "<b>casa</b> ⮕ house\n".repeat(500);
+    let (_, keyboard) = reply_page("casa", "pten", false, &full, 1, Locale::English);
+    let data = callback_data(&keyboard.expect("Long replies get a keyboard"));
+
+    assert!(data.contains(&"page:p0:pten:casa".to_string()));
+    assert!(data.contains(&"noop".to_string()));
+    assert!(data.contains(&"page:p2:pten:casa".to_string()));
+    assert!(data.contains(&"all:p:pten:casa".to_string()));
+}
+
+#[test]
+fn test_reply_page_long_phrase_fits() {
+    let phrase = "não há mal que sempre dure nem bem que nunca acabe já já";
+    assert_eq!(phrase.len(), 60);
+
+    let full = "<b>frase</b> ⮕ saying\n".repeat(500);
+    let (text, keyboard) = reply_page(phrase, "pten", true, &full, 1, Locale::English);
+    assert!(!text.is_empty());
+
+    // Buttons that cannot carry the phrase are dropped, the rest fit
+    let data = callback_data(&keyboard.expect("The page counter is always shown"));
+    assert_eq!(data, vec!["noop".to_string()]);
+    assert!(data.iter().all(|data| fits(data)));
+}
+
+#[test]
+fn test_word_picker_drops_long_words() {
+    let words = vec!["casa".to_string(), "a".repeat(CALLBACK_DATA_LIMIT)];
+    assert_eq!(callback_data(&word_picker(&words)), vec!["pick:casa"]);
+}
+
+#[test]
+fn test_settings_picker_marks_choices() {
+    let keyboard = settings_picker(Locale::English, "pten", None);
+    let marked: Vec<&str> = keyboard
+        .inline_keyboard
+        .iter()
+        .flatten()
+        .filter(|button| button.text.starts_with("• "))
+        .map(|button| button.text.as_str())
+        .collect();
+    assert_eq!(marked, vec!["• 🇵🇹 Portuguese → 🇬🇧 English", "• 🌐 Auto"]);
+    assert!(callback_data(&keyboard).contains(&"loc:it".to_string()));
+}
+
+#[test]
+fn test_variant_picker_toggles_the_other_variant() {
+    let preference = VariantPreference {
+        preferred: Some(Variant::Brazilian),
+        hide_others: true,
+    };
+    let keyboard = variant_picker(Locale::English, preference);
+    assert_eq!(
+        callback_data(&keyboard),
+        vec!["var:pt", "var:br", "var:any", "var:show"]
+    );
+    assert!(keyboard.inline_keyboard[0][1].text.starts_with("• "));
+}
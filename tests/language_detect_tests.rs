@@ -0,0 +1,43 @@
+use pt_dict_bot::constants::{LANG_EN_PT, LANG_IT_EN, LANG_PT_EN};
+use pt_dict_bot::language_detect::{detect_direction, detect_language, score};
+use pt_dict_bot::languages::direction;
+
+#[test]
+fn test_detect_portuguese_and_english() {
+    for word in ["cozinha", "esquecer", "felicidade", "lembrança"] {
+        assert_eq!(detect_language(word, &["pt", "en"]), Some("pt"), "{}", word);
+    }
+    for word in ["kitchen", "forget", "weather", "through"] {
+        assert_eq!(detect_language(word, &["pt", "en"]), Some("en"), "{}", word);
+    }
+}
+
+#[test]
+fn test_detect_italian() {
+    assert_eq!(detect_language("dimenticare", &["it", "en"]), Some("it"));
+    assert_eq!(detect_language("felicità", &["it", "en"]), Some("it"));
+    assert_eq!(detect_language("window", &["it", "en"]), Some("en"));
+}
+
+#[test]
+fn test_foreign_letters_are_penalised() {
+    // "ç" and "ã" are not English letters
+    assert!(score("coração", "pt").unwrap() > score("coração", "en").unwrap());
+}
+
+#[test]
+fn test_unknown_language_and_empty_text() {
+    assert_eq!(score("casa", "ja"), None);
+    assert_eq!(score("", "pt"), None);
+    assert_eq!(detect_language("casa", &["ja"]), None);
+}
+
+#[test]
+fn test_detect_direction() {
+    let pten = direction(LANG_PT_EN).unwrap();
+    assert_eq!(detect_direction("cozinha", pten).code, LANG_PT_EN);
+    assert_eq!(detect_direction("kitchen", pten).code, LANG_EN_PT);
+
+    let iten = direction(LANG_IT_EN).unwrap();
+    assert_eq!(detect_direction("window", iten).code, "enit");
+}
@@ -0,0 +1,37 @@
+use pt_dict_bot::constants::{DEFAULT_LANG_DIRECTION, LANG_PT_EN};
+use pt_dict_bot::languages::{DIRECTIONS, LANGUAGES, direction, language};
+
+#[test]
+fn test_every_direction_is_consistent() {
+    for d in DIRECTIONS {
+        assert_eq!(d.code.len(), 4, "{} should be a 4-letter code", d.code);
+        assert_eq!(d.code, format!("{}{}", d.source, d.target));
+        assert!(language(d.source).is_some(), "{}: unknown source", d.code);
+        assert!(language(d.target).is_some(), "{}: unknown target", d.code);
+        assert!(!d.table_header.is_empty());
+
+        // Every direction can be flipped, and flipping twice is the identity
+        let reversed = d.reversed().expect("Every direction has a reverse");
+        assert_eq!(reversed.reversed(), Some(d));
+    }
+}
+
+#[test]
+fn test_registry_covers_requested_languages() {
+    for code in ["pt", "en", "it", "es", "fr", "de"] {
+        assert!(LANGUAGES.iter().any(|l| l.code == code), "missing {}", code);
+    }
+    for code in [
+        "esen", "enes", "fren", "enfr", "deen", "ende", "ptes", "espt",
+    ] {
+        assert!(direction(code).is_some(), "missing {}", code);
+    }
+    assert!(direction(DEFAULT_LANG_DIRECTION).is_some());
+}
+
+#[test]
+fn test_lookup_by_code() {
+    assert_eq!(direction(LANG_PT_EN).unwrap().source, "pt");
+    assert_eq!(language("it").unwrap().flag, "🇮🇹");
+    assert_eq!(direction("xxyy"), None);
+}
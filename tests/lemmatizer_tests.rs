@@ -0,0 +1,104 @@
+use pt_dict_bot::i18n::{self, Locale};
+use pt_dict_bot::lemmatizer::{
+    LemmaCandidate, MAX_RULE_CANDIDATES, descriptions, format_lemma_note, lemmatize, lookup_order,
+};
+
+fn lemmas(word: &str, lang: &str) -> Vec<String> {
+    lemmatize(word, lang).into_iter().map(|c| c.lemma).collect()
+}
+
+#[test]
+fn test_irregular_portuguese_forms() {
+    let candidates = lemmatize("fiz", "pt");
+    assert_eq!(
+        candidates[0],
+        LemmaCandidate {
+            lemma: "fazer".to_string(),
+            description: "1st sg. preterite".to_string(),
+            listed: true,
+        }
+    );
+
+    // Ambiguous listed forms keep every lemma
+    assert_eq!(lemmas("foi", "pt")[..2], ["ser", "ir"]);
+}
+
+#[test]
+fn test_regular_portuguese_rules() {
+    let candidates = lemmatize("falaram", "pt");
+    assert_eq!(candidates[0].lemma, "falar");
+    assert_eq!(candidates[0].description, "3rd pl. preterite");
+    assert!(!candidates[0].listed);
+
+    assert_eq!(lemmas("casas", "pt"), vec!["casa"]);
+    assert_eq!(lemmas("canções", "pt")[0], "canção");
+    assert_eq!(lemmas("animais", "pt")[0], "animal");
+}
+
+#[test]
+fn test_italian_forms() {
+    assert_eq!(lemmas("fece", "it")[0], "fare");
+    assert_eq!(lemmas("parlarono", "it")[0], "parlare");
+    assert_eq!(lemmas("amiche", "it")[0], "amica");
+    assert!(lemmas("libri", "it").contains(&"libro".to_string()));
+}
+
+#[test]
+fn test_short_and_unknown() {
+    // Stems shorter than two characters are not produced
+    assert!(lemmatize("as", "pt").is_empty());
+    // Unsupported languages have no lemmatiser
+    assert!(lemmatize("houses", "en").is_empty());
+    // The word itself is never a candidate
+    assert!(!lemmas("casa", "pt").contains(&"casa".to_string()));
+}
+
+#[test]
+fn test_format_lemma_note() {
+    let candidate = &lemmatize("fiz", "pt")[0];
+    assert_eq!(
+        format_lemma_note("fiz", candidate, Locale::English),
+        "<code>fiz</code> → form of <code>fazer</code> (1st sg. preterite)\n\n"
+    );
+    assert_eq!(
+        format_lemma_note("fiz", candidate, Locale::Portuguese),
+        "<code>fiz</code> → forma de <code>fazer</code> (1.ª sg. pretérito perfeito)\n\n"
+    );
+}
+
+#[test]
+fn test_every_description_is_translated() {
+    for description in descriptions() {
+        // A missing message would come back as its id
+        let id = i18n::inflection_id(description);
+        assert_eq!(i18n::t(Locale::English, &id), description);
+        for locale in Locale::ALL {
+            assert!(
+                i18n::missing_messages(locale).iter().all(|m| *m != id),
+                "{} is missing {}",
+                locale.code(),
+                id
+            );
+        }
+    }
+}
+
+#[test]
+fn test_lookup_order_tries_the_word_first() {
+    // A form that is also a headword keeps its own entry ahead of the lemma's
+    let order = lookup_order("visto", "pt");
+    assert_eq!(order[0], ("visto".to_string(), None));
+    assert_eq!(order[1].0, "ver");
+    assert!(order[1].1.as_ref().is_some_and(|c| c.listed));
+
+    // Listed lemmas come before a bounded number of rule guesses
+    let order = lookup_order("falaram", "pt");
+    assert_eq!(order[0].0, "falaram");
+    assert_eq!(order[1].0, "falar");
+    assert!(order.len() <= 1 + MAX_RULE_CANDIDATES);
+
+    assert_eq!(
+        lookup_order("house", "en"),
+        vec![("house".to_string(), None)]
+    );
+}
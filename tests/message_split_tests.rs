@@ -0,0 +1,57 @@
+use pt_dict_bot::message_split::{TELEGRAM_MESSAGE_LIMIT, split_message, telegram_len};
+
+fn entry(i: usize) -> String {
+    format!("<b>dar</b> vtr (entregar) ⮕ give number {}\n", i)
+}
+
+#[test]
+fn test_short_message_is_single_chunk() {
+    let html = "<b>casa</b> nf ⮕ house\n";
+    assert_eq!(split_message(html, TELEGRAM_MESSAGE_LIMIT), vec![html]);
+}
+
+#[test]
+fn test_empty_message_has_no_chunks() {
+    assert!(split_message("", TELEGRAM_MESSAGE_LIMIT).is_empty());
+}
+
+#[test]
+fn test_splits_between_entries() {
+    let html: String = (0..300).map(entry).collect();
+    assert!(telegram_len(&html) > TELEGRAM_MESSAGE_LIMIT);
+
+    let chunks = split_message(&html, TELEGRAM_MESSAGE_LIMIT);
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        assert!(telegram_len(chunk) <= TELEGRAM_MESSAGE_LIMIT);
+        // Every chunk holds whole entries only
+        assert!(chunk.starts_with("<b>dar</b>"));
+        assert!(chunk.ends_with('\n'));
+    }
+    assert_eq!(chunks.concat(), html);
+}
+
+#[test]
+fn test_long_line_keeps_tags_balanced() {
+    let html = format!("<b>{}</b> ⮕ end\n", "palavra ".repeat(20));
+    let chunks = split_message(&html, 50);
+
+    assert!(chunks.len() > 1);
+    for chunk in &chunks {
+        assert!(telegram_len(chunk) <= 50, "{:?}", chunk);
+        assert_eq!(
+            chunk.matches("<b>").count(),
+            chunk.matches("</b>").count(),
+            "{:?}",
+            chunk
+        );
+    }
+    assert!(chunks.last().unwrap().ends_with("end\n"), "{:?}", chunks);
+}
+
+#[test]
+fn test_counts_utf16_units() {
+    // Flags are two code points and four UTF-16 units
+    assert_eq!(telegram_len("🇵🇹"), 4);
+    assert_eq!(telegram_len("ção"), 3);
+}
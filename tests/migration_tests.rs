@@ -1,8 +1,10 @@
 use pt_dict_bot::entities::cached_articles::Entity as CachedArticles;
 use pt_dict_bot::entities::users::Entity as Users;
 use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::saved_words_repository::SavedWordsRepository;
+use pt_dict_bot::user_repository::UserRepository;
 use sea_orm::Database;
-use sea_orm::EntityTrait;
+use sea_orm::{ConnectionTrait, EntityTrait};
 use sea_orm_migration::MigratorTrait;
 
 #[tokio::test]
@@ -28,3 +30,97 @@ async fn test_migrations_create_tables() {
     assert!(users.is_none());
     assert!(cached.is_none());
 }
+
+#[tokio::test]
+async fn test_existing_group_directions_are_kept() {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+
+    // Rows stored before chat directions became optional
+    Migrator::up(&db, Some(13))
+        .await
+        .expect("Failed to run migrations");
+    let repo = UserRepository::new(db.clone());
+    for (chat_id, direction) in [("42", "pten"), ("-100", "pten"), ("-200", "enpt")] {
+        repo.create_or_update_user(chat_id, direction, None, None)
+            .await
+            .expect("Failed to create user");
+    }
+
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    repo.create_chat("-300", None, None)
+        .await
+        .expect("Failed to create chat");
+
+    let direction = |chat_id: &'static str| {
+        let repo = repo.clone();
+        async move {
+            repo.get_user(chat_id)
+                .await
+                .unwrap()
+                .expect("Row should be kept")
+                .translation_direction
+        }
+    };
+    // A stored default may have been chosen with /flip, so it stays
+    assert_eq!(direction("42").await.as_deref(), Some("pten"));
+    assert_eq!(direction("-100").await.as_deref(), Some("pten"));
+    assert_eq!(direction("-200").await.as_deref(), Some("enpt"));
+    // Groups created since have none until it is set for the whole group
+    assert_eq!(direction("-300").await, None);
+
+    // Rolling back gives them the default
+    Migrator::down(&db, Some(2))
+        .await
+        .expect("Failed to roll back");
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    assert_eq!(direction("-300").await.as_deref(), Some("pten"));
+}
+
+#[tokio::test]
+async fn test_saved_words_get_their_owner() {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+
+    // Words saved before they were kept per member
+    Migrator::up(&db, Some(14))
+        .await
+        .expect("Failed to run migrations");
+    db.execute_unprepared(
+        "INSERT INTO saved_words (chat_id, word, lang_direction) \
+         VALUES ('42', 'casa', 'pten'), ('-100', 'livro', 'pten')",
+    )
+    .await
+    .expect("Failed to save words");
+
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+
+    let repo = SavedWordsRepository::new(db.clone());
+    let private = repo.get_saved_words("42", 42).await.unwrap();
+    assert_eq!(private.len(), 1);
+    assert_eq!(private[0].user_id, Some(42));
+    // Older group words stay shared by every member
+    for user_id in [7, 8] {
+        let shared = repo.get_saved_words("-100", user_id).await.unwrap();
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].user_id, None);
+    }
+
+    // Rolling back keeps one copy of a word saved by several members
+    repo.save_word("-100", 7, "livro", "pten").await.unwrap();
+    Migrator::down(&db, Some(1))
+        .await
+        .expect("Failed to roll back");
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    assert_eq!(repo.get_saved_words("-100", 7).await.unwrap().len(), 1);
+}
@@ -0,0 +1,29 @@
+use std::time::{Duration, Instant};
+
+use pt_dict_bot::miss_cache::MissCache;
+
+#[test]
+fn test_records_misses_per_direction() {
+    let misses = MissCache::new(Duration::from_secs(60));
+    let now = Instant::now();
+
+    misses.record_at("xyzzy", "pten", now);
+
+    assert!(misses.contains_at("xyzzy", "pten", now));
+    assert!(!misses.contains_at("xyzzy", "enpt", now));
+    assert!(!misses.contains_at("casa", "pten", now));
+}
+
+#[test]
+fn test_misses_expire() {
+    let misses = MissCache::new(Duration::from_secs(60));
+    let start = Instant::now();
+    misses.record_at("xyzzy", "pten", start);
+
+    assert!(misses.contains_at("xyzzy", "pten", start + Duration::from_secs(59)));
+    assert!(!misses.contains_at("xyzzy", "pten", start + Duration::from_secs(60)));
+
+    // Expired entries are dropped when the next miss is recorded
+    misses.record_at("plugh", "pten", start + Duration::from_secs(61));
+    assert_eq!(misses.len(), 1);
+}
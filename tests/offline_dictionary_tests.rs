@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use pt_dict_bot::constants::{LANG_EN_PT, LANG_PT_EN};
+use pt_dict_bot::dictionary_export::{DictionaryEntry, build_stardict};
+use pt_dict_bot::fetch_translations::TranslationEntry;
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::offline_dictionary::{
+    DictionaryFormat, OfflineArticle, OfflineMode, OfflineProvider, dsl_headwords, dsl_to_text,
+    parse_dsl, parse_offline_dictionaries, parse_stardict_idx, stardict_to_text,
+};
+use pt_dict_bot::offline_index_repository::OfflineIndexRepository;
+use sea_orm::Database;
+use sea_orm_migration::MigratorTrait;
+
+const DSL: &str = "#NAME \"Test PT-EN\"\n\
+                   #INDEX_LANGUAGE \"Portuguese\"\n\
+                   \n\
+                   casa\n\
+                   lar\n\
+                   \t[m1][b]1.[/b] [trn]house[/trn][/m]\n\
+                   \t[m2][ex][lang id=1033]a ~ azul[/lang] {{note}}— the blue house[/ex][/m]\n\
+                   \n\
+                   (o) livro{s}\n\
+                   \t[trn]book[/trn]\n";
+
+async fn setup_provider(mode: OfflineMode) -> OfflineProvider {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    OfflineProvider::new(OfflineIndexRepository::new(db), mode)
+}
+
+fn write_stardict(dir: &Path) -> PathBuf {
+    let entry = DictionaryEntry {
+        headword: "house".to_string(),
+        lang_direction: LANG_EN_PT.to_string(),
+        entries: vec![TranslationEntry {
+            word: "house".to_string(),
+            part_of_speech: "n".to_string(),
+            translation: "casa".to_string(),
+            ..Default::default()
+        }],
+        updated_at: NaiveDate::from_ymd_opt(2025, 10, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap(),
+    };
+    let stardict = build_stardict("Test EN-PT", "", &[entry]);
+    let base = dir.join("en-pt");
+    std::fs::write(base.with_extension("ifo"), &stardict.ifo).unwrap();
+    std::fs::write(base.with_extension("idx"), &stardict.idx).unwrap();
+    std::fs::write(base.with_extension("dict"), &stardict.dict).unwrap();
+    base.with_extension("ifo")
+}
+
+#[test]
+fn test_parse_offline_dictionaries() {
+    let parsed =
+        parse_offline_dictionaries("pten=/d/pt-en.ifo; enpt = /d/en-pt.dsl ;xx=/d/a.dsl;pten=");
+    assert_eq!(
+        parsed,
+        vec![
+            ("pten".to_string(), PathBuf::from("/d/pt-en.ifo")),
+            ("enpt".to_string(), PathBuf::from("/d/en-pt.dsl")),
+        ]
+    );
+    assert!(parse_offline_dictionaries("").is_empty());
+}
+
+#[test]
+fn test_dictionary_format_from_path() {
+    assert_eq!(
+        DictionaryFormat::from_path(Path::new("/d/pt-en.ifo")),
+        Some(DictionaryFormat::StarDict)
+    );
+    assert_eq!(
+        DictionaryFormat::from_path(Path::new("/d/PT-EN.DSL.dz")),
+        Some(DictionaryFormat::Dsl)
+    );
+    assert_eq!(DictionaryFormat::from_path(Path::new("/d/pt-en.txt")), None);
+}
+
+#[test]
+fn test_parse_stardict_idx() {
+    let mut idx = Vec::new();
+    for (word, offset, size) in [("casa", 0u32, 5u32), ("mesa", 5, 7)] {
+        idx.extend_from_slice(word.as_bytes());
+        idx.push(0);
+        idx.extend_from_slice(&offset.to_be_bytes());
+        idx.extend_from_slice(&size.to_be_bytes());
+    }
+
+    assert_eq!(
+        parse_stardict_idx(&idx, 32).unwrap(),
+        vec![("casa".to_string(), 0, 5), ("mesa".to_string(), 5, 7)]
+    );
+    assert!(parse_stardict_idx(&idx[..idx.len() - 2], 32).is_err());
+}
+
+#[test]
+fn test_stardict_to_text() {
+    assert_eq!(stardict_to_text(b"house\nhome", "m"), "house\nhome");
+    assert_eq!(
+        stardict_to_text(b"<b>house</b><br>R&amp;D", "h"),
+        "house\nR&D"
+    );
+    // Without sametypesequence each field starts with its type
+    assert_eq!(stardict_to_text(b"tka.za\0mhouse\0", ""), "ka.za\nhouse");
+}
+
+#[test]
+fn test_dsl_headwords() {
+    assert_eq!(dsl_headwords("casa"), vec!["casa"]);
+    assert_eq!(dsl_headwords("(o) livro{s}"), vec!["o livro", "livro"]);
+    assert_eq!(dsl_headwords("Rock\\(s\\)"), vec!["rock(s)"]);
+}
+
+#[test]
+fn test_dsl_to_text() {
+    let body = "\t[m1][b]1.[/b] [trn]house[/trn][/m]\n\
+                \t[m2][ex]a ~ azul {{note}}— the blue house[/ex][/m]\n\
+                \t<<lar>> \\[sic\\]";
+    assert_eq!(
+        dsl_to_text(body, "casa"),
+        "1. house\na casa azul — the blue house\nlar [sic]"
+    );
+}
+
+#[test]
+fn test_parse_dsl_utf8_and_utf16() {
+    let utf8 = parse_dsl(DSL.as_bytes());
+    assert_eq!(utf8.name.as_deref(), Some("Test PT-EN"));
+    let headwords: Vec<&str> = utf8.headwords.iter().map(|h| h.0.as_str()).collect();
+    assert_eq!(headwords, vec!["casa", "lar", "o livro", "livro"]);
+
+    // Both headwords of the first article point at the same body
+    let (_, offset, size) = utf8.headwords[0];
+    assert_eq!(utf8.headwords[1].1, offset);
+    let body = &DSL.as_bytes()[offset as usize..(offset + size) as usize];
+    assert!(body.starts_with(b"\t[m1]"));
+    assert!(body.ends_with(b"house[/ex][/m]"));
+
+    let mut utf16 = vec![0xff, 0xfe];
+    for unit in DSL.replace('\n', "\r\n").encode_utf16() {
+        utf16.extend_from_slice(&unit.to_le_bytes());
+    }
+    let parsed = parse_dsl(&utf16);
+    assert_eq!(parsed.name, utf8.name);
+    assert_eq!(
+        parsed.headwords.iter().map(|h| &h.0).collect::<Vec<_>>(),
+        utf8.headwords.iter().map(|h| &h.0).collect::<Vec<_>>()
+    );
+    let (_, offset, size) = parsed.headwords[3];
+    let units: Vec<u16> = utf16[offset as usize..(offset + size) as usize]
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    assert_eq!(String::from_utf16(&units).unwrap(), "\t[trn]book[/trn]");
+}
+
+#[test]
+fn test_offline_article_html_and_entries() {
+    let article = OfflineArticle {
+        headword: "casa".to_string(),
+        dictionary: "Test <PT-EN>".to_string(),
+        text: "house\nhome".to_string(),
+    };
+
+    assert_eq!(
+        article.to_html(),
+        "📖 <i>Test &lt;PT-EN&gt;</i>\n<b>casa</b>\nhouse\nhome\n"
+    );
+    let entries = article.entries();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1].word, "casa");
+    assert_eq!(entries[1].translation, "home");
+}
+
+#[tokio::test]
+async fn test_provider_indexes_and_looks_up() {
+    let dir = tempfile::tempdir().unwrap();
+    let dsl_path = dir.path().join("pt-en.dsl");
+    std::fs::write(&dsl_path, DSL).unwrap();
+    let ifo_path = write_stardict(dir.path());
+    let data_dir = dir.path().join("data");
+    let dictionaries = vec![
+        (LANG_PT_EN.to_string(), dsl_path),
+        (LANG_EN_PT.to_string(), ifo_path),
+    ];
+
+    let provider = setup_provider(OfflineMode::Fallback).await;
+    assert_eq!(provider.prepare(&dictionaries, &data_dir).await.unwrap(), 2);
+    // Unchanged files are not indexed again
+    assert_eq!(provider.prepare(&dictionaries, &data_dir).await.unwrap(), 0);
+
+    let article = provider.lookup("Lar", LANG_PT_EN).await.unwrap().unwrap();
+    assert_eq!(article.dictionary, "Test PT-EN");
+    assert_eq!(article.text, "1. house\na Lar azul — the blue house");
+
+    let article = provider.lookup("house", LANG_EN_PT).await.unwrap().unwrap();
+    assert_eq!(article.text, "house n — casa");
+
+    assert!(
+        provider
+            .lookup("house", LANG_PT_EN)
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert!(provider.lookup("mesa", LANG_PT_EN).await.unwrap().is_none());
+
+    // Dictionaries dropped from the configuration are dropped from the index
+    provider
+        .prepare(&dictionaries[..1], &data_dir)
+        .await
+        .unwrap();
+    assert!(
+        provider
+            .lookup("house", LANG_EN_PT)
+            .await
+            .unwrap()
+            .is_none()
+    );
+}
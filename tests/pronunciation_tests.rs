@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use pt_dict_bot::pronunciation::{Synthesizer, TtsConfig, voice_for};
+use pt_dict_bot::variants::Variant;
+
+#[test]
+fn test_voice_for() {
+    assert_eq!(voice_for("pt", None), Some("pt"));
+    assert_eq!(voice_for("pt", Some(Variant::European)), Some("pt"));
+    assert_eq!(voice_for("pt", Some(Variant::Brazilian)), Some("pt-br"));
+    assert_eq!(voice_for("it", Some(Variant::Brazilian)), Some("it"));
+    assert_eq!(voice_for("xx", None), None);
+}
+
+#[test]
+fn test_cache_path_is_keyed_by_word_and_voice() {
+    let synthesizer = Synthesizer::new(TtsConfig::from_env(), Path::new("/audio"));
+
+    let path = synthesizer.cache_path("casa", "pt");
+    assert!(path.starts_with("/audio/pt"));
+    assert_eq!(path.extension().unwrap(), "ogg");
+
+    // Queries that normalize to the same word share a clip
+    assert_eq!(synthesizer.cache_path(" Casa ", "pt"), path);
+    assert_ne!(synthesizer.cache_path("casa", "pt-br"), path);
+    assert_ne!(synthesizer.cache_path("caso", "pt"), path);
+}
+
+/// Writes an executable shell script standing in for espeak-ng or ffmpeg.
+#[cfg(unix)]
+fn script(dir: &Path, name: &str, body: &str) -> String {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = dir.join(name);
+    std::fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+    path.to_string_lossy().into_owned()
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_synthesize_caches_clips() {
+    let tools = tempfile::tempdir().unwrap();
+    let audio = tempfile::tempdir().unwrap();
+    let log = tools.path().join("runs.log");
+
+    // espeak-ng -v VOICE --stdin -w WAV: records the spoken text as the "audio"
+    let espeak = script(
+        tools.path(),
+        "espeak-ng",
+        &format!(
+            "[ \"$1\" = --version ] && exit 0\ncat > \"$5\"\necho run >> {}",
+            log.display()
+        ),
+    );
+    // ffmpeg -y -loglevel error -i WAV ... OGG: copies the input through
+    let ffmpeg = script(
+        tools.path(),
+        "ffmpeg",
+        "[ \"$1\" = -version ] && exit 0\ncp \"$5\" \"${12}\"",
+    );
+    let synthesizer = Synthesizer::new(TtsConfig { espeak, ffmpeg }, audio.path());
+    assert!(synthesizer.is_available().await);
+
+    let path = synthesizer.synthesize("casa", "pt").await.unwrap();
+    assert_eq!(path, synthesizer.cache_path("casa", "pt"));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "casa");
+
+    // The second request is served from disk without running the engine
+    synthesizer.synthesize("casa", "pt").await.unwrap();
+    assert_eq!(std::fs::read_to_string(&log).unwrap().lines().count(), 1);
+
+    // Only the finished clip is left in the voice's directory
+    let files = std::fs::read_dir(audio.path().join("pt")).unwrap().count();
+    assert_eq!(files, 1);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_synthesize_reports_failures() {
+    let tools = tempfile::tempdir().unwrap();
+    let audio = tempfile::tempdir().unwrap();
+    let espeak = script(tools.path(), "espeak-ng", "echo 'no voice' >&2\nexit 1");
+    let config = TtsConfig {
+        espeak,
+        ffmpeg: "true".to_string(),
+    };
+    let synthesizer = Synthesizer::new(config, audio.path());
+
+    let error = synthesizer.synthesize("casa", "pt").await.unwrap_err();
+    assert!(error.to_string().contains("no voice"), "{}", error);
+    assert!(!synthesizer.cache_path("casa", "pt").exists());
+
+    let missing = Synthesizer::new(
+        TtsConfig {
+            espeak: tools.path().join("missing").to_string_lossy().into_owned(),
+            ffmpeg: "true".to_string(),
+        },
+        audio.path(),
+    );
+    assert!(!missing.is_available().await);
+}
@@ -0,0 +1,64 @@
+use pt_dict_bot::query::{
+    MAX_BATCH_LINES, MAX_MESSAGE_WORDS, normalize_query, split_queries, tokenize_words,
+};
+
+#[test]
+fn test_normalize_query() {
+    assert_eq!(normalize_query("  De   Repente \t"), "de repente");
+    assert_eq!(normalize_query("Coração"), "coração");
+    assert_eq!(normalize_query("   "), "");
+}
+
+#[test]
+fn test_split_queries() {
+    let text = "casa\n\n  De repente \nCASA\r\nfazer";
+    assert_eq!(
+        split_queries(text),
+        (
+            vec![
+                "casa".to_string(),
+                "de repente".to_string(),
+                "fazer".to_string()
+            ],
+            0
+        )
+    );
+}
+
+#[test]
+fn test_split_queries_single_line() {
+    assert_eq!(
+        split_queries("de repente"),
+        (vec!["de repente".to_string()], 0)
+    );
+}
+
+#[test]
+fn test_split_queries_caps_batch() {
+    let text = (0..MAX_BATCH_LINES + 5)
+        .map(|i| format!("word{}", i))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let (queries, dropped) = split_queries(&text);
+    assert_eq!(queries.len(), MAX_BATCH_LINES);
+    assert_eq!(dropped, 5);
+}
+
+#[test]
+fn test_tokenize_words() {
+    let text = "@amigo Olha: o Guarda-chuva d'água custou 20 euros, olha! /t #dica https://x.pt e";
+    assert_eq!(
+        tokenize_words(text),
+        vec!["olha", "guarda-chuva", "d'água", "custou", "euros"]
+    );
+    assert!(tokenize_words("  ").is_empty());
+}
+
+#[test]
+fn test_tokenize_words_caps_words() {
+    let text = (0..MAX_MESSAGE_WORDS + 5)
+        .map(|i| format!("palavra{}", i))
+        .collect::<Vec<_>>()
+        .join(" ");
+    assert_eq!(tokenize_words(&text).len(), MAX_MESSAGE_WORDS);
+}
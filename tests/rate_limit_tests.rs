@@ -0,0 +1,142 @@
+use std::time::{Duration, Instant};
+
+use pt_dict_bot::rate_limit::{
+    CircuitBreaker, RateLimitConfig, RateLimiter, TokenBucket, parse_admin_ids,
+};
+
+fn config() -> RateLimitConfig {
+    RateLimitConfig {
+        chat_burst: 5,
+        chat_per_minute: 60,
+        user_burst: 3,
+        user_per_minute: 60,
+        fetch_per_second: 1,
+        admin_ids: vec![42],
+    }
+}
+
+#[test]
+fn test_token_bucket_empties_and_refills() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(2, 60, start);
+
+    assert!(bucket.try_take(1, start));
+    assert!(bucket.try_take(1, start));
+    assert!(!bucket.try_take(1, start));
+    assert_eq!(bucket.wait_time(start), Duration::from_secs(1));
+
+    // One token a second at 60 a minute
+    let later = start + Duration::from_secs(1);
+    assert!(bucket.try_take(1, later));
+    assert!(!bucket.try_take(1, later));
+}
+
+#[test]
+fn test_token_bucket_caps_at_capacity() {
+    let start = Instant::now();
+    let mut bucket = TokenBucket::new(2, 60, start);
+    let later = start + Duration::from_secs(3600);
+    assert!(bucket.try_take(2, later));
+    assert!(!bucket.try_take(1, later));
+}
+
+#[test]
+fn test_rate_limiter_limits_user_across_chats() {
+    let limiter = RateLimiter::new(config());
+    let now = Instant::now();
+
+    assert!(limiter.check_at(1, Some(7), 2, now));
+    assert!(limiter.check_at(2, Some(7), 1, now));
+    assert!(!limiter.check_at(3, Some(7), 1, now));
+    // Another user is unaffected
+    assert!(limiter.check_at(3, Some(8), 1, now));
+}
+
+#[test]
+fn test_rate_limiter_limits_chat_across_users() {
+    let limiter = RateLimiter::new(config());
+    let now = Instant::now();
+
+    assert!(limiter.check_at(1, Some(7), 3, now));
+    assert!(limiter.check_at(1, Some(8), 2, now));
+    assert!(!limiter.check_at(1, Some(9), 1, now));
+}
+
+#[test]
+fn test_rate_limiter_rejection_takes_nothing() {
+    let limiter = RateLimiter::new(config());
+    let now = Instant::now();
+
+    // The chat has room but the user does not: neither bucket is charged
+    assert!(!limiter.check_at(1, Some(7), 4, now));
+    assert!(limiter.check_at(1, Some(7), 3, now));
+}
+
+#[test]
+fn test_rate_limiter_exempts_admins_and_free_lookups() {
+    let limiter = RateLimiter::new(config());
+    let now = Instant::now();
+
+    for _ in 0..20 {
+        assert!(limiter.check_at(1, Some(42), 1, now));
+    }
+    assert!(limiter.is_admin(Some(42)));
+    assert!(!limiter.is_admin(None));
+
+    assert!(limiter.check_at(2, Some(7), 3, now));
+    assert!(limiter.check_at(2, Some(7), 0, now));
+}
+
+#[test]
+fn test_rate_limiter_drops_refilled_buckets() {
+    let limiter = RateLimiter::new(config());
+    let start = Instant::now();
+
+    assert!(limiter.check_at(1, Some(7), 1, start));
+    assert!(limiter.check_at(2, Some(8), 1, start));
+    assert_eq!(limiter.len(), 4);
+
+    // A token refills in a second, after which those buckets are full again
+    assert!(limiter.check_at(3, Some(9), 1, start + Duration::from_secs(2)));
+    assert_eq!(limiter.len(), 2);
+
+    // A recreated bucket starts full, as the dropped one was
+    assert!(limiter.check_at(1, Some(7), 3, start + Duration::from_secs(2)));
+}
+
+#[test]
+fn test_rate_limiter_serves_part_of_a_large_batch() {
+    // A 50-word batch of uncached words against the default limits
+    let limiter = RateLimiter::new(RateLimitConfig::default());
+    let now = Instant::now();
+
+    assert!(!limiter.check_at(1, Some(7), 50, now));
+    assert_eq!(limiter.take_up_to_at(1, Some(7), 50, now), 40);
+    assert_eq!(limiter.take_up_to_at(1, Some(7), 50, now), 0);
+    // The chat's bucket lets another member look up the rest
+    assert_eq!(limiter.take_up_to_at(1, Some(8), 50, now), 20);
+
+    let admin = RateLimiter::new(config());
+    assert_eq!(admin.take_up_to_at(1, Some(42), 50, now), 50);
+}
+
+#[test]
+fn test_parse_admin_ids() {
+    assert_eq!(parse_admin_ids("1, 22,abc,,333"), vec![1, 22, 333]);
+    assert!(parse_admin_ids("").is_empty());
+}
+
+#[test]
+fn test_circuit_breaker() {
+    let breaker = CircuitBreaker::new(Duration::from_secs(60));
+    let start = Instant::now();
+    assert!(!breaker.is_open_at(start));
+
+    breaker.record_failure_at(start);
+    assert!(breaker.is_open_at(start + Duration::from_secs(59)));
+    assert!(!breaker.is_open_at(start + Duration::from_secs(60)));
+
+    breaker.record_failure_at(start);
+    breaker.record_success();
+    assert!(!breaker.is_open_at(start));
+}
@@ -0,0 +1,65 @@
+use pt_dict_bot::render::{escape_html, sanitize_html, to_plain_text};
+
+#[test]
+fn test_escape_html() {
+    assert_eq!(
+        escape_html("R&D <i> \"x\""),
+        "R&amp;D &lt;i&gt; &quot;x&quot;"
+    );
+    assert_eq!(escape_html("casa"), "casa");
+}
+
+#[test]
+fn test_sanitize_keeps_allowed_tags() {
+    let html = "<b>casa</b> n ⮕ <i>house</i>, <code>home</code>\n";
+    assert_eq!(sanitize_html(html), html);
+}
+
+#[test]
+fn test_sanitize_escapes_stray_markup() {
+    assert_eq!(sanitize_html("R&D"), "R&amp;D");
+    assert_eq!(sanitize_html("a < b > c"), "a &lt; b &gt; c");
+    assert_eq!(
+        sanitize_html("<span class=\"x\">word</span>"),
+        "&lt;span class=\"x\"&gt;word&lt;/span&gt;"
+    );
+}
+
+#[test]
+fn test_sanitize_keeps_valid_entities() {
+    assert_eq!(
+        sanitize_html("&lt;i&gt; &amp; &#39; &#x1F600;"),
+        "&lt;i&gt; &amp; &#39; &#x1F600;"
+    );
+    assert_eq!(sanitize_html("&nbsp;"), "&amp;nbsp;");
+}
+
+#[test]
+fn test_sanitize_balances_tags() {
+    assert_eq!(sanitize_html("<b>open"), "<b>open</b>");
+    assert_eq!(sanitize_html("closed</i> text"), "closed text");
+    assert_eq!(sanitize_html("<b><i>x</b>"), "<b><i>x</i></b>");
+}
+
+#[test]
+fn test_sanitize_links_keep_only_href() {
+    assert_eq!(
+        sanitize_html("<a href=\"https://example.com\" onclick=\"x\">link</a>"),
+        "<a href=\"https://example.com\">link</a>"
+    );
+}
+
+#[test]
+fn test_sanitize_is_idempotent() {
+    let messy = "<b>R&D</b> <x> & <i>open";
+    let once = sanitize_html(messy);
+    assert_eq!(sanitize_html(&once), once);
+}
+
+#[test]
+fn test_to_plain_text() {
+    assert_eq!(
+        to_plain_text("<b>R&amp;D</b> ⮕ &lt;i&gt; &#39;x&#39;"),
+        "R&D ⮕ <i> 'x'"
+    );
+}
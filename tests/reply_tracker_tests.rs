@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+use pt_dict_bot::reply_tracker::ReplyTracker;
+
+#[test]
+fn test_records_replies_per_chat() {
+    let tracker = ReplyTracker::new(Duration::from_secs(60));
+    let now = Instant::now();
+
+    tracker.record_at(1, 10, 11, now);
+    tracker.record_at(2, 10, 12, now);
+
+    assert_eq!(tracker.get_at(1, 10, now), Some(11));
+    assert_eq!(tracker.get_at(2, 10, now), Some(12));
+    assert_eq!(tracker.get_at(1, 99, now), None);
+}
+
+#[test]
+fn test_replies_expire() {
+    let tracker = ReplyTracker::new(Duration::from_secs(60));
+    let start = Instant::now();
+    tracker.record_at(1, 10, 11, start);
+
+    assert_eq!(
+        tracker.get_at(1, 10, start + Duration::from_secs(59)),
+        Some(11)
+    );
+    assert_eq!(tracker.get_at(1, 10, start + Duration::from_secs(60)), None);
+
+    // Expired entries are dropped when the next reply is recorded
+    tracker.record_at(1, 20, 21, start + Duration::from_secs(61));
+    assert_eq!(tracker.len(), 1);
+}
+
+#[test]
+fn test_clones_share_entries() {
+    let tracker = ReplyTracker::new(Duration::from_secs(60));
+    let clone = tracker.clone();
+    let now = Instant::now();
+
+    clone.record_at(1, 10, 11, now);
+    assert_eq!(tracker.get_at(1, 10, now), Some(11));
+    assert!(!tracker.is_empty());
+}
@@ -0,0 +1,105 @@
+use pt_dict_bot::constants::{LANG_IT_EN, LANG_PT_EN};
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::saved_words_repository::SavedWordsRepository;
+use sea_orm::{Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+
+async fn setup_test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+
+    db
+}
+
+#[tokio::test]
+async fn test_save_and_list_words() {
+    let db = setup_test_db().await;
+    let repo = SavedWordsRepository::new(db);
+
+    repo.save_word("chat_1", 7, "casa", LANG_PT_EN)
+        .await
+        .expect("Failed to save word");
+    repo.save_word("chat_1", 7, "gatto", LANG_IT_EN)
+        .await
+        .expect("Failed to save word");
+    repo.save_word("chat_2", 7, "livro", LANG_PT_EN)
+        .await
+        .expect("Failed to save word");
+
+    let words = repo.get_saved_words("chat_1", 7).await.unwrap();
+    let words: Vec<_> = words
+        .iter()
+        .map(|w| (w.word.as_str(), w.lang_direction.as_str()))
+        .collect();
+    assert_eq!(words, vec![("casa", LANG_PT_EN), ("gatto", LANG_IT_EN)]);
+}
+
+#[tokio::test]
+async fn test_save_word_twice_is_noop() {
+    let db = setup_test_db().await;
+    let repo = SavedWordsRepository::new(db);
+
+    let first = repo
+        .save_word("chat_1", 7, "casa", LANG_PT_EN)
+        .await
+        .unwrap();
+    let second = repo
+        .save_word("chat_1", 7, "casa", LANG_PT_EN)
+        .await
+        .unwrap();
+
+    assert_eq!(first.id, second.id);
+    assert_eq!(repo.get_saved_words("chat_1", 7).await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_group_members_keep_their_own_words() {
+    let db = setup_test_db().await;
+    let repo = SavedWordsRepository::new(db);
+
+    repo.save_word("-100", 7, "casa", LANG_PT_EN).await.unwrap();
+    repo.save_word("-100", 8, "casa", LANG_PT_EN).await.unwrap();
+    repo.save_word("-100", 8, "livro", LANG_PT_EN)
+        .await
+        .unwrap();
+
+    let words = |user_id| {
+        let repo = repo.clone();
+        async move {
+            repo.get_saved_words("-100", user_id)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|w| w.word)
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(words(7).await, vec!["casa"]);
+    assert_eq!(words(8).await, vec!["casa", "livro"]);
+    assert!(words(9).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_delete_saved_words() {
+    let db = setup_test_db().await;
+    let repo = SavedWordsRepository::new(db);
+
+    repo.save_word("chat_1", 7, "casa", LANG_PT_EN)
+        .await
+        .unwrap();
+    repo.save_word("chat_1", 7, "livro", LANG_PT_EN)
+        .await
+        .unwrap();
+    repo.save_word("chat_2", 7, "casa", LANG_PT_EN)
+        .await
+        .unwrap();
+
+    assert_eq!(repo.delete_saved_words("chat_1").await.unwrap(), 2);
+    assert!(repo.get_saved_words("chat_1", 7).await.unwrap().is_empty());
+    assert_eq!(repo.get_saved_words("chat_2", 7).await.unwrap().len(), 1);
+}
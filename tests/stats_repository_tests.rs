@@ -0,0 +1,47 @@
+use chrono::NaiveDate;
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::stats_repository::StatsRepository;
+use sea_orm::{Database, DatabaseConnection};
+use sea_orm_migration::MigratorTrait;
+
+async fn setup_test_db() -> DatabaseConnection {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+
+    db
+}
+
+#[tokio::test]
+async fn test_record_lookups_per_day() {
+    let repo = StatsRepository::new(setup_test_db().await);
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 20).unwrap();
+    let tuesday = NaiveDate::from_ymd_opt(2025, 10, 21).unwrap();
+
+    repo.record_lookup_on(monday, true).await.unwrap();
+    repo.record_lookup_on(monday, false).await.unwrap();
+    repo.record_lookup_on(monday, true).await.unwrap();
+    repo.record_lookup_on(tuesday, false).await.unwrap();
+
+    let day = repo.get_day(monday).await.unwrap();
+    assert_eq!((day.lookups, day.cache_hits), (3, 2));
+    let day = repo.get_day(tuesday).await.unwrap();
+    assert_eq!((day.lookups, day.cache_hits), (1, 0));
+
+    assert_eq!(repo.get_totals().await.unwrap(), (4, 2));
+}
+
+#[tokio::test]
+async fn test_get_day_without_lookups() {
+    let repo = StatsRepository::new(setup_test_db().await);
+    let day = repo
+        .get_day(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap())
+        .await
+        .unwrap();
+    assert_eq!((day.lookups, day.cache_hits), (0, 0));
+    assert_eq!(repo.get_totals().await.unwrap(), (0, 0));
+}
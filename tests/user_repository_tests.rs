@@ -1,4 +1,7 @@
-use pt_dict_bot::constants::LANG_PT_EN;
+use pt_dict_bot::constants::{
+    DEFAULT_LANG_DIRECTION, LANG_EN_IT, LANG_EN_PT, LANG_IT_EN, LANG_PT_EN,
+};
+use pt_dict_bot::group_chat::GroupMode;
 use pt_dict_bot::migration::Migrator;
 use pt_dict_bot::user_repository::UserRepository;
 use sea_orm::{Database, DatabaseConnection};
@@ -30,7 +33,7 @@ async fn test_create_and_get_user() {
         .expect("Failed to create user");
 
     assert_eq!(user.chat_id, "123");
-    assert_eq!(user.translation_direction, LANG_PT_EN);
+    assert_eq!(user.translation_direction.as_deref(), Some(LANG_PT_EN));
     assert_eq!(user.user_id, Some(456789));
     assert_eq!(user.username, Some("johndoe".to_string()));
 
@@ -42,7 +45,7 @@ async fn test_create_and_get_user() {
         .expect("User should exist");
 
     assert_eq!(retrieved.chat_id, "123");
-    assert_eq!(retrieved.translation_direction, LANG_PT_EN);
+    assert_eq!(retrieved.translation_direction.as_deref(), Some(LANG_PT_EN));
 }
 
 #[tokio::test]
@@ -61,7 +64,7 @@ async fn test_update_translation_direction() {
         .await
         .expect("Failed to update direction");
 
-    assert_eq!(updated.translation_direction, "iten");
+    assert_eq!(updated.translation_direction.as_deref(), Some("iten"));
 }
 
 #[tokio::test]
@@ -76,3 +79,250 @@ async fn test_get_nonexistent_user() {
 
     assert!(result.is_none());
 }
+
+#[tokio::test]
+async fn test_update_auto_detect() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    let user = repo
+        .create_or_update_user("789", LANG_PT_EN, None, None)
+        .await
+        .expect("Failed to create user");
+    assert!(!user.auto_detect); // Off by default
+
+    let updated = repo
+        .update_auto_detect("789", true)
+        .await
+        .expect("Failed to update auto-detect");
+    assert!(updated.auto_detect);
+    assert_eq!(updated.translation_direction.as_deref(), Some(LANG_PT_EN)); // Preserved
+
+    assert!(repo.update_auto_detect("missing", true).await.is_err());
+}
+
+#[tokio::test]
+async fn test_update_variant_preference() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    let user = repo
+        .create_or_update_user("321", LANG_PT_EN, None, None)
+        .await
+        .expect("Failed to create user");
+    assert_eq!(user.preferred_variant, None); // No preference by default
+    assert!(!user.hide_other_variant);
+
+    let updated = repo
+        .update_variant_preference("321", Some("br"), true)
+        .await
+        .expect("Failed to update variant preference");
+    assert_eq!(updated.preferred_variant.as_deref(), Some("br"));
+    assert!(updated.hide_other_variant);
+
+    let cleared = repo
+        .update_variant_preference("321", None, false)
+        .await
+        .expect("Failed to clear variant preference");
+    assert_eq!(cleared.preferred_variant, None);
+    assert!(!cleared.hide_other_variant);
+}
+
+#[tokio::test]
+async fn test_update_group_mode() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    let group = repo
+        .create_or_update_user("-100123", LANG_PT_EN, None, None)
+        .await
+        .expect("Failed to create group");
+    assert_eq!(group.group_mode, "mention"); // Mentions only by default
+
+    let updated = repo
+        .update_group_mode("-100123", GroupMode::All)
+        .await
+        .expect("Failed to update group mode");
+    assert_eq!(
+        GroupMode::from_code(&updated.group_mode),
+        Some(GroupMode::All)
+    );
+
+    assert!(
+        repo.update_group_mode("missing", GroupMode::Reply)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_update_locale() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    let chat = repo
+        .create_or_update_user("123", LANG_PT_EN, None, None)
+        .await
+        .expect("Failed to create chat");
+    assert_eq!(chat.locale, None); // Follows the Telegram language by default
+
+    let updated = repo
+        .update_locale("123", Some("it"))
+        .await
+        .expect("Failed to update locale");
+    assert_eq!(updated.locale.as_deref(), Some("it"));
+
+    let reset = repo
+        .update_locale("123", None)
+        .await
+        .expect("Failed to reset locale");
+    assert_eq!(reset.locale, None);
+
+    assert!(repo.update_locale("missing", Some("pt")).await.is_err());
+}
+
+#[tokio::test]
+async fn test_member_direction() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    assert_eq!(
+        repo.get_member_direction("-100123", 42).await.unwrap(),
+        None
+    );
+
+    repo.set_member_direction("-100123", 42, LANG_EN_PT)
+        .await
+        .expect("Failed to set member direction");
+    repo.set_member_direction("-100123", 42, LANG_IT_EN)
+        .await
+        .expect("Failed to update member direction");
+
+    assert_eq!(
+        repo.get_member_direction("-100123", 42).await.unwrap(),
+        Some(LANG_IT_EN.to_string())
+    );
+    // Overrides are per chat
+    assert_eq!(
+        repo.get_member_direction("-100456", 42).await.unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn test_resolve_direction_layers() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    // Nothing stored anywhere
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(42)).await.unwrap(),
+        DEFAULT_LANG_DIRECTION
+    );
+
+    // The user's global preference is their private chat's direction
+    repo.create_or_update_user("42", LANG_EN_IT, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(42)).await.unwrap(),
+        LANG_EN_IT
+    );
+
+    // The group's default wins over it
+    repo.create_or_update_user("-100123", LANG_EN_PT, None, None)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(42)).await.unwrap(),
+        LANG_EN_PT
+    );
+    assert_eq!(
+        repo.resolve_direction("-100123", None).await.unwrap(),
+        LANG_EN_PT
+    );
+
+    // And the user's own setting in the group wins over everything
+    repo.set_member_direction("-100123", 42, LANG_IT_EN)
+        .await
+        .unwrap();
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(42)).await.unwrap(),
+        LANG_IT_EN
+    );
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(7)).await.unwrap(),
+        LANG_EN_PT
+    );
+}
+
+#[tokio::test]
+async fn test_group_settings_keep_members_directions() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    repo.create_or_update_user("42", LANG_EN_IT, None, None)
+        .await
+        .unwrap();
+
+    // Setting /variant in the group creates its row without a direction
+    let chat = repo.create_chat("-100123", Some(42), None).await.unwrap();
+    assert_eq!(chat.translation_direction, None);
+    repo.update_variant_preference("-100123", Some("br"), false)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(42)).await.unwrap(),
+        LANG_EN_IT
+    );
+    assert_eq!(
+        repo.resolve_direction("-100123", Some(7)).await.unwrap(),
+        DEFAULT_LANG_DIRECTION
+    );
+}
+
+#[tokio::test]
+async fn test_count_users_and_chats() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    // One user in a private chat and a group, plus a chat without a user
+    repo.create_or_update_user("1", LANG_PT_EN, Some(10), None)
+        .await
+        .unwrap();
+    repo.create_or_update_user("-100", LANG_PT_EN, Some(10), None)
+        .await
+        .unwrap();
+    repo.create_or_update_user("2", LANG_PT_EN, None, None)
+        .await
+        .unwrap();
+
+    assert_eq!(repo.count_chats().await.unwrap(), 3);
+    assert_eq!(repo.count_users().await.unwrap(), 1);
+
+    let mut chat_ids = repo.get_chat_ids().await.unwrap();
+    chat_ids.sort();
+    assert_eq!(chat_ids, vec!["-100", "1", "2"]);
+}
+
+#[tokio::test]
+async fn test_list_and_delete_users() {
+    let db = setup_test_db().await;
+    let repo = UserRepository::new(db);
+
+    repo.create_or_update_user("1", LANG_PT_EN, None, None)
+        .await
+        .unwrap();
+    repo.create_or_update_user("2", LANG_PT_EN, None, None)
+        .await
+        .unwrap();
+    assert_eq!(repo.list_users().await.unwrap().len(), 2);
+
+    assert!(repo.delete_user("1").await.unwrap());
+    assert!(!repo.delete_user("1").await.unwrap());
+
+    let remaining = repo.list_users().await.unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].chat_id, "2");
+}
@@ -0,0 +1,66 @@
+use pt_dict_bot::constants::{LANG_EN_PT, LANG_IT_EN};
+use pt_dict_bot::variants::{Variant, VariantPreference, apply_preference, parse_labels};
+
+const BUS: &str = "<b>bus</b> (vehicle) ⮕ ônibus (BR)\n\
+                   <b>bus</b> (vehicle) ⮕ autocarro (PT)\n\
+                   <b>coach</b> (long-distance bus) ⮕ ônibus\n";
+
+fn prefer(variant: Variant, hide_others: bool) -> VariantPreference {
+    VariantPreference {
+        preferred: Some(variant),
+        hide_others,
+    }
+}
+
+#[test]
+fn test_parse_labels() {
+    assert_eq!(parse_labels("ônibus (BR)"), vec![Variant::Brazilian]);
+    assert_eq!(
+        parse_labels("(pt) comboio (PT, BR)"),
+        vec![Variant::European, Variant::Brazilian]
+    );
+    assert!(parse_labels("casa (lar, habitação)").is_empty());
+    assert!(parse_labels("unclosed (BR").is_empty());
+}
+
+#[test]
+fn test_apply_preference_highlights() {
+    let result = apply_preference(BUS, LANG_EN_PT, prefer(Variant::European, false));
+
+    assert_eq!(
+        result,
+        "<i><b>bus</b> (vehicle) ⮕ ônibus (BR)</i>\n\
+         🇵🇹 <b>bus</b> (vehicle) ⮕ autocarro (PT)\n\
+         <b>coach</b> (long-distance bus) ⮕ ônibus\n"
+    );
+}
+
+#[test]
+fn test_apply_preference_hides_other_variant() {
+    let result = apply_preference(BUS, LANG_EN_PT, prefer(Variant::Brazilian, true));
+
+    assert_eq!(
+        result,
+        "🇧🇷 <b>bus</b> (vehicle) ⮕ ônibus (BR)\n\
+         <b>coach</b> (long-distance bus) ⮕ ônibus\n"
+    );
+
+    // Nothing is hidden when every row is for the other variant
+    let only_pt = "<b>bus</b> ⮕ autocarro (PT)\n";
+    assert_eq!(
+        apply_preference(only_pt, LANG_EN_PT, prefer(Variant::Brazilian, true)),
+        only_pt
+    );
+}
+
+#[test]
+fn test_apply_preference_without_preference_or_portuguese() {
+    assert_eq!(
+        apply_preference(BUS, LANG_EN_PT, VariantPreference::default()),
+        BUS
+    );
+    assert_eq!(
+        apply_preference(BUS, LANG_IT_EN, prefer(Variant::Brazilian, true)),
+        BUS
+    );
+}
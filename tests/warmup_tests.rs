@@ -0,0 +1,73 @@
+use pt_dict_bot::cache_repository::CacheRepository;
+use pt_dict_bot::constants::LANG_PT_EN;
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::warmup::{
+    WarmupStatus, WarmupSummary, parse_word_list, read_checkpoint, warm_word, write_checkpoint,
+};
+use sea_orm::Database;
+use sea_orm_migration::MigratorTrait;
+
+#[test]
+fn test_parse_word_list() {
+    let list = "# Portuguese top words\n\
+                de 120345\n\
+                2\tCasa\t9876\n\
+                \n\
+                de repente 40\n\
+                casa 12\n\
+                1234\n";
+    assert_eq!(parse_word_list(list), vec!["de", "casa", "de repente"]);
+}
+
+#[test]
+fn test_checkpoint_round_trip() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("words.txt.pten.progress");
+
+    assert_eq!(read_checkpoint(&path), 0);
+    write_checkpoint(&path, 42).unwrap();
+    assert_eq!(read_checkpoint(&path), 42);
+}
+
+#[test]
+fn test_summary_counts_statuses() {
+    let mut summary = WarmupSummary::default();
+    for status in [
+        WarmupStatus::Cached,
+        WarmupStatus::Fetched,
+        WarmupStatus::Fetched,
+        WarmupStatus::NotFound,
+        WarmupStatus::Failed("timeout".to_string()),
+    ] {
+        summary.record(&status);
+    }
+    assert_eq!(
+        summary,
+        WarmupSummary {
+            cached: 1,
+            fetched: 2,
+            not_found: 1,
+            failed: 1,
+        }
+    );
+    assert_eq!(
+        WarmupStatus::Failed("timeout".to_string()).to_string(),
+        "failed (timeout)"
+    );
+}
+
+#[tokio::test]
+async fn test_warm_word_skips_cached_words() {
+    let db = Database::connect("sqlite::memory:").await.unwrap();
+    Migrator::up(&db, None).await.unwrap();
+    let cache_repo = CacheRepository::new(db);
+    cache_repo
+        .insert_html("casa", LANG_PT_EN, "<table></table>")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        warm_word(&cache_repo, "casa", LANG_PT_EN).await,
+        WarmupStatus::Cached
+    );
+}
@@ -0,0 +1,155 @@
+use std::io::Cursor;
+
+use pt_dict_bot::constants::{LANG_EN_PT, LANG_PT_EN};
+use pt_dict_bot::migration::Migrator;
+use pt_dict_bot::wiktionary::{
+    WiktionaryMode, WiktionaryProvider, format_entries, import_file, import_kaikki,
+    parse_kaikki_line, translation_entries,
+};
+use pt_dict_bot::wiktionary_repository::WiktionaryRepository;
+use sea_orm::Database;
+use sea_orm_migration::MigratorTrait;
+
+const CASA: &str = r#"{"word": "casa", "lang": "Portuguese", "lang_code": "pt", "pos": "noun", "etymology_text": "From Latin casa.", "sounds": [{"ipa": "/ˈka.zɐ/"}, {"ipa": "/ˈka.zɐ/"}, {"audio": "x.ogg"}], "forms": [{"form": "casas", "tags": ["plural"]}, {"form": "pt-noun", "tags": ["inflection-template"]}], "senses": [{"glosses": ["house"], "examples": [{"text": "A casa é azul.", "english": "The house is blue."}]}, {"glosses": ["home"], "tags": ["figuratively"]}, {"tags": ["form-of"]}]}"#;
+const MESA: &str =
+    r#"{"word": "mesa", "lang_code": "pt", "pos": "noun", "senses": [{"glosses": ["table"]}]}"#;
+const HOUSE: &str = r#"{"word": "house", "lang_code": "en", "pos": "noun", "senses": [{"glosses": ["A building."]}]}"#;
+
+async fn setup_repo() -> WiktionaryRepository {
+    let db = Database::connect("sqlite::memory:")
+        .await
+        .expect("Failed to connect to test database");
+    Migrator::up(&db, None)
+        .await
+        .expect("Failed to run migrations");
+    WiktionaryRepository::new(db)
+}
+
+fn pt() -> Vec<String> {
+    vec!["pt".to_string()]
+}
+
+#[test]
+fn test_parse_kaikki_line() {
+    let entry = parse_kaikki_line(CASA).unwrap().unwrap();
+
+    assert_eq!(entry.word, "casa");
+    assert_eq!(entry.lang_code, "pt");
+    assert_eq!(entry.pos, "noun");
+    assert_eq!(entry.etymology_number, 0);
+    assert_eq!(entry.etymology.as_deref(), Some("From Latin casa."));
+    assert_eq!(entry.ipa, vec!["/ˈka.zɐ/"]);
+    assert_eq!(entry.forms.len(), 1);
+    assert_eq!(entry.forms[0].form, "casas");
+    // The sense without glosses is dropped
+    assert_eq!(entry.senses.len(), 2);
+    assert_eq!(entry.senses[0].examples[0].text, "A casa é azul.");
+    assert_eq!(
+        entry.senses[0].examples[0].translation.as_deref(),
+        Some("The house is blue.")
+    );
+
+    assert!(
+        parse_kaikki_line(r#"{"word": "x", "lang_code": "pt", "senses": []}"#)
+            .unwrap()
+            .is_none()
+    );
+    assert!(parse_kaikki_line("not json").is_err());
+}
+
+#[test]
+fn test_format_entries() {
+    let entries = vec![parse_kaikki_line(CASA).unwrap().unwrap()];
+
+    assert_eq!(
+        format_entries(&entries),
+        "📗 <i>Wiktionary</i>\n\
+         \n<b>casa</b> /ˈka.zɐ/\n\
+         <i>noun</i> · casas (plural)\n\
+         1. house\n    <i>A casa é azul.</i> — The house is blue.\n\
+         2. <i>(figuratively)</i> home\n\
+         <i>Etymology:</i> From Latin casa.\n"
+    );
+
+    let rows = translation_entries(&entries);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].translation, "house");
+    assert_eq!(rows[1].sense, "figuratively");
+}
+
+#[tokio::test]
+async fn test_import_is_incremental() {
+    let repo = setup_repo().await;
+
+    let dump = format!("{}\n{}\n{}\nnot json\n", CASA, MESA, HOUSE);
+    let summary = import_kaikki(&repo, Cursor::new(dump), &pt())
+        .await
+        .unwrap();
+    assert_eq!(summary.added, 2);
+    assert_eq!(summary.skipped, 2);
+    assert_eq!(repo.count_entries().await.unwrap(), 2);
+
+    // A newer dump: casa unchanged, mesa changed, a new word, nothing else
+    let mesa = MESA.replace("table", "desk");
+    let livro =
+        r#"{"word": "livro", "lang_code": "pt", "pos": "noun", "senses": [{"glosses": ["book"]}]}"#;
+    let dump = format!("{}\n{}\n{}\n", CASA, mesa, livro);
+    let summary = import_kaikki(&repo, Cursor::new(dump), &pt())
+        .await
+        .unwrap();
+    assert_eq!(summary.unchanged, 1);
+    assert_eq!(summary.updated, 1);
+    assert_eq!(summary.added, 1);
+    assert_eq!(summary.removed, 0);
+
+    let entries = repo.find("pt", "mesa").await.unwrap();
+    assert_eq!(entries[0].senses[0].glosses, vec!["desk"]);
+
+    // Words missing from a dump of the same language are removed
+    let summary = import_kaikki(&repo, Cursor::new(format!("{}\n", CASA)), &pt())
+        .await
+        .unwrap();
+    assert_eq!(summary.removed, 2);
+    assert_eq!(repo.count_entries().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_import_file_skips_known_dump() {
+    let repo = setup_repo().await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("kaikki.jsonl");
+    std::fs::write(&path, format!("{}\n{}\n", CASA, MESA)).unwrap();
+
+    let summary = import_file(&repo, &path, &pt(), false).await.unwrap();
+    assert_eq!(summary.unwrap().added, 2);
+    assert!(
+        import_file(&repo, &path, &pt(), false)
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let forced = import_file(&repo, &path, &pt(), true).await.unwrap();
+    assert_eq!(forced.unwrap().unchanged, 2);
+}
+
+#[tokio::test]
+async fn test_provider_serves_directions_into_english() {
+    let repo = setup_repo().await;
+    import_kaikki(&repo, Cursor::new(CASA), &pt())
+        .await
+        .unwrap();
+    let provider = WiktionaryProvider::new(repo, WiktionaryMode::Fallback);
+
+    let entries = provider.lookup("Casa", LANG_PT_EN).await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].senses[0].glosses, vec!["house"]);
+
+    assert!(
+        provider
+            .lookup("casa", LANG_EN_PT)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}